@@ -0,0 +1,117 @@
+use autolog::server::router::{ServerConfig, handle_request};
+use autolog::server::timesheet_store::TimesheetStore;
+use chrono::Utc;
+use clap::{App, Arg};
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use tiny_http::{Header, Response, Server};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+const DEFAULT_API_ENDPOINT: &str = "api/timesheet";
+const DEFAULT_EXPIRE_TIME_SECONDS: i64 = 86400;
+const DEFAULT_DB_FILE_NAME: &str = "autolog-server.db";
+
+fn get_header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("Header is not valid")
+}
+
+fn main() {
+    dotenv::dotenv().ok();
+
+    let matches = App::new("autolog-server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about(
+            "Self-hosted server for sharing autolog timesheets. Set API_ROUTE_BEARER_KEY \n\
+            to the key the cli sends, and optionally API_ENDPOINT and EXPIRE_TIME_SECONDS.",
+        )
+        .arg(
+            Arg::with_name("address")
+                .short("a")
+                .long("address")
+                .value_name("host:port")
+                .default_value(DEFAULT_ADDRESS)
+                .help("Address to listen on"),
+        )
+        .arg(
+            Arg::with_name("db")
+                .long("db")
+                .value_name("path")
+                .default_value(DEFAULT_DB_FILE_NAME)
+                .help("Path to the sqlite database that stores shared timesheets"),
+        )
+        .get_matches();
+
+    let api_key = env::var("API_ROUTE_BEARER_KEY").unwrap_or_else(|_| {
+        eprintln!("API_ROUTE_BEARER_KEY must be set to accept timesheets from autolog");
+        process::exit(exitcode::CONFIG);
+    });
+
+    let config = ServerConfig {
+        api_endpoint: env::var("API_ENDPOINT").unwrap_or_else(|_| DEFAULT_API_ENDPOINT.to_string()),
+        api_key,
+    };
+
+    let expire_time_seconds: i64 = env::var("EXPIRE_TIME_SECONDS")
+        .ok()
+        .map(|seconds| {
+            seconds.parse().unwrap_or_else(|_| {
+                eprintln!("EXPIRE_TIME_SECONDS can't be parsed to a number of seconds");
+                process::exit(exitcode::CONFIG);
+            })
+        })
+        .unwrap_or(DEFAULT_EXPIRE_TIME_SECONDS);
+
+    let db_path = PathBuf::from(matches.value_of("db").unwrap());
+    let store = TimesheetStore::open(&db_path, expire_time_seconds).unwrap_or_else(|err| {
+        eprintln!("Error opening database: {}", err);
+        process::exit(exitcode::CANTCREAT);
+    });
+
+    let address = matches.value_of("address").unwrap();
+    let server = Server::http(address).unwrap_or_else(|err| {
+        eprintln!("Error listening on {}: {}", address, err);
+        process::exit(exitcode::UNAVAILABLE);
+    });
+
+    println!(
+        "autolog-server listening on http://{} (api route /{})",
+        address,
+        config.api_endpoint.trim_matches('/')
+    );
+
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        if let Err(err) = request.as_reader().read_to_string(&mut body) {
+            eprintln!("Error reading request body: {}", err);
+            continue;
+        }
+
+        let authorization = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .map(|header| header.value.as_str().to_string());
+
+        let response = handle_request(
+            &store,
+            &config,
+            request.method().as_str(),
+            request.url(),
+            authorization.as_deref(),
+            &body,
+            Utc::now(),
+        );
+
+        let mut http_response = Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(get_header("Content-Type", response.content_type));
+        if let Some(location) = response.location.as_ref() {
+            http_response = http_response.with_header(get_header("Location", location));
+        }
+
+        if let Err(err) = request.respond(http_response) {
+            eprintln!("Error sending response: {}", err);
+        }
+    }
+}
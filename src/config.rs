@@ -1,12 +1,25 @@
 use crate::data::client_repositories::ClientRepositories;
-use crate::data::repository::Repository;
+use crate::data::locked_month::LockedMonth;
+use crate::data::manual_entry::ManualEntry;
+use crate::data::repository::{RESET_REPOSITORY, Repository};
+use crate::data::timesheet_history::{self, ChangedDays};
 use crate::interface::help_prompt::ConfigurationDoc;
 use crate::interface::help_prompt::HelpPrompt;
 use crate::interface::help_prompt::Onboarding;
+use crate::interface::help_prompt::PromptAnswers;
+use crate::interface::review::{self, ReviewGrid};
+use crate::utils::date::date_parser::{
+    check_for_valid_day, check_for_valid_month, check_for_valid_year,
+};
 use crate::utils::db::db_reader;
 use crate::utils::exit_process;
+use crate::utils::export::export_builder;
+use crate::utils::is_non_interactive;
 use crate::utils::link::link_builder;
+use crate::utils::pdf::pdf_builder;
+use crate::utils::settings::settings_file::{self, Settings, ShareConfig};
 use ansi_term::Style;
+use chrono::NaiveDate;
 use semver::Version;
 use std::process;
 
@@ -59,8 +72,8 @@ impl Config {
     }
 
     /// Find and update client if sheet exists, otherwise write a new one
-    fn write_to_db(config_doc: &ConfigurationDoc) {
-        match db_reader::save_config_doc_to_db(config_doc) {
+    fn write_to_db(config_doc: &ConfigurationDoc, changed_days: &ChangedDays) {
+        match db_reader::save_config_doc_to_db(config_doc, changed_days) {
             Ok(_) => {}
             Err(err) => {
                 eprintln!("Error writing to database: {}", err);
@@ -78,27 +91,6 @@ impl Config {
         client_name: Option<&String>,
     ) -> Result<(Option<&'a Repository>, Option<&'a ClientRepositories>), Box<dyn std::error::Error>>
     {
-        let namespace: Option<String> = match repo_namespace {
-            Some(ns) => Some(ns.to_owned()),
-            None => {
-                // Only try to get namespace from repository if no namespace was provided
-                if let Some(path) = repo_path {
-                    let mut temp_repository = Repository {
-                        repo_path: Some(path.to_owned()),
-                        ..Default::default()
-                    };
-
-                    temp_repository
-                        .find_git_path_from_directory_from()?
-                        .find_namespace_from_git_path()?;
-
-                    temp_repository.namespace
-                } else {
-                    None
-                }
-            }
-        };
-
         let mut option: (Option<&Repository>, Option<&ClientRepositories>) =
             (Option::None, Option::None);
         // if client_name is passed, find the client from the config
@@ -124,6 +116,29 @@ impl Config {
                 std::process::exit(exitcode::CANTCREAT);
             }
         } else {
+            // the namespace is only needed when the client isn't passed, so that
+            // clients can be found from outside of a git repository
+            let namespace: Option<String> = match repo_namespace {
+                Some(ns) => Some(ns.to_owned()),
+                None => {
+                    // Only try to get namespace from repository if no namespace was provided
+                    if let Some(path) = repo_path {
+                        let mut temp_repository = Repository {
+                            repo_path: Some(path.to_owned()),
+                            ..Default::default()
+                        };
+
+                        temp_repository
+                            .find_git_path_from_directory_from()?
+                            .find_namespace_from_git_path()?;
+
+                        temp_repository.namespace
+                    } else {
+                        None
+                    }
+                }
+            };
+
             // otherwise check whether any clients contain the namespace
             // and return the repository and the client
             for client in deserialized_config.iter() {
@@ -151,6 +166,131 @@ impl Config {
         Ok(option)
     }
 
+    /// Find the client that time is logged against. Outside a repository with only one
+    /// client, that client is used
+    fn find_client_id_for_entries(
+        self,
+        config_doc: &mut ConfigurationDoc,
+        client_name: Option<&String>,
+    ) -> Option<String> {
+        if client_name.is_none() && config_doc.len() == 1 {
+            return config_doc[0].get_client_id();
+        }
+
+        let current_repo_path = db_reader::get_canonical_path(".");
+        let (_found_repo, found_client_repo) = self
+            .find_client_or_repo_in_buffer(
+                config_doc,
+                Option::from(&current_repo_path),
+                Option::None,
+                client_name,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Error trying to read from database: {}", err);
+                std::process::exit(exitcode::DATAERR);
+            });
+
+        found_client_repo.and_then(|client| client.get_client_id())
+    }
+
+    /// Lock or unlock a month of a client's timesheet
+    fn set_month_locked(self, options: Vec<Option<String>>, prompt: &mut HelpPrompt, locked: bool) {
+        let mut config_doc = self.find_or_create_db(prompt);
+
+        if !config_doc.is_empty() {
+            let client_id = self.find_client_id_for_entries(&mut config_doc, options[0].as_ref());
+            let client = config_doc
+                .iter_mut()
+                .find(|client| client_id.is_some() && client.get_client_id() == client_id);
+
+            if let Some(client) = client {
+                let month_year_string =
+                    link_builder::get_string_month_year(&options[1], &options[2]).unwrap_or_else(
+                        |err| {
+                            eprintln!("Error parsing month or year: {}", err);
+                            process::exit(exitcode::DATAERR);
+                        },
+                    );
+                // safe to parse as they've been checked above
+                let month: u32 = options[1].as_ref().unwrap().parse().unwrap();
+                let year: i32 = options[2].as_ref().unwrap().parse().unwrap();
+
+                let client_name = client.get_client_name().unwrap_or_default();
+                let was_locked = client.is_month_locked(year, month);
+                match locked {
+                    true => client.lock_month(year, month),
+                    false => client.unlock_month(year, month),
+                };
+
+                Config::write_to_db(&config_doc, &ChangedDays::default());
+                match locked {
+                    true => {
+                        HelpPrompt::show_month_locked(&client_name, &month_year_string, was_locked)
+                    }
+                    false => HelpPrompt::show_month_unlocked(
+                        &client_name,
+                        &month_year_string,
+                        was_locked,
+                    ),
+                }
+            } else {
+                crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
+            }
+        }
+    }
+
+    /// Preview the hours of the reset days against the ones worked out from git again,
+    /// leaving without saving unless the reset is confirmed
+    fn confirm_reset_days(
+        prompt: &mut HelpPrompt,
+        edited_repository: &Repository,
+        reset_days: Vec<NaiveDate>,
+    ) {
+        let reset_repository = prompt
+            .client_repositories()
+            .repositories
+            .iter()
+            .flatten()
+            .find(|repository| repository.id == edited_repository.id);
+
+        let changes = reset_days
+            .into_iter()
+            .map(|date| {
+                (
+                    date,
+                    edited_repository.get_hours_on_day(&date),
+                    reset_repository.and_then(|repository| repository.get_hours_on_day(&date)),
+                )
+            })
+            .collect();
+
+        let namespace = edited_repository.namespace.as_deref().unwrap_or_default();
+        match HelpPrompt::confirm_reset_days(namespace, changes) {
+            Ok(true) => {}
+            Ok(false) => crate::utils::exit_process(),
+            Err(err) => {
+                eprintln!("Error confirming reset: {}", err);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+
+    fn parse_manual_entry(
+        options: &[Option<String>],
+    ) -> Result<ManualEntry, Box<dyn std::error::Error>> {
+        let month = check_for_valid_month(&options[4])?;
+        let year: i32 = check_for_valid_year(&options[5])?.parse()?;
+        let day: u32 = check_for_valid_day(&options[3], month, year)?.parse()?;
+        let date = NaiveDate::from_ymd_opt(year, month, day).ok_or("Not a real date")?;
+
+        ManualEntry::new(
+            date,
+            options[2].as_deref().unwrap_or_default(),
+            options[1].as_deref(),
+            options[6].as_deref(),
+        )
+    }
+
     async fn check_for_homebrew_update(self) -> Result<(), Box<dyn std::error::Error>> {
         // Get the current version from Cargo.toml
         let current_version_str = env!("CARGO_PKG_VERSION");
@@ -200,7 +340,7 @@ impl Config {
                     Ok(latest_version) => {
                         // Compare versions semantically
                         if latest_version > current_version {
-                            println!(
+                            eprintln!(
                                 "A new version of autolog is available: {}. You are using {}. Please update using `brew upgrade autolog`.",
                                 version_str, current_version_str
                             );
@@ -223,7 +363,7 @@ impl Config {
                     Ok(latest_version) => {
                         // Compare versions semantically
                         if latest_version > current_version {
-                            println!(
+                            eprintln!(
                                 "A new version of autolog is available: {}. You are using {}. Please update using `brew upgrade autolog`.",
                                 cached_version, current_version_str
                             );
@@ -248,7 +388,7 @@ impl Config {
             Err(e) => {
                 // Fall back to displaying the current version if homebrew check fails
                 eprintln!("Failed to check for updates: {}", e);
-                println!(
+                eprintln!(
                     "You are using autolog version {}",
                     env!("CARGO_PKG_VERSION")
                 );
@@ -257,6 +397,61 @@ impl Config {
         }
     }
 
+    /// Replace the client in the config document, or add it if it's a new client
+    fn set_client_in_config_doc(
+        config_doc: &mut ConfigurationDoc,
+        client_repositories: &ClientRepositories,
+    ) {
+        let client_id = client_repositories.get_client_id();
+
+        match config_doc
+            .iter_mut()
+            .find(|client| client.get_client_id() == client_id)
+        {
+            Some(client) => *client = client_repositories.clone(),
+            None => config_doc.push(client_repositories.clone()),
+        }
+    }
+
+    /// Add a project without a git repository. This doesn't onboard through a repository,
+    /// so it can be run from any directory
+    fn init_project(self, namespace: String, prompt: &mut HelpPrompt) {
+        let mut config_doc = db_reader::load_config_doc_from_db().unwrap_or_else(|err| {
+            eprintln!("Error trying to read from database: {}", err);
+            std::process::exit(exitcode::DATAERR);
+        });
+
+        let exists = config_doc
+            .iter()
+            .flat_map(|client| client.repositories.iter().flatten())
+            .any(|repo| {
+                repo.namespace
+                    .as_ref()
+                    .is_some_and(|x| x.to_lowercase() == namespace.to_lowercase())
+            });
+
+        if exists {
+            crate::interface::help_prompt::HelpPrompt::project_already_initialised(&namespace);
+            return;
+        }
+
+        prompt
+            .prompt_for_client_then_onboard_project(&config_doc, namespace)
+            .unwrap_or_else(|err| {
+                eprintln!("Error adding project to client: {}", err);
+                std::process::exit(exitcode::CANTCREAT);
+            });
+
+        let mut client_repositories = prompt.client_repositories().clone();
+        let mut repository = prompt.repository().clone();
+
+        Config::fetch_interaction_data(&mut client_repositories, &mut repository);
+        Config::set_client_in_config_doc(&mut config_doc, &client_repositories);
+
+        Config::write_to_db(&config_doc, &ChangedDays::default());
+        crate::interface::help_prompt::HelpPrompt::show_write_new_repo_success();
+    }
+
     fn find_or_create_db(self, prompt: &mut HelpPrompt) -> ConfigurationDoc {
         // Try to load existing config from the database
         match db_reader::load_config_doc_from_db() {
@@ -293,7 +488,7 @@ impl Config {
         Config::fetch_interaction_data(&mut client_repositories, &mut repository);
         // Save to database
         let new_config = vec![client_repositories];
-        match db_reader::save_config_doc_to_db(&new_config) {
+        match db_reader::save_config_doc_to_db(&new_config, &ChangedDays::default()) {
             Ok(_) => {
                 crate::interface::help_prompt::HelpPrompt::show_write_new_config_success();
                 new_config
@@ -318,6 +513,13 @@ impl Init for Config {
             eprintln!("Failed to check for updates: {}", e);
         }
 
+        prompt.set_answers(PromptAnswers::from_init_options(&options));
+
+        if let Some(namespace) = options.get(1).cloned().flatten() {
+            self.init_project(namespace, prompt);
+            return;
+        }
+
         // Load or create the database
         let mut config_doc = self.find_or_create_db(prompt);
 
@@ -352,26 +554,11 @@ impl Init for Config {
                 // Fetch interaction data
                 Config::fetch_interaction_data(&mut client_repositories, &mut repository);
 
-                // check if this is a new client
-                let client_id = client_repositories.get_client_id();
-                let mut client_exists = false;
-
-                // Update the client repository in the config document
-                for client in config_doc.iter_mut() {
-                    if client.get_client_id() == client_id {
-                        *client = client_repositories.clone();
-                        client_exists = true;
-                        break;
-                    }
-                }
-
-                // If this is a new client, add it to config_doc
-                if !client_exists {
-                    config_doc.push(client_repositories.clone());
-                }
+                // Update the client repository in the config document, or add a new client
+                Config::set_client_in_config_doc(&mut config_doc, &client_repositories);
 
                 // Write updated config back to database
-                Config::write_to_db(&config_doc);
+                Config::write_to_db(&config_doc, &ChangedDays::default());
                 crate::interface::help_prompt::HelpPrompt::show_write_new_repo_success();
             }
         }
@@ -407,21 +594,62 @@ impl Make for Config {
                 });
 
             Self::set_prompt_with_found_values(prompt, found_repo, found_client_repo);
+            prompt.set_answers(PromptAnswers::from_project_numbers(options[6].as_ref()));
 
             if found_client_repo.is_some() {
-                prompt.add_project_numbers().unwrap_or_else(|err| {
-                    eprintln!("Error parsing project number: {}", err);
-                    std::process::exit(exitcode::CANTCREAT);
-                });
+                let (month_option, year_option) = (options[1].clone(), options[2].clone());
 
-                // Generate autolog.dev link using existing config
-                link_builder::build_unique_uri(prompt.client_repositories(), options)
-                    .await
-                    .unwrap_or_else(|err| {
-                        eprintln!("Error building unique link: {}", err);
+                if options[3].is_some() {
+                    prompt.set_passed_project_numbers().unwrap_or_else(|err| {
+                        eprintln!("Error parsing project number: {}", err);
+                        std::process::exit(exitcode::CANTCREAT);
+                    });
+
+                    // Export locally using the project numbers stored against each repository
+                    export_builder::build_export(prompt.client_repositories(), options)
+                        .unwrap_or_else(|err| {
+                            eprintln!("Error exporting timesheet: {}", err);
+                            std::process::exit(exitcode::CANTCREAT);
+                        });
+                } else if options[5].is_some() {
+                    prompt.add_project_numbers().unwrap_or_else(|err| {
+                        eprintln!("Error parsing project number: {}", err);
                         std::process::exit(exitcode::CANTCREAT);
                     });
 
+                    // Render the pdf locally so nothing is uploaded
+                    pdf_builder::build_pdf(prompt.client_repositories(), options).unwrap_or_else(
+                        |err| {
+                            eprintln!("Error rendering pdf: {}", err);
+                            std::process::exit(exitcode::CANTCREAT);
+                        },
+                    );
+                } else {
+                    prompt.add_project_numbers().unwrap_or_else(|err| {
+                        eprintln!("Error parsing project number: {}", err);
+                        std::process::exit(exitcode::CANTCREAT);
+                    });
+
+                    // Generate autolog.dev link using existing config
+                    link_builder::build_unique_uri(prompt.client_repositories(), options)
+                        .await
+                        .unwrap_or_else(|err| {
+                            eprintln!("Error building unique link: {}", err);
+                            std::process::exit(exitcode::CANTCREAT);
+                        });
+                }
+
+                // The month has been shared or exported, so lock it so that history fetched
+                // later can't change it. Safe to parse as the builders have checked them
+                let month: u32 = month_option.as_ref().unwrap().parse().unwrap();
+                let year: i32 = year_option.as_ref().unwrap().parse().unwrap();
+                if prompt.client_repositories().lock_month(year, month) {
+                    HelpPrompt::show_locked_after_make(
+                        &link_builder::get_string_month_year(&month_option, &year_option)
+                            .unwrap_or_default(),
+                    );
+                }
+
                 // Update the client repository in the config document
                 let client_id = prompt.client_repositories().get_client_id();
                 for client in config_doc.iter_mut() {
@@ -432,7 +660,7 @@ impl Make for Config {
                 }
 
                 // Write to database
-                Config::write_to_db(&config_doc);
+                Config::write_to_db(&config_doc, &ChangedDays::default());
             } else {
                 crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
             }
@@ -472,14 +700,54 @@ impl Edit for Config {
             // Set the prompt with found values to prepare for editing
             Self::set_prompt_with_found_values(prompt, found_repo, found_client_repo);
             if found_client_repo.is_some() {
-                // Update the hours in the repository's timesheet
-                prompt
-                    .repository()
-                    .update_hours_on_month_day_entry(&options)
-                    .unwrap_or_else(|err| {
-                        eprintln!("Error editing timesheet: {}", err);
-                        process::exit(exitcode::DATAERR);
-                    });
+                let reset_scope = options.get(10).cloned().flatten();
+                let is_resetting = reset_scope.is_some();
+                let edited_repository = prompt.repository().clone();
+                let locked_months = prompt
+                    .client_repositories()
+                    .locked_months
+                    .clone()
+                    .unwrap_or_default();
+
+                // Update the hours and description in the repository's timesheet, or forget the
+                // edits being reset. Nothing is saved unless every day in a range can be edited,
+                // and locked months can't be edited. Resetting a whole repository leaves them out
+                let repository = prompt.repository();
+                let days = match is_resetting {
+                    true => repository
+                        .find_days_to_reset(&options)
+                        .map(|mut days| {
+                            if reset_scope.as_deref() == Some(RESET_REPOSITORY) {
+                                days.retain(|date| {
+                                    !locked_months.iter().any(|month| month.contains(date))
+                                });
+                            }
+                            days
+                        })
+                        .and_then(|days| {
+                            LockedMonth::check_days_are_unlocked(&locked_months, &days)
+                                .map(|_| days)
+                        })
+                        .map(|days| repository.reset_edited_days(&days)),
+                    false => Repository::find_days_to_edit(&options).and_then(|days| {
+                        LockedMonth::check_days_are_unlocked(&locked_months, &days)?;
+                        match options.get(6).cloned().flatten() {
+                            Some(_) => repository.update_hours_on_date_range(&options),
+                            None => repository.update_hours_on_month_day_entry(&options),
+                        }?
+                        .update_description_on_month_day_entry(&options)?;
+                        Ok(days)
+                    }),
+                }
+                .unwrap_or_else(|err| {
+                    eprintln!("Error editing timesheet: {}", err);
+                    process::exit(exitcode::DATAERR);
+                });
+
+                if is_resetting && days.is_empty() {
+                    println!("\n\u{1F916} There aren't any edited days to reset.");
+                    crate::utils::exit_process();
+                }
 
                 // Clone repository to update the client repositories
                 let mut repository_clone = prompt.repository().clone();
@@ -491,6 +759,21 @@ impl Edit for Config {
                     .exec_generate_timesheets_from_git_history()
                     .compare_logs_and_set_timesheets();
 
+                // The history keeps the edited or reset days under the edit's source
+                let mut changed_days = ChangedDays::new(match is_resetting {
+                    true => timesheet_history::SOURCE_RESET,
+                    false => timesheet_history::SOURCE_EDIT,
+                });
+                if let Some(repository_id) = &edited_repository.id {
+                    for date in &days {
+                        changed_days.add(repository_id, *date);
+                    }
+                }
+
+                if is_resetting {
+                    Self::confirm_reset_days(prompt, &edited_repository, days);
+                }
+
                 // Find and update the matching client repository in the config document
                 let client_id = prompt.client_repositories().get_client_id();
                 let mut updated = false;
@@ -509,7 +792,7 @@ impl Edit for Config {
                 }
 
                 // Write the updated configuration back to the database
-                match db_reader::save_config_doc_to_db(&config_doc) {
+                match db_reader::save_config_doc_to_db(&config_doc, &changed_days) {
                     Ok(_) => {
                         crate::interface::help_prompt::HelpPrompt::show_edited_config_success();
                     }
@@ -579,7 +862,7 @@ impl Remove for Config {
                 }
 
                 // Write the updated configuration back to the database
-                match db_reader::save_config_doc_to_db(&config_doc) {
+                match db_reader::save_config_doc_to_db(&config_doc, &ChangedDays::default()) {
                     Ok(_) => {
                         println!("Successfully removed the requested item.");
                     }
@@ -627,8 +910,24 @@ impl Update for Config {
             // Set the prompt with found values to prepare for updating
             Self::set_prompt_with_found_values(prompt, found_repo, found_client_repo);
 
+            // the client is found by name, so the repository is found within it
+            if let (Some(namespace), Some(client)) = (options[1].as_ref(), found_client_repo) {
+                match client.find_repository_by_namespace(namespace) {
+                    Some(repository) => {
+                        prompt.repository().set_values_from_buffer(repository);
+                    }
+                    None => {
+                        eprintln!(
+                            "The client, or client + namespace combination you passed has not be found."
+                        );
+                        std::process::exit(exitcode::CANTCREAT);
+                    }
+                }
+            }
+
             if found_client_repo.is_some() {
-                // Prompt user for updated information
+                // Prompt user for updated information, unless it was passed as flags
+                prompt.set_answers(PromptAnswers::from_update_options(&options));
                 prompt.prompt_for_update(options).unwrap_or_else(|err| {
                     eprintln!("Update failed: {}", err);
                     std::process::exit(exitcode::DATAERR);
@@ -654,7 +953,7 @@ impl Update for Config {
                 }
 
                 // Write the updated configuration back to the database
-                match db_reader::save_config_doc_to_db(&config_doc) {
+                match db_reader::save_config_doc_to_db(&config_doc, &ChangedDays::default()) {
                     Ok(_) => {
                         crate::interface::help_prompt::HelpPrompt::show_updated_config_success();
                     }
@@ -695,11 +994,359 @@ impl List for Config {
     }
 }
 
+pub trait Log {
+    /// Log time spent on work that doesn't show up in git
+    fn log(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl Log for Config {
+    #[tokio::main]
+    async fn log(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        let mut config_doc = self.find_or_create_db(prompt);
+
+        if !config_doc.is_empty() {
+            let client_id = self.find_client_id_for_entries(&mut config_doc, options[0].as_ref());
+            let client = config_doc
+                .iter_mut()
+                .find(|client| client_id.is_some() && client.get_client_id() == client_id);
+
+            if let Some(client) = client {
+                let entry = Config::parse_manual_entry(&options).unwrap_or_else(|err| {
+                    eprintln!("Error logging time: {}", err);
+                    process::exit(exitcode::DATAERR);
+                });
+
+                let locked_months = client.locked_months.clone().unwrap_or_default();
+                LockedMonth::check_days_are_unlocked(&locked_months, &[entry.date]).unwrap_or_else(
+                    |err| {
+                        eprintln!("Error logging time: {}", err);
+                        process::exit(exitcode::DATAERR);
+                    },
+                );
+
+                client.add_manual_entry(entry.clone());
+                let client_name = client.get_client_name().unwrap_or_default();
+
+                Config::write_to_db(&config_doc, &ChangedDays::default());
+                HelpPrompt::show_manual_entry_added(&client_name, &entry);
+            } else {
+                crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
+            }
+        }
+    }
+}
+
+pub trait Entries {
+    /// List or remove the time logged against a client
+    fn entries(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl Entries for Config {
+    #[tokio::main]
+    async fn entries(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        let mut config_doc = self.find_or_create_db(prompt);
+
+        if !config_doc.is_empty() {
+            let client_id = self.find_client_id_for_entries(&mut config_doc, options[0].as_ref());
+            let client = config_doc
+                .iter_mut()
+                .find(|client| client_id.is_some() && client.get_client_id() == client_id);
+
+            if let Some(client) = client {
+                if let Some(id) = options[3].as_ref() {
+                    let locked_months = client.locked_months.clone().unwrap_or_default();
+                    match client.remove_manual_entry(id) {
+                        Some(entry) => {
+                            LockedMonth::check_days_are_unlocked(&locked_months, &[entry.date])
+                                .unwrap_or_else(|err| {
+                                    eprintln!("Error removing time: {}", err);
+                                    process::exit(exitcode::DATAERR);
+                                });
+                            Config::write_to_db(&config_doc, &ChangedDays::default());
+                            HelpPrompt::show_manual_entry_removed(&entry);
+                        }
+                        None => {
+                            eprintln!("No time has been logged with the id '{}'", id);
+                            process::exit(exitcode::DATAERR);
+                        }
+                    }
+                } else {
+                    let month_year_string =
+                        link_builder::get_string_month_year(&options[1], &options[2])
+                            .unwrap_or_else(|err| {
+                                eprintln!("Error parsing month or year: {}", err);
+                                process::exit(exitcode::DATAERR);
+                            });
+                    // safe to parse as they've been checked above
+                    let month: u32 = options[1].as_ref().unwrap().parse().unwrap();
+                    let year: i32 = options[2].as_ref().unwrap().parse().unwrap();
+
+                    HelpPrompt::show_manual_entries(
+                        &client.get_client_name().unwrap_or_default(),
+                        &month_year_string,
+                        client.get_manual_entries_for_month(year, month),
+                    );
+                }
+            } else {
+                crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
+            }
+        }
+    }
+}
+
+pub trait Review {
+    /// Review and edit a month of a client's timesheet in the terminal
+    fn review(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl Review for Config {
+    #[tokio::main]
+    async fn review(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        if is_non_interactive() {
+            eprintln!("review needs a terminal, so can't be run non-interactively");
+            process::exit(exitcode::USAGE);
+        }
+
+        let current_repo_path = db_reader::get_canonical_path(".");
+        let mut config_doc = self.find_or_create_db(prompt);
+
+        if !config_doc.is_empty() {
+            let (found_repo, found_client_repo) = self
+                .find_client_or_repo_in_buffer(
+                    &mut config_doc,
+                    Option::from(&current_repo_path),
+                    Option::None,
+                    Option::from(&options[0]),
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Error trying to read from database: {}", err);
+                    std::process::exit(exitcode::DATAERR);
+                });
+
+            Self::set_prompt_with_found_values(prompt, found_repo, found_client_repo);
+
+            if found_client_repo.is_some() {
+                let month_year_string =
+                    link_builder::get_string_month_year(&options[1], &options[2]).unwrap_or_else(
+                        |err| {
+                            eprintln!("Error parsing month or year: {}", err);
+                            process::exit(exitcode::DATAERR);
+                        },
+                    );
+                // safe to parse as they've been checked above
+                let month: u32 = options[1].as_ref().unwrap().parse().unwrap();
+                let year: i32 = options[2].as_ref().unwrap().parse().unwrap();
+
+                if prompt.client_repositories().is_month_locked(year, month) {
+                    eprintln!(
+                        "{} is locked. Run 'autolog unlock' to change it",
+                        month_year_string
+                    );
+                    process::exit(exitcode::DATAERR);
+                }
+
+                let repositories = prompt
+                    .client_repositories()
+                    .repositories
+                    .get_or_insert_default();
+                let mut grid = ReviewGrid::from_repositories(repositories, year, month);
+                if grid.is_empty() {
+                    println!("\n\u{1F916} No timesheet found for {}.", month_year_string);
+                    crate::utils::exit_process();
+                    return;
+                }
+
+                let should_save = review::run(&mut grid).unwrap_or_else(|err| {
+                    eprintln!("Error showing the timesheet: {}", err);
+                    process::exit(exitcode::IOERR);
+                });
+                if !should_save {
+                    crate::utils::exit_process();
+                    return;
+                }
+
+                let changed_days = grid.apply_to(repositories).unwrap_or_else(|err| {
+                    eprintln!("Error editing timesheet: {}", err);
+                    process::exit(exitcode::DATAERR);
+                });
+
+                let client_id = prompt.client_repositories().get_client_id();
+                for client in config_doc.iter_mut() {
+                    if client.get_client_id() == client_id {
+                        *client = prompt.client_repositories().clone();
+                        break;
+                    }
+                }
+
+                Config::write_to_db(&config_doc, &changed_days);
+                HelpPrompt::show_edited_config_success();
+            } else {
+                crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
+            }
+        }
+    }
+}
+
+pub trait History {
+    /// Show the changes made to the hours of a month of a client's timesheet
+    fn history(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl History for Config {
+    #[tokio::main]
+    async fn history(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        let mut config_doc = self.find_or_create_db(prompt);
+
+        if !config_doc.is_empty() {
+            let client_id = self.find_client_id_for_entries(&mut config_doc, options[0].as_ref());
+            let client = config_doc
+                .iter()
+                .find(|client| client_id.is_some() && client.get_client_id() == client_id);
+
+            if let Some(client) = client {
+                let month_year_string =
+                    link_builder::get_string_month_year(&options[1], &options[2]).unwrap_or_else(
+                        |err| {
+                            eprintln!("Error parsing month or year: {}", err);
+                            process::exit(exitcode::DATAERR);
+                        },
+                    );
+                // safe to parse as they've been checked above
+                let month: u32 = options[1].as_ref().unwrap().parse().unwrap();
+                let year: i32 = options[2].as_ref().unwrap().parse().unwrap();
+
+                let changes = db_reader::load_timesheet_history(&client_id.unwrap(), year, month)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Error reading timesheet history: {}", err);
+                        process::exit(exitcode::DATAERR);
+                    });
+
+                HelpPrompt::show_timesheet_history(
+                    &client.get_client_name().unwrap_or_default(),
+                    &month_year_string,
+                    changes,
+                );
+            } else {
+                crate::interface::help_prompt::HelpPrompt::client_or_repository_not_found();
+            }
+        }
+    }
+}
+
+pub trait Lock {
+    /// Keep the hours of a month as they are, rather than working them out from git again
+    fn lock(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl Lock for Config {
+    #[tokio::main]
+    async fn lock(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        self.set_month_locked(options, prompt, true);
+    }
+}
+
+pub trait Unlock {
+    /// Work out the hours of a locked month from git again
+    fn unlock(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt);
+}
+
+impl Unlock for Config {
+    #[tokio::main]
+    async fn unlock(&self, options: Vec<Option<String>>, prompt: &mut HelpPrompt) {
+        if let Err(e) = self.check_for_update().await {
+            eprintln!("Failed to check for updates: {}", e);
+        }
+
+        self.set_month_locked(options, prompt, false);
+    }
+}
+
+pub trait Configure {
+    /// View or change the settings that are resolved at runtime, such as the share endpoint
+    fn configure(&self, options: Vec<Option<String>>);
+}
+
+impl Configure for Config {
+    fn configure(&self, options: Vec<Option<String>>) {
+        let settings_path = settings_file::get_settings_path();
+        let mut settings = Settings::load_from(&settings_path).unwrap_or_else(|err| {
+            eprintln!("Error reading settings from {:?}: {}", settings_path, err);
+            std::process::exit(exitcode::CONFIG);
+        });
+
+        let key = options.get(1).cloned().flatten().unwrap_or_default();
+        let value = options.get(2).cloned().flatten().unwrap_or_default();
+
+        let result: Result<(), Box<dyn std::error::Error>> = match options[0].as_deref() {
+            Some("set") => settings.set(&key, &value).map(|_| true),
+            Some("unset") => settings.unset(&key).map(|_| true),
+            _ => Ok(false),
+        }
+        .and_then(|changed| {
+            if changed && !crate::utils::is_test_mode() {
+                settings.save_to(&settings_path)?;
+            }
+            Ok(())
+        });
+
+        if let Err(err) = result {
+            eprintln!("Error updating settings: {}", err);
+            std::process::exit(exitcode::CONFIG);
+        }
+
+        let share_config = ShareConfig::resolve(&settings).unwrap_or_else(|err| {
+            eprintln!("Error resolving settings: {}", err);
+            std::process::exit(exitcode::CONFIG);
+        });
+
+        match options[0].as_deref() {
+            Some("get") => match share_config.get(&key) {
+                Ok(value) => println!("{}", value),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(exitcode::USAGE);
+                }
+            },
+            Some("set") | Some("unset") => {
+                HelpPrompt::show_setting_updated(&key, &share_config.get(&key).unwrap_or_default())
+            }
+            _ => {
+                let rows = settings_file::SETTING_KEYS
+                    .iter()
+                    .map(|key| (key.to_string(), share_config.get(key).unwrap_or_default()))
+                    .collect();
+                HelpPrompt::show_settings(&settings_path, rows);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::{Config, Edit, New, Remove};
     use crate::data::client_repositories::ClientRepositories;
     use crate::data::repository::Repository;
+    use crate::data::timesheet_history::ChangedDays;
     use crate::interface::help_prompt::ConfigurationDoc;
     use crate::utils::db::db_reader::test_utils::{create_test_client, setup_test_db};
     use envtestkit::lock::lock_test;
@@ -731,7 +1378,12 @@ mod tests {
         // Create test client with default timesheet data
         let test_client = create_test_client("apple", "autolog");
         let tx = conn.transaction().unwrap();
-        crate::utils::db::db_reader::save_client_repository(&tx, &test_client).unwrap();
+        crate::utils::db::db_reader::save_client_repository(
+            &tx,
+            &test_client,
+            &ChangedDays::default(),
+        )
+        .unwrap();
         tx.commit().unwrap();
 
         let config = Config::new();
@@ -784,7 +1436,12 @@ mod tests {
         // Create test data that matches what we're testing for
         let test_client = create_test_client("apple", "autolog");
         let tx = conn.transaction().unwrap();
-        crate::utils::db::db_reader::save_client_repository(&tx, &test_client).unwrap();
+        crate::utils::db::db_reader::save_client_repository(
+            &tx,
+            &test_client,
+            &ChangedDays::default(),
+        )
+        .unwrap();
         tx.commit().unwrap();
 
         let namespace = "autolog".to_string();
@@ -845,7 +1502,12 @@ mod tests {
         // Create test data
         let test_client = create_test_client("apple", "autolog");
         let tx = conn.transaction().unwrap();
-        crate::utils::db::db_reader::save_client_repository(&tx, &test_client).unwrap();
+        crate::utils::db::db_reader::save_client_repository(
+            &tx,
+            &test_client,
+            &ChangedDays::default(),
+        )
+        .unwrap();
         tx.commit().unwrap();
 
         let client = "apple".to_string();
@@ -0,0 +1,156 @@
+use crate::data::git_commit::GitCommit;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// Another name, email or pattern the user commits under. Identities are written one per
+/// line as `Name`, `email@example.com`, `Name <email@example.com>`, or `/regex/` to match
+/// against `Name <email>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorIdentity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl AuthorIdentity {
+    pub fn parse(value: &str) -> Result<Self, Box<dyn Error>> {
+        let value = value.trim();
+        let identity = if value.len() > 1 && value.starts_with('/') && value.ends_with('/') {
+            let pattern = &value[1..value.len() - 1];
+            regex::Regex::new(pattern)?;
+            AuthorIdentity {
+                name: None,
+                email: None,
+                pattern: Some(pattern.to_string()),
+            }
+        } else if let Some((name, email)) = value.strip_suffix('>').and_then(|x| x.split_once('<'))
+        {
+            AuthorIdentity {
+                name: Some(name.trim().to_string()).filter(|x| !x.is_empty()),
+                email: Some(email.trim().to_string()).filter(|x| !x.is_empty()),
+                pattern: None,
+            }
+        } else if value.contains('@') && !value.contains(' ') {
+            AuthorIdentity {
+                name: None,
+                email: Some(value.to_string()),
+                pattern: None,
+            }
+        } else {
+            AuthorIdentity {
+                name: Some(value.to_string()),
+                email: None,
+                pattern: None,
+            }
+        };
+
+        if identity.name.is_none() && identity.email.is_none() && identity.pattern.is_none() {
+            return Err(format!("'{}' isn't a name, email or /pattern/", value).into());
+        }
+
+        Ok(identity)
+    }
+
+    /// Parse identities written one per line, ignoring blank lines
+    pub fn parse_lines(value: &str) -> Result<Vec<Self>, Box<dyn Error>> {
+        value
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(AuthorIdentity::parse)
+            .collect()
+    }
+
+    pub fn matches(&self, author_name: &str, author_email: &str) -> bool {
+        if let Some(pattern) = self.pattern.as_ref() {
+            return regex::Regex::new(pattern)
+                .map(|regex| regex.is_match(&format!("{} <{}>", author_name, author_email)))
+                .unwrap_or(false);
+        }
+
+        let name_matches = self
+            .name
+            .as_ref()
+            .is_none_or(|name| name.trim() == author_name.trim());
+        let email_matches = self
+            .email
+            .as_ref()
+            .is_none_or(|email| email.trim().eq_ignore_ascii_case(author_email.trim()));
+
+        name_matches && email_matches
+    }
+
+    pub fn matches_commit(&self, commit: &GitCommit) -> bool {
+        self.matches(&commit.author_name, &commit.author_email)
+    }
+}
+
+impl fmt::Display for AuthorIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.pattern, &self.name, &self.email) {
+            (Some(pattern), _, _) => write!(f, "/{}/", pattern),
+            (None, Some(name), Some(email)) => write!(f, "{} <{}>", name, email),
+            (None, Some(name), None) => write!(f, "{}", name),
+            (None, None, Some(email)) => write!(f, "{}", email),
+            (None, None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_author_identities() {
+        let identities = AuthorIdentity::parse_lines(
+            "Jim Jones\n\njim@work.com\nJ Jones <jim@home.com>\n/^Jim .*@jones\\.dev>$/\n",
+        )
+        .unwrap();
+
+        assert_eq!(identities.len(), 4);
+        assert_eq!(identities[0].name, Some("Jim Jones".to_string()));
+        assert_eq!(identities[1].email, Some("jim@work.com".to_string()));
+        assert_eq!(identities[2].name, Some("J Jones".to_string()));
+        assert_eq!(identities[2].email, Some("jim@home.com".to_string()));
+        assert_eq!(
+            identities[3].pattern,
+            Some("^Jim .*@jones\\.dev>$".to_string())
+        );
+
+        let lines: Vec<String> = identities.iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Jim Jones",
+                "jim@work.com",
+                "J Jones <jim@home.com>",
+                "/^Jim .*@jones\\.dev>$/"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_author_identities() {
+        assert!(AuthorIdentity::parse("/[/").is_err());
+        assert!(AuthorIdentity::parse("<>").is_err());
+    }
+
+    #[test]
+    fn it_matches_author_identities() {
+        let identity = AuthorIdentity::parse("jim@work.com").unwrap();
+        assert!(identity.matches("Anyone", "Jim@Work.com"));
+        assert!(!identity.matches("Jim Jones", "jim@home.com"));
+
+        let identity = AuthorIdentity::parse("J Jones <jim@home.com>").unwrap();
+        assert!(identity.matches("J Jones", "jim@home.com"));
+        assert!(!identity.matches("Jim Jones", "jim@home.com"));
+
+        let identity = AuthorIdentity::parse("/^Jim .*@jones\\.dev>$/").unwrap();
+        assert!(identity.matches("Jim Jones", "jim@jones.dev"));
+        assert!(!identity.matches("Jim Jones", "jim@jones.com"));
+    }
+}
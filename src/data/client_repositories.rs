@@ -1,9 +1,14 @@
 use crate::config::New;
+use crate::data::author_identity::AuthorIdentity;
+use crate::data::git_history;
+use crate::data::locked_month::LockedMonth;
+use crate::data::manual_entry::ManualEntry;
 use crate::data::repository::{GitLogDates, Repository};
+use crate::data::vcs;
+use crate::utils::date::date_parser::TimesheetYears;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::process;
-use std::process::Command;
 
 /// Repositories are modified at a Repository level and a client level.
 /// ClientRepositories  holds the client and the repositories when they are found in the buffer
@@ -40,6 +45,8 @@ pub struct ClientRepositories {
     pub repositories: Option<Vec<Repository>>,
     pub requires_approval: Option<bool>,
     pub approver: Option<Approver>,
+    pub manual_entries: Option<Vec<ManualEntry>>,
+    pub locked_months: Option<Vec<LockedMonth>>,
 }
 
 impl New for ClientRepositories {
@@ -200,6 +207,161 @@ impl ClientRepositories {
         self
     }
 
+    /// Set how hours are worked out for every repository under the client
+    pub fn update_hours_estimation(
+        &mut self,
+        mode: String,
+        lead_in_minutes: Option<i64>,
+        session_gap_minutes: Option<i64>,
+    ) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos {
+                repo.set_hours_estimation(mode.clone(), lead_in_minutes, session_gap_minutes);
+            }
+        }
+        self
+    }
+
+    /// Set how a day's hours are split between the client's repositories
+    pub fn update_hours_split(&mut self, value: String) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos {
+                repo.set_hours_split(value.clone());
+            }
+        }
+        self
+    }
+
+    /// Set how hours are rounded for every repository under the client
+    pub fn update_hours_rounding(&mut self, value: String) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos {
+                repo.set_hours_rounding(value.clone());
+            }
+        }
+        self
+    }
+
+    /// Set the timezone and day start hour used to work out which day each commit counts towards
+    pub fn update_day_boundary(
+        &mut self,
+        timezone: Option<String>,
+        day_start_hour: u32,
+    ) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos {
+                repo.set_day_boundary(timezone.clone(), day_start_hour);
+            }
+        }
+        self
+    }
+
+    /// Set the other identities the user commits under for every repository under the client
+    pub fn update_author_identities(
+        &mut self,
+        identities: Vec<AuthorIdentity>,
+        use_mailmap: bool,
+    ) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos {
+                repo.set_author_identities(identities.clone(), use_mailmap);
+            }
+        }
+        self
+    }
+
+    /// Set the ref patterns for one of the client's repositories
+    pub fn update_ref_patterns(
+        &mut self,
+        repository_id: &str,
+        included: Vec<String>,
+        excluded: Vec<String>,
+    ) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos
+                .iter_mut()
+                .filter(|repo| repo.id.as_deref() == Some(repository_id))
+            {
+                repo.set_ref_patterns(included.clone(), excluded.clone());
+            }
+        }
+        self
+    }
+
+    /// Copy the namespace and location of a repository changed with 'autolog update' to the
+    /// client's copy of it
+    pub fn update_repository(&mut self, repository: &Repository) -> &mut Self {
+        if let Some(repos) = self.repositories.as_mut() {
+            for repo in repos
+                .iter_mut()
+                .filter(|repo| repo.id.is_some() && repo.id == repository.id)
+            {
+                repo.namespace = repository.namespace.clone();
+                repo.namespace_alias = repository.namespace_alias.clone();
+                repo.repo_path = repository.repo_path.clone();
+                repo.git_path = repository.git_path.clone();
+                repo.service = repository.service.clone();
+                repo.service_username = repository.service_username.clone();
+                repo.name = repository.name.clone();
+                repo.email = repository.email.clone();
+            }
+        }
+        self
+    }
+
+    pub fn add_manual_entry(&mut self, entry: ManualEntry) -> &mut Self {
+        let entries = self.manual_entries.get_or_insert_with(Vec::new);
+        entries.push(entry);
+        entries.sort_by_key(|entry| entry.date);
+        self
+    }
+
+    /// Remove a manual entry by id, returning it if it was found
+    pub fn remove_manual_entry(&mut self, id: &str) -> Option<ManualEntry> {
+        let entries = self.manual_entries.as_mut()?;
+        let index = entries.iter().position(|entry| entry.id == id)?;
+        Some(entries.remove(index))
+    }
+
+    pub fn get_manual_entries_for_month(&self, year: i32, month: u32) -> Vec<&ManualEntry> {
+        self.manual_entries
+            .iter()
+            .flatten()
+            .filter(|entry| entry.is_in_month(year, month))
+            .collect()
+    }
+
+    /// Lock a month so its hours are kept as they are. Returns false if it was already locked
+    pub fn lock_month(&mut self, year: i32, month: u32) -> bool {
+        let locked_months = self.locked_months.get_or_insert_with(Vec::new);
+        let locked_month = LockedMonth::new(year, month);
+        if locked_months.contains(&locked_month) {
+            return false;
+        }
+
+        locked_months.push(locked_month);
+        locked_months.sort();
+        true
+    }
+
+    /// Unlock a month so its hours are worked out from the git history again. Returns false
+    /// if it wasn't locked
+    pub fn unlock_month(&mut self, year: i32, month: u32) -> bool {
+        let Some(locked_months) = self.locked_months.as_mut() else {
+            return false;
+        };
+        let count = locked_months.len();
+        locked_months.retain(|locked_month| locked_month != &LockedMonth::new(year, month));
+        locked_months.len() != count
+    }
+
+    pub fn is_month_locked(&self, year: i32, month: u32) -> bool {
+        self.locked_months
+            .iter()
+            .flatten()
+            .any(|locked_month| locked_month == &LockedMonth::new(year, month))
+    }
+
     pub fn set_values_from_buffer(
         &mut self,
         client_repositories: &ClientRepositories,
@@ -208,6 +370,14 @@ impl ClientRepositories {
         self
     }
 
+    pub fn find_repository_by_namespace(&self, namespace: &str) -> Option<&Repository> {
+        self.repositories.as_ref()?.iter().find(|repo| {
+            repo.namespace
+                .as_ref()
+                .is_some_and(|value| value.to_lowercase() == namespace.to_lowercase())
+        })
+    }
+
     pub fn remove_repository_by_namespace(&mut self, namespace: &str) -> &mut Self {
         if let Some(repos) = self.repositories.as_mut() {
             repos.retain(|repo| {
@@ -289,26 +459,25 @@ impl ClientRepositories {
     }
 
     pub fn exec_generate_timesheets_from_git_history(&mut self) -> &mut Self {
+        let options = git_history::get_scan_options();
+
         if let Some(repositories) = &mut self.repositories {
-            for repository in repositories {
-                let command = String::from("--author");
-                if let Some(author) = repository.name.as_ref() {
-                    let author = [command, author.to_string()].join("=");
-
-                    let output = Command::new("git")
-                        .arg("-C")
-                        .arg(repository.git_path.as_ref().unwrap())
-                        .arg("log")
-                        .arg("--date=rfc")
-                        .arg(author)
-                        .arg("--all")
-                        .output()
-                        .expect("Failed to execute command");
-
-                    let output_string = crate::utils::trim_output_from_utf8(output)
-                        .unwrap_or_else(|_| "Parsing output failed".to_string());
-
-                    repository.parse_git_log_dates_from_git_history(output_string);
+            let histories = vcs::read_histories(repositories, &options);
+
+            for (repository, history) in repositories.iter_mut().zip(histories) {
+                if !repository.is_git_repository() {
+                    // projects without git only have the days logged against them by hand
+                    repository.set_values_from_commits(&[]);
+                } else if repository.name.is_some() {
+                    let commits = history.unwrap_or_else(|err| {
+                        eprintln!(
+                            "Error reading history for {}: {}",
+                            repository.namespace.as_deref().unwrap_or_default(),
+                            err
+                        );
+                        vec![]
+                    });
+                    repository.set_values_from_git_log(commits);
                 } else {
                     eprint!(
                         "Could not parse git log dates from git history. Repository name is missing"
@@ -321,7 +490,11 @@ impl ClientRepositories {
     }
 
     pub fn compare_logs_and_set_timesheets(&mut self) -> &mut Self {
+        let locked_months = self.locked_months.clone().unwrap_or_default();
+
         if let Some(repositories) = &mut self.repositories {
+            let day_weights = crate::utils::date::date_parser::get_day_weights(repositories);
+
             for i in 0..repositories.len() {
                 // for each repository, build a vec of the git_log_dates from the other repositories
                 let adjacent_git_log_dates: Vec<GitLogDates> = repositories
@@ -331,12 +504,13 @@ impl ClientRepositories {
                     .map(|(_, repo)| repo.git_log_dates.as_ref().unwrap().clone())
                     .collect();
 
-                let timesheet = match &repositories[i].git_log_dates {
+                let mut timesheet = match &repositories[i].git_log_dates {
                     Some(git_log_dates) => {
                         crate::utils::date::date_parser::get_timesheet_map_from_date_hashmap(
                             git_log_dates.clone(),
                             &mut repositories[i],
                             adjacent_git_log_dates,
+                            &day_weights,
                             i,
                         )
                     }
@@ -346,6 +520,11 @@ impl ClientRepositories {
                     }
                 };
 
+                keep_locked_months(
+                    &mut timesheet,
+                    repositories[i].timesheet.as_ref(),
+                    &locked_months,
+                );
                 repositories[i].set_timesheet(timesheet);
             }
         }
@@ -354,14 +533,53 @@ impl ClientRepositories {
     }
 }
 
+/// Put back the days of each locked month from the timesheet saved before, so that newly
+/// fetched history can't change a month that has already been invoiced
+fn keep_locked_months(
+    timesheet: &mut TimesheetYears,
+    saved_timesheet: Option<&TimesheetYears>,
+    locked_months: &[LockedMonth],
+) {
+    for locked_month in locked_months {
+        let (year, month) = (
+            locked_month.year.to_string(),
+            locked_month.month.to_string(),
+        );
+        let saved_days = saved_timesheet
+            .and_then(|saved_timesheet| saved_timesheet.get(&year))
+            .and_then(|months| months.get(&month));
+
+        match saved_days {
+            Some(days) => {
+                timesheet
+                    .entry(year)
+                    .or_default()
+                    .insert(month, days.clone());
+            }
+            None => {
+                if let Some(months) = timesheet.get_mut(&year) {
+                    months.remove(&month);
+                    if months.is_empty() {
+                        timesheet.remove(&year);
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::data::client_repositories::{Client, ClientRepositories, New, User};
+    use crate::data::manual_entry::ManualEntry;
     use crate::data::repository::Repository;
     use crate::helpers::mocks;
+    use crate::utils::date::date_parser::create_single_day_object;
+    use chrono::NaiveDate;
     use nanoid::nanoid;
-    use serde_json::json;
+    use serde_json::{Map, json};
     use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
 
     #[test]
     fn test_fetch_user_thumbnail() {
@@ -594,6 +812,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_updates_hours_estimation() {
+        let mut client_repo = ClientRepositories {
+            ..Default::default()
+        };
+
+        mocks::create_mock_client_repository(&mut client_repo);
+
+        client_repo.update_hours_estimation("commits".to_string(), Some(15), Some(90));
+        let repository = &client_repo.repositories.as_ref().unwrap()[0];
+        assert!(repository.estimates_hours_from_commits());
+        assert_eq!(repository.commit_lead_in_minutes, Some(15));
+        assert_eq!(repository.commit_session_gap_minutes, Some(90));
+    }
+
+    #[test]
+    fn it_updates_ref_patterns() {
+        let mut client_repo = ClientRepositories {
+            ..Default::default()
+        };
+
+        mocks::create_mock_client_repository(&mut client_repo);
+        client_repo.repositories.as_mut().unwrap()[0].id = Some("repo1".to_string());
+
+        client_repo.update_ref_patterns(
+            "repo1",
+            vec!["main".to_string()],
+            vec!["refs/stash".to_string()],
+        );
+        let repository = &client_repo.repositories.as_ref().unwrap()[0];
+        assert_eq!(repository.included_refs, Some(vec!["main".to_string()]));
+        assert_eq!(
+            repository.excluded_refs,
+            Some(vec!["refs/stash".to_string()])
+        );
+    }
+
+    #[test]
+    fn it_finds_a_repository_by_namespace() {
+        let mut client_repo = ClientRepositories {
+            ..Default::default()
+        };
+
+        mocks::create_mock_client_repository(&mut client_repo);
+        assert!(
+            client_repo
+                .find_repository_by_namespace("Autolog")
+                .is_some()
+        );
+        assert!(client_repo.find_repository_by_namespace("design").is_none());
+    }
+
+    #[test]
+    fn it_updates_a_repository() {
+        let mut client_repo = ClientRepositories {
+            ..Default::default()
+        };
+
+        mocks::create_mock_client_repository(&mut client_repo);
+        client_repo.repositories.as_mut().unwrap()[0].id = Some("repo1".to_string());
+
+        let mut repository = client_repo.repositories.as_ref().unwrap()[0].clone();
+        repository
+            .set_namespace_alias("ingenious".to_string())
+            .set_default_hours(2.0);
+        client_repo.update_repository(&repository);
+
+        let updated = &client_repo.repositories.as_ref().unwrap()[0];
+        assert_eq!(updated.namespace_alias, Some("ingenious".to_string()));
+        // only the namespace and location are copied
+        assert_ne!(updated.default_hours, Some(2.0));
+    }
+
+    #[test]
+    fn it_adds_and_removes_manual_entries() {
+        let mut client_repo = ClientRepositories {
+            ..Default::default()
+        };
+
+        let date = |day| NaiveDate::from_ymd_opt(2021, 10, day).unwrap();
+        let call = ManualEntry::new(date(12), "1", None, Some("Call")).unwrap();
+        let design = ManualEntry::new(date(4), "3", Some("Design"), None).unwrap();
+        let later = ManualEntry::new(
+            NaiveDate::from_ymd_opt(2021, 11, 1).unwrap(),
+            "2",
+            None,
+            None,
+        )
+        .unwrap();
+
+        client_repo
+            .add_manual_entry(call.clone())
+            .add_manual_entry(design.clone())
+            .add_manual_entry(later);
+
+        assert_eq!(
+            client_repo.get_manual_entries_for_month(2021, 10),
+            vec![&design, &call]
+        );
+        assert_eq!(client_repo.remove_manual_entry(&call.id), Some(call));
+        assert_eq!(client_repo.remove_manual_entry("missing"), None);
+        assert_eq!(
+            client_repo.get_manual_entries_for_month(2021, 10),
+            vec![&design]
+        );
+    }
+
     #[test]
     fn it_updates_approvers_name() {
         let mut client_repo = ClientRepositories {
@@ -697,6 +1022,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_generates_an_empty_timesheet_for_a_project_without_git() {
+        let mut client_repositories = ClientRepositories {
+            repositories: Option::from(vec![Repository {
+                namespace: Option::from("figma".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        client_repositories
+            .exec_generate_timesheets_from_git_history()
+            .compare_logs_and_set_timesheets();
+
+        let repository = &client_repositories.repositories.as_ref().unwrap()[0];
+        assert!(!repository.is_git_repository());
+        assert_eq!(repository.timesheet, Some(Default::default()));
+    }
+
     #[test]
     fn it_compares_git_logs_and_sets_timesheets() {
         let mut client_repositories: ClientRepositories = ClientRepositories {
@@ -757,4 +1101,42 @@ mod tests {
 
         assert_eq!(ts, vec![8.0, 4.0, 3.0]);
     }
+
+    #[test]
+    fn it_keeps_the_saved_hours_of_locked_months() {
+        let saved_days: Vec<_> = (1..=28)
+            .map(|_| Map::from_iter(create_single_day_object(false, 6.5, false)))
+            .collect();
+        let mut client_repositories = ClientRepositories {
+            repositories: Option::Some(vec![Repository {
+                namespace: Option::Some("Project_1".to_string()),
+                git_log_dates: Option::Some(mocks::generate_project_git_log_dates([1, 2, 3])),
+                timesheet: Option::Some(HashMap::from([(
+                    "2021".to_string(),
+                    HashMap::from([("2".to_string(), saved_days.clone())]),
+                )])),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        assert!(client_repositories.lock_month(2021, 2));
+        assert!(client_repositories.lock_month(2021, 9));
+        assert!(!client_repositories.lock_month(2021, 9));
+        assert!(client_repositories.lock_month(2020, 5));
+        assert!(client_repositories.unlock_month(2020, 5));
+        assert!(!client_repositories.unlock_month(2020, 5));
+        assert!(client_repositories.is_month_locked(2021, 2));
+        assert!(!client_repositories.is_month_locked(2020, 5));
+
+        client_repositories.compare_logs_and_set_timesheets();
+
+        let timesheet = client_repositories.repositories.unwrap()[0]
+            .timesheet
+            .clone()
+            .unwrap();
+        assert_eq!(timesheet["2021"]["2"], saved_days);
+        assert!(!timesheet["2021"].contains_key("9"));
+        assert_eq!(timesheet["2020"]["5"].len(), 31);
+    }
 }
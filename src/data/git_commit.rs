@@ -0,0 +1,147 @@
+use chrono::{DateTime, FixedOffset};
+use std::sync::OnceLock;
+
+/// Each commit starts with a record separator, and its fields are separated by NUL so that
+/// nothing in a name or subject can be mistaken for another field. Anything git prints after
+/// the subject, such as `--shortstat` output, stays in the same record.
+pub const GIT_LOG_FORMAT: &str = "%x1e%H%x00%an%x00%ae%x00%aI%x00%cI%x00%s";
+/// The same format, with author names and emails mapped through the repository's .mailmap
+pub const GIT_LOG_FORMAT_MAILMAP: &str = "%x1e%H%x00%aN%x00%aE%x00%aI%x00%cI%x00%s";
+
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\0';
+
+/// A commit read from `git log` using GIT_LOG_FORMAT
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub author_date: DateTime<FixedOffset>,
+    pub committer_date: DateTime<FixedOffset>,
+    pub subject: String,
+    /// Insertions plus deletions, when the log was run with `--shortstat`
+    pub lines_changed: Option<u64>,
+}
+
+fn shortstat_regex() -> &'static regex::Regex {
+    static REGEX: OnceLock<regex::Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        regex::Regex::new(
+            r"\d+ files? changed(?:, (?P<insertions>\d+) insertions?\(\+\))?(?:, (?P<deletions>\d+) deletions?\(-\))?",
+        )
+        .unwrap()
+    })
+}
+
+/// Read the lines changed from `git --shortstat` output, which jj's diff stat also ends with
+pub fn parse_lines_changed(stat: &str) -> Option<u64> {
+    let cap = shortstat_regex().captures(stat)?;
+    let count = |name: &str| {
+        cap.name(name)
+            .and_then(|value| value.as_str().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Some(count("insertions") + count("deletions"))
+}
+
+impl GitCommit {
+    fn from_record(record: &str, lines_changed: fn(&str) -> Option<u64>) -> Option<Self> {
+        let mut fields = record.splitn(6, FIELD_SEPARATOR);
+        let hash = fields.next()?.trim().to_string();
+        let author_name = fields.next()?.to_string();
+        let author_email = fields.next()?.to_string();
+        let author_date = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let committer_date = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+
+        // the subject is on one line, followed by anything else git printed for the commit
+        let remainder = fields.next()?;
+        let (subject, rest) = remainder.split_once('\n').unwrap_or((remainder, ""));
+
+        if hash.is_empty() {
+            return None;
+        }
+
+        Some(GitCommit {
+            hash,
+            author_name,
+            author_email,
+            author_date,
+            committer_date,
+            subject: subject.to_string(),
+            lines_changed: lines_changed(rest),
+        })
+    }
+}
+
+/// Parse the output of `git log --format=GIT_LOG_FORMAT`, skipping any record that can't be read
+pub fn parse_git_log(output: &str) -> Vec<GitCommit> {
+    parse_log_records(output, parse_lines_changed)
+}
+
+/// Parse records laid out like GIT_LOG_FORMAT, whichever VCS printed them, reading the lines
+/// changed from anything printed after the subject
+pub fn parse_log_records(output: &str, lines_changed: fn(&str) -> Option<u64>) -> Vec<GitCommit> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .filter_map(|record| GitCommit::from_record(record, lines_changed))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+
+    #[test]
+    fn it_parses_commits_from_git_log() {
+        let output = [
+            mocks::create_mock_git_log_record(
+                "Thu, 21 Oct 2021 10:06:14 +0200",
+                "Date:   Fri, 1 Jan 2021 10:00:00 +0000 isn't a commit date",
+                Some("2 files changed, 10 insertions(+), 3 deletions(-)"),
+            ),
+            mocks::create_mock_git_log_record("Wed, 20 Oct 2021 12:09:16 +0200", "", None),
+        ]
+        .concat();
+
+        let commits = parse_git_log(&output);
+        assert_eq!(commits.len(), 2);
+
+        assert_eq!(commits[0].author_name, "Jim Jones");
+        assert_eq!(commits[0].author_email, "jim@jones.com");
+        assert_eq!(
+            commits[0].author_date,
+            DateTime::parse_from_rfc2822("Thu, 21 Oct 2021 10:06:14 +0200").unwrap()
+        );
+        assert_eq!(
+            commits[0].subject,
+            "Date:   Fri, 1 Jan 2021 10:00:00 +0000 isn't a commit date"
+        );
+        assert_eq!(commits[0].lines_changed, Some(13));
+
+        assert_eq!(commits[1].subject, "");
+        assert_eq!(commits[1].lines_changed, None);
+    }
+
+    #[test]
+    fn it_skips_records_that_cant_be_read() {
+        let output = "\u{1e}abc\0Jim\0jim@jones.com\0not a date\0not a date\0subject\n";
+        assert!(parse_git_log(output).is_empty());
+        assert!(parse_git_log("").is_empty());
+    }
+
+    #[test]
+    fn it_parses_lines_changed_from_shortstat() {
+        assert_eq!(
+            parse_lines_changed(" 1 file changed, 1 insertion(+)"),
+            Some(1)
+        );
+        assert_eq!(
+            parse_lines_changed(" 1 file changed, 4 deletions(-)"),
+            Some(4)
+        );
+        assert_eq!(parse_lines_changed(""), None);
+    }
+}
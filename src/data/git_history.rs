@@ -0,0 +1,539 @@
+use crate::data::git_commit::{self, GIT_LOG_FORMAT, GIT_LOG_FORMAT_MAILMAP, GitCommit};
+use crate::data::repository::Repository;
+#[cfg(feature = "in-process-git")]
+use crate::data::vcs::libgit2;
+use crate::utils::db::db_reader;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+
+/// How much of a repository's history to read. By default only the commits that weren't
+/// reachable at the last scan are read from git, and added to the commits stored in the database.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitScanOptions {
+    /// Ignore the stored commits and read the whole history again
+    pub full_rescan: bool,
+    /// Only read commits made on or after this day
+    pub since: Option<NaiveDate>,
+    /// Only read commits made on or before this day
+    pub until: Option<NaiveDate>,
+}
+
+/// Set by the global --full-rescan, --since and --until flags
+static SCAN_OPTIONS: OnceLock<GitScanOptions> = OnceLock::new();
+
+pub fn set_scan_options(options: GitScanOptions) {
+    let _ = SCAN_OPTIONS.set(options);
+}
+
+pub fn get_scan_options() -> GitScanOptions {
+    SCAN_OPTIONS.get().cloned().unwrap_or_default()
+}
+
+impl GitScanOptions {
+    pub fn is_windowed(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    /// git compares --since and --until against the committer date, in local time
+    pub fn contains(&self, committer_date: &DateTime<FixedOffset>) -> bool {
+        let date = committer_date.with_timezone(&Local).date_naive();
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+
+    fn window_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(since) = self.since {
+            args.push(format!("--since={}T00:00:00", since.format("%Y-%m-%d")));
+        }
+        if let Some(until) = self.until {
+            args.push(format!("--until={}T23:59:59", until.format("%Y-%m-%d")));
+        }
+        args
+    }
+}
+
+/// The commits read from a repository so far
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitHistory {
+    /// The log options the commits were read with. If they change, the history is read again
+    pub scan_key: String,
+    /// The commits at the tips of the repository's refs when it was last scanned
+    pub scanned_commits: Vec<String>,
+    pub commits: Vec<GitCommit>,
+}
+
+/// Describes everything that changes which commits git returns, or what is read for each of them
+pub fn get_scan_key(repository: &Repository) -> String {
+    // every author's commits are read, and the user's are matched once they've been read
+    format!(
+        "author=*;mailmap={};shortstat={};refs={:?}/{:?}",
+        repository.uses_mailmap(),
+        repository.splits_hours_by_lines_changed(),
+        repository.included_refs.as_deref().unwrap_or_default(),
+        repository.excluded_refs.as_deref().unwrap_or_default()
+    )
+}
+
+fn git_command(repository: &Repository) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repository.git_path.as_ref().unwrap());
+    command
+}
+
+/// Run git, passing the revisions on stdin since a repository can have more refs than fit on a
+/// command line
+fn run_git(command: &mut Command, revisions: &[String]) -> Result<Output, Box<dyn Error>> {
+    if revisions.is_empty() {
+        return Ok(command.output()?);
+    }
+
+    let mut child = command
+        .arg("--stdin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for revision in revisions {
+            writeln!(stdin, "{}", revision)?;
+        }
+    }
+
+    Ok(child.wait_with_output()?)
+}
+
+/// Turn a ref pattern into a regex, where `*` matches any run of characters and `?` any one
+fn ref_pattern_regex(pattern: &str) -> Option<regex::Regex> {
+    let pattern = regex::escape(pattern.trim())
+        .replace("\\*", ".*")
+        .replace("\\?", ".");
+    regex::Regex::new(&format!("^{}$", pattern)).ok()
+}
+
+/// Patterns match either the full name of a ref, or its name without `refs/heads/`,
+/// `refs/remotes/`, `refs/tags/` or `refs/`
+pub fn ref_matches_pattern(refname: &str, pattern: &str) -> bool {
+    let short_name = ["refs/heads/", "refs/remotes/", "refs/tags/", "refs/"]
+        .iter()
+        .find_map(|prefix| refname.strip_prefix(prefix))
+        .unwrap_or(refname);
+
+    ref_pattern_regex(pattern)
+        .is_some_and(|regex| regex.is_match(refname) || regex.is_match(short_name))
+}
+
+/// Keep the refs matching any of the included patterns, or every ref when there are none,
+/// then drop the refs matching any of the excluded patterns
+pub fn select_refs(refs: &[String], included: &[String], excluded: &[String]) -> Vec<String> {
+    refs.iter()
+        .filter(|refname| {
+            included.is_empty()
+                || included
+                    .iter()
+                    .any(|pattern| ref_matches_pattern(refname, pattern))
+        })
+        .filter(|refname| {
+            !excluded
+                .iter()
+                .any(|pattern| ref_matches_pattern(refname, pattern))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Run git, failing with what it printed to stderr when it doesn't succeed
+fn read_git_lines(
+    command: &mut Command,
+    revisions: &[String],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let output = run_git(command, revisions)?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// The full names of the repository's refs
+fn read_ref_names(repository: &Repository) -> Result<Vec<String>, Box<dyn Error>> {
+    #[cfg(feature = "in-process-git")]
+    if let Some(repo) = libgit2::open(repository) {
+        return libgit2::read_ref_names(&repo);
+    }
+
+    read_git_lines(
+        git_command(repository).args(["for-each-ref", "--format=%(refname)"]),
+        &[],
+    )
+}
+
+/// The refs to read history from, or None to read from every ref and HEAD like `git log --all`
+fn read_selected_refs(repository: &Repository) -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    if !repository.has_ref_patterns() {
+        return Ok(None);
+    }
+
+    let refs = read_ref_names(repository)?;
+
+    Ok(Some(select_refs(
+        &refs,
+        repository.included_refs.as_deref().unwrap_or_default(),
+        repository.excluded_refs.as_deref().unwrap_or_default(),
+    )))
+}
+
+/// The commits that history is read from
+fn read_ref_tips(
+    repository: &Repository,
+    refs: Option<&[String]>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    #[cfg(feature = "in-process-git")]
+    if let Some(repo) = libgit2::open(repository) {
+        return libgit2::read_ref_tips(&repo, refs);
+    }
+
+    let mut command = git_command(repository);
+    command.args(["log", "--no-walk", "--format=%H"]);
+
+    match refs {
+        None => read_git_lines(command.arg("--all"), &[]),
+        Some([]) => Ok(vec![]),
+        Some(refs) => read_git_lines(&mut command, refs),
+    }
+}
+
+/// Read the commits reachable from the refs that can't be reached from any of the excluded commits
+fn read_git_log(
+    repository: &Repository,
+    refs: Option<&[String]>,
+    excluded_commits: &[String],
+    options: &GitScanOptions,
+) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+    #[cfg(feature = "in-process-git")]
+    if let Some(repo) = libgit2::open(repository) {
+        return libgit2::read_git_log(&repo, repository, refs, excluded_commits, options);
+    }
+
+    let mut command = git_command(repository);
+    command.arg("log");
+
+    let mut revisions: Vec<String> = match refs {
+        None => {
+            command.arg("--all");
+            vec![]
+        }
+        // without any revisions git would read from HEAD
+        Some([]) => return Ok(vec![]),
+        Some(refs) => refs.to_vec(),
+    };
+    revisions.extend(excluded_commits.iter().map(|hash| format!("^{}", hash)));
+
+    if repository.uses_mailmap() {
+        command
+            .arg("--use-mailmap")
+            .arg(format!("--format={}", GIT_LOG_FORMAT_MAILMAP));
+    } else {
+        command.arg(format!("--format={}", GIT_LOG_FORMAT));
+    }
+
+    // lines changed are only needed when they're used to split hours
+    if repository.splits_hours_by_lines_changed() {
+        command.arg("--shortstat");
+    }
+
+    command.args(options.window_args());
+
+    let output = run_git(&mut command, &revisions)?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+    }
+
+    Ok(git_commit::parse_git_log(&String::from_utf8(
+        output.stdout,
+    )?))
+}
+
+/// Add the new commits to the ones already read, skipping any that were already there
+pub fn merge_commits(commits: Vec<GitCommit>, new_commits: Vec<GitCommit>) -> Vec<GitCommit> {
+    let mut hashes: HashSet<String> = commits.iter().map(|commit| commit.hash.clone()).collect();
+    let mut merged = commits;
+    merged.extend(
+        new_commits
+            .into_iter()
+            .filter(|commit| hashes.insert(commit.hash.clone())),
+    );
+    merged
+}
+
+/// Replace the stored commits inside the window with the ones just read from it
+pub fn replace_commits_in_window(
+    commits: Vec<GitCommit>,
+    window_commits: Vec<GitCommit>,
+    options: &GitScanOptions,
+) -> Vec<GitCommit> {
+    let outside_window = commits
+        .into_iter()
+        .filter(|commit| !options.contains(&commit.committer_date))
+        .collect();
+    merge_commits(outside_window, window_commits)
+}
+
+fn read_full_git_history(
+    repository: &Repository,
+    refs: Option<&[String]>,
+    scan_key: String,
+    options: &GitScanOptions,
+) -> Result<GitHistory, Box<dyn Error>> {
+    // only a complete read can be continued from next time
+    let scanned_commits = match options.is_windowed() {
+        true => vec![],
+        false => read_ref_tips(repository, refs)?,
+    };
+    let commits = read_git_log(repository, refs, &[], options)?;
+
+    Ok(GitHistory {
+        scan_key,
+        scanned_commits,
+        commits,
+    })
+}
+
+/// Read a repository's commits, starting from the ones stored at the last scan when possible.
+/// With the `in-process-git` feature they're read with libgit2, and git is only run for
+/// repositories libgit2 can't open
+pub fn read_git_history(
+    repository: &Repository,
+    options: &GitScanOptions,
+) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+    let scan_key = get_scan_key(repository);
+    let refs = read_selected_refs(repository)?;
+    let refs = refs.as_deref();
+    let repository_id = match repository.id.as_ref() {
+        Some(id) => id,
+        None => return Ok(read_full_git_history(repository, refs, scan_key, options)?.commits),
+    };
+
+    let stored_history = match options.full_rescan {
+        true => None,
+        false => db_reader::load_git_history(repository_id).unwrap_or_else(|err| {
+            eprintln!("Error reading stored git history: {}", err);
+            None
+        }),
+    }
+    .filter(|history| history.scan_key == scan_key);
+
+    let (history, new_commits) = match stored_history {
+        Some(stored) if options.is_windowed() => {
+            let window_commits = read_git_log(repository, refs, &[], options)?;
+            let history = GitHistory {
+                commits: replace_commits_in_window(stored.commits, window_commits, options),
+                ..stored
+            };
+            (history, None)
+        }
+        Some(stored) => {
+            let scanned_commits = read_ref_tips(repository, refs)?;
+            match read_git_log(repository, refs, &stored.scanned_commits, options) {
+                Ok(new_commits) => {
+                    let history = GitHistory {
+                        scan_key,
+                        scanned_commits,
+                        commits: merge_commits(stored.commits, new_commits.clone()),
+                    };
+                    (history, Some(new_commits))
+                }
+                // a commit from the last scan has gone, e.g. after a rebase, so start again
+                Err(_) => (
+                    read_full_git_history(repository, refs, scan_key, options)?,
+                    None,
+                ),
+            }
+        }
+        None => (
+            read_full_git_history(repository, refs, scan_key, options)?,
+            None,
+        ),
+    };
+
+    let saved = match new_commits.as_ref() {
+        Some(new_commits) => db_reader::add_to_git_history(repository_id, &history, new_commits),
+        None => db_reader::save_git_history(repository_id, &history),
+    };
+    if let Err(err) = saved {
+        eprintln!("Error storing git history: {}", err);
+    }
+
+    Ok(history.commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks::{self, git};
+
+    fn commit(hash: &str, committer_date: &str) -> GitCommit {
+        let date = DateTime::parse_from_rfc3339(committer_date).unwrap();
+        GitCommit {
+            hash: hash.to_string(),
+            author_name: "Jim Jones".to_string(),
+            author_email: "jim@jones.com".to_string(),
+            author_date: date,
+            committer_date: date,
+            subject: String::new(),
+            lines_changed: None,
+        }
+    }
+
+    fn hashes(commits: &[GitCommit]) -> Vec<&str> {
+        commits.iter().map(|commit| commit.hash.as_str()).collect()
+    }
+
+    #[test]
+    fn it_merges_new_commits_without_duplicates() {
+        let commits = vec![
+            commit("a", "2024-01-01T10:00:00+00:00"),
+            commit("b", "2024-01-02T10:00:00+00:00"),
+        ];
+        let new_commits = vec![
+            commit("c", "2024-01-03T10:00:00+00:00"),
+            commit("b", "2024-01-02T10:00:00+00:00"),
+        ];
+
+        assert_eq!(
+            hashes(&merge_commits(commits, new_commits)),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn it_replaces_commits_inside_the_scan_window() {
+        let options = GitScanOptions {
+            since: NaiveDate::from_ymd_opt(2024, 2, 1),
+            until: NaiveDate::from_ymd_opt(2024, 2, 29),
+            ..Default::default()
+        };
+        let commits = vec![
+            commit("january", "2024-01-15T12:00:00+00:00"),
+            commit("rebased", "2024-02-15T12:00:00+00:00"),
+            commit("march", "2024-03-15T12:00:00+00:00"),
+        ];
+        let window_commits = vec![commit("february", "2024-02-15T12:00:00+00:00")];
+
+        assert_eq!(
+            hashes(&replace_commits_in_window(
+                commits,
+                window_commits,
+                &options
+            )),
+            vec!["january", "march", "february"]
+        );
+        assert_eq!(
+            options.window_args(),
+            vec!["--since=2024-02-01T00:00:00", "--until=2024-02-29T23:59:59"]
+        );
+    }
+
+    #[test]
+    fn it_changes_the_scan_key_with_the_log_options() {
+        let mut repository = mocks::create_mock_repository();
+        let scan_key = get_scan_key(&repository);
+
+        repository.set_hours_split("lines".to_string());
+        assert_ne!(get_scan_key(&repository), scan_key);
+    }
+
+    #[test]
+    fn it_selects_refs_matching_the_ref_patterns() {
+        let refs: Vec<String> = [
+            "refs/heads/main",
+            "refs/heads/feature/login",
+            "refs/heads/experiment",
+            "refs/remotes/origin/main",
+            "refs/stash",
+        ]
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+        let patterns = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+
+        assert_eq!(
+            select_refs(&refs, &patterns(&["main", "feature/*"]), &[]),
+            vec!["refs/heads/main", "refs/heads/feature/login"]
+        );
+        assert_eq!(
+            select_refs(&refs, &[], &patterns(&["refs/stash", "exp*"])),
+            vec![
+                "refs/heads/main",
+                "refs/heads/feature/login",
+                "refs/remotes/origin/main"
+            ]
+        );
+        assert_eq!(
+            select_refs(&refs, &patterns(&["*main"]), &patterns(&["origin/*"])),
+            vec!["refs/heads/main"]
+        );
+    }
+
+    #[test]
+    fn it_only_reads_commits_added_since_the_last_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "first"],
+        );
+
+        let repository = Repository {
+            git_path: Some(dir.path().to_str().unwrap().to_string()),
+            name: Some("Jim Jones".to_string()),
+            ..Default::default()
+        };
+        let options = GitScanOptions::default();
+
+        let scanned_commits = read_ref_tips(&repository, None).unwrap();
+        let commits = read_git_log(&repository, None, &[], &options).unwrap();
+        assert_eq!(scanned_commits.len(), 1);
+        assert_eq!(commits.len(), 1);
+
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "second"],
+        );
+        let new_commits = read_git_log(&repository, None, &scanned_commits, &options).unwrap();
+        assert_eq!(new_commits.len(), 1);
+        assert_eq!(new_commits[0].subject, "second");
+
+        // only the selected refs are read
+        git(dir.path(), &["checkout", "-q", "-b", "experiment"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "third"],
+        );
+        let refs = vec!["refs/heads/experiment".to_string()];
+        assert_eq!(read_ref_tips(&repository, Some(&refs)).unwrap().len(), 1);
+        assert_eq!(
+            read_git_log(&repository, Some(&refs), &scanned_commits, &options)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(
+            read_git_log(&repository, Some(&[]), &[], &options)
+                .unwrap()
+                .is_empty()
+        );
+
+        // commits that no longer exist can't be continued from
+        let missing = vec!["0".repeat(40)];
+        assert!(read_git_log(&repository, None, &missing, &options).is_err());
+    }
+}
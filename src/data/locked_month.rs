@@ -0,0 +1,68 @@
+use chrono::{Datelike, Month, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// A month of a client's timesheet that has been invoiced or shared. Its hours are kept as
+/// they are rather than being worked out from the git history again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedMonth {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl LockedMonth {
+    pub fn new(year: i32, month: u32) -> Self {
+        LockedMonth { year, month }
+    }
+
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        date.year() == self.year && date.month() == self.month
+    }
+
+    /// The month in the same form as the rest of the prompts, e.g. "September, 2026"
+    pub fn get_month_year_string(&self) -> String {
+        let month = u8::try_from(self.month)
+            .ok()
+            .and_then(|month| Month::try_from(month).ok())
+            .map_or_else(|| self.month.to_string(), |month| month.name().to_string());
+
+        format!("{}, {}", month, self.year)
+    }
+
+    /// Error if any of the days fall in one of the locked months
+    pub fn check_days_are_unlocked(
+        locked_months: &[LockedMonth],
+        days: &[NaiveDate],
+    ) -> Result<(), Box<dyn Error>> {
+        match locked_months
+            .iter()
+            .find(|locked_month| days.iter().any(|date| locked_month.contains(date)))
+        {
+            Some(locked_month) => Err(format!(
+                "{} is locked. Run 'autolog unlock' to change it",
+                locked_month.get_month_year_string()
+            )
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_days_are_unlocked() {
+        let locked_months = vec![LockedMonth::new(2026, 9)];
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+
+        assert!(LockedMonth::check_days_are_unlocked(&locked_months, &[date(10, 1)]).is_ok());
+        assert_eq!(
+            LockedMonth::check_days_are_unlocked(&locked_months, &[date(8, 31), date(9, 1)])
+                .unwrap_err()
+                .to_string(),
+            "September, 2026 is locked. Run 'autolog unlock' to change it"
+        );
+    }
+}
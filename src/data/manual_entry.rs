@@ -0,0 +1,92 @@
+use chrono::{Datelike, NaiveDate};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// The line manual entries without a project are shown under on the timesheet
+pub const MANUAL_ENTRIES_NAMESPACE: &str = "Other work";
+
+/// Time worked for a client that doesn't show up in git, such as meetings, calls or design
+/// work. The project can be one of the client's repositories or any other name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManualEntry {
+    pub id: String,
+    pub date: NaiveDate,
+    pub hours: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl ManualEntry {
+    pub fn new(
+        date: NaiveDate,
+        hours: &str,
+        project: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let hours: f64 = hours
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' isn't a number of hours", hours))?;
+
+        if !(hours > 0.0 && hours <= 24.0) {
+            return Err("Hours must be more than 0 and no more than 24".into());
+        }
+
+        let non_empty = |value: Option<&str>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Ok(ManualEntry {
+            id: nanoid!(8),
+            date,
+            hours,
+            project: non_empty(project),
+            note: non_empty(note),
+        })
+    }
+
+    pub fn is_in_month(&self, year: i32, month: u32) -> bool {
+        self.date.year() == year && self.date.month() == month
+    }
+
+    /// The name of the line this entry is shown under on the timesheet
+    pub fn get_namespace(&self) -> &str {
+        self.project.as_deref().unwrap_or(MANUAL_ENTRIES_NAMESPACE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_creates_a_manual_entry() {
+        let date = NaiveDate::from_ymd_opt(2021, 10, 4).unwrap();
+        let entry = ManualEntry::new(date, "1.5", Some(" Design "), Some("")).unwrap();
+
+        assert_eq!(entry.id.len(), 8);
+        assert_eq!(entry.hours, 1.5);
+        assert_eq!(entry.project, Some("Design".to_string()));
+        assert_eq!(entry.note, None);
+        assert_eq!(entry.get_namespace(), "Design");
+        assert!(entry.is_in_month(2021, 10));
+        assert!(!entry.is_in_month(2021, 11));
+
+        let entry = ManualEntry::new(date, "2", None, Some("Kick-off call")).unwrap();
+        assert_eq!(entry.get_namespace(), MANUAL_ENTRIES_NAMESPACE);
+    }
+
+    #[test]
+    fn it_rejects_invalid_hours() {
+        let date = NaiveDate::from_ymd_opt(2021, 10, 4).unwrap();
+
+        assert!(ManualEntry::new(date, "two", None, None).is_err());
+        assert!(ManualEntry::new(date, "0", None, None).is_err());
+        assert!(ManualEntry::new(date, "25", None, None).is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod author_identity;
 pub mod client_repositories;
+pub mod git_commit;
+pub mod git_history;
+pub mod locked_month;
+pub mod manual_entry;
 pub mod repository;
+pub mod timesheet_history;
+pub mod vcs;
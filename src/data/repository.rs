@@ -1,16 +1,45 @@
+use crate::data::author_identity::AuthorIdentity;
+use crate::data::git_commit::{self, GitCommit};
+use crate::data::vcs::{self, VcsBackend, VcsUserDetails};
 use crate::utils::date::date_parser::{
-    DayMap, TimesheetYears, check_for_valid_day, check_for_valid_month, check_for_valid_year,
-    create_single_day_object,
+    CommitDay, TimesheetYears, check_for_valid_day, check_for_valid_month, check_for_valid_year,
+    create_single_day_object, get_days_from_month, parse_weekdays,
 };
-use chrono::{DateTime, Datelike};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::process;
-use std::process::{Command, Output};
+use std::process::Output;
 
 pub type GitLogDates = HashMap<i32, HashMap<u32, HashSet<u32>>>;
+pub type GitLogTimes = HashMap<NaiveDate, Vec<DateTime<FixedOffset>>>;
+pub type GitLogSubjects = HashMap<NaiveDate, Vec<String>>;
+
+/// Credit default_hours to each worked day, split between repositories worked on that day
+pub const HOURS_ESTIMATION_DEFAULT: &str = "default";
+/// Estimate the hours worked each day from the commit timestamps
+pub const HOURS_ESTIMATION_COMMITS: &str = "commits";
+
+/// Split a day's hours evenly between the repositories worked on that day
+pub const HOURS_SPLIT_EVEN: &str = "even";
+/// Split a day's hours in proportion to the number of commits made in each repository
+pub const HOURS_SPLIT_COMMITS: &str = "commits";
+/// Split a day's hours in proportion to the lines changed in each repository
+pub const HOURS_SPLIT_LINES: &str = "lines";
+/// Split a day's hours in proportion to the session time estimated from commit times
+pub const HOURS_SPLIT_SESSIONS: &str = "sessions";
+
+/// Reset the passed day, or the days from --from to --to
+pub const RESET_DAY: &str = "day";
+/// Reset every day in the month of the passed day
+pub const RESET_MONTH: &str = "month";
+/// Reset every day in the repository's timesheet
+pub const RESET_REPOSITORY: &str = "repo";
+
+const USER_EDITED: &str = "user_edited";
+const DESCRIPTION_EDITED: &str = "description_edited";
 
 /// Holds the data from the db. Config can access these values
 // and perform various operations on it
@@ -35,6 +64,25 @@ pub struct Repository {
     pub service: Option<String>,
     pub service_username: Option<String>,
     pub default_hours: Option<f64>,
+    pub hours_estimation: Option<String>,
+    pub commit_lead_in_minutes: Option<i64>,
+    pub commit_session_gap_minutes: Option<i64>,
+    pub hours_split: Option<String>,
+    pub hours_rounding: Option<String>,
+    pub timezone: Option<String>,
+    pub day_start_hour: Option<u32>,
+    pub author_identities: Option<Vec<AuthorIdentity>>,
+    pub use_mailmap: Option<bool>,
+    pub included_refs: Option<Vec<String>>,
+    pub excluded_refs: Option<Vec<String>>,
+    pub vcs: Option<String>,
+    // commit activity is only needed while the timesheet is being generated, so isn't stored
+    #[serde(skip)]
+    pub git_log_times: Option<GitLogTimes>,
+    #[serde(skip)]
+    pub git_log_lines_changed: Option<HashMap<NaiveDate, u64>>,
+    #[serde(skip)]
+    pub git_log_subjects: Option<GitLogSubjects>,
 }
 
 struct Iter<'a> {
@@ -92,6 +140,120 @@ impl Repository {
         self
     }
 
+    pub fn set_hours_estimation(
+        &mut self,
+        mode: String,
+        lead_in_minutes: Option<i64>,
+        session_gap_minutes: Option<i64>,
+    ) -> &mut Self {
+        self.hours_estimation = Option::from(mode);
+        self.commit_lead_in_minutes = lead_in_minutes;
+        self.commit_session_gap_minutes = session_gap_minutes;
+        self
+    }
+
+    pub fn set_hours_split(&mut self, value: String) -> &mut Self {
+        self.hours_split = Option::from(value);
+        self
+    }
+
+    pub fn set_hours_rounding(&mut self, value: String) -> &mut Self {
+        self.hours_rounding = Option::from(value);
+        self
+    }
+
+    pub fn set_day_boundary(&mut self, timezone: Option<String>, day_start_hour: u32) -> &mut Self {
+        self.timezone = timezone;
+        self.day_start_hour = Option::from(day_start_hour);
+        self
+    }
+
+    pub fn set_author_identities(
+        &mut self,
+        identities: Vec<AuthorIdentity>,
+        use_mailmap: bool,
+    ) -> &mut Self {
+        self.author_identities = Option::from(identities);
+        self.use_mailmap = Option::from(use_mailmap);
+        self
+    }
+
+    pub fn uses_mailmap(&self) -> bool {
+        self.use_mailmap.unwrap_or(false)
+    }
+
+    /// Patterns such as `main`, `feature/*` or `refs/stash` choosing which refs history is read from.
+    /// Without any, history is read from every ref like `git log --all`
+    pub fn set_ref_patterns(&mut self, included: Vec<String>, excluded: Vec<String>) -> &mut Self {
+        self.included_refs = Some(included).filter(|patterns| !patterns.is_empty());
+        self.excluded_refs = Some(excluded).filter(|patterns| !patterns.is_empty());
+        self
+    }
+
+    pub fn set_vcs(&mut self, value: String) -> &mut Self {
+        self.vcs = Option::from(value);
+        self
+    }
+
+    /// Repositories added before other backends were supported are read with git
+    pub fn get_vcs_backend(&self) -> &'static dyn VcsBackend {
+        vcs::get_backend(self.vcs.as_deref())
+    }
+
+    /// Projects added without a git repository only have the days logged against them by hand
+    pub fn is_git_repository(&self) -> bool {
+        self.git_path.is_some()
+    }
+
+    pub fn has_ref_patterns(&self) -> bool {
+        self.included_refs.is_some() || self.excluded_refs.is_some()
+    }
+
+    /// Whether the commit was made under the user's git name or email, or any of their other
+    /// identities. Every backend reads all of the commits and matches them here, so a name
+    /// always has to match exactly rather than as git's `--author` pattern. Without a name,
+    /// email or identity every commit is kept
+    pub fn is_authored_by_user(&self, commit: &GitCommit) -> bool {
+        let is_set = |value: &Option<String>| value.as_ref().is_some_and(|x| !x.trim().is_empty());
+        if !is_set(&self.name)
+            && !is_set(&self.email)
+            && self.author_identities.iter().flatten().next().is_none()
+        {
+            return true;
+        }
+
+        let name_matches = self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.trim() == commit.author_name.trim());
+        let email_matches = self.email.as_ref().is_some_and(|email| {
+            !email.trim().is_empty()
+                && email
+                    .trim()
+                    .eq_ignore_ascii_case(commit.author_email.trim())
+        });
+
+        name_matches
+            || email_matches
+            || self
+                .author_identities
+                .iter()
+                .flatten()
+                .any(|identity| identity.matches_commit(commit))
+    }
+
+    pub fn get_commit_day(&self) -> CommitDay {
+        CommitDay::new(self.timezone.as_deref(), self.day_start_hour)
+    }
+
+    pub fn splits_hours_by_lines_changed(&self) -> bool {
+        self.hours_split.as_deref() == Some(HOURS_SPLIT_LINES)
+    }
+
+    pub fn estimates_hours_from_commits(&self) -> bool {
+        self.hours_estimation.as_deref() == Some(HOURS_ESTIMATION_COMMITS)
+    }
+
     /// Get values from buffer and set these to the Repository struct fields
     pub fn set_values_from_buffer(&mut self, repository: &Repository) -> &mut Repository {
         *self = repository.clone();
@@ -173,42 +335,55 @@ impl Repository {
         self
     }
 
+    pub fn set_git_log_times(&mut self, value: GitLogTimes) -> &mut Self {
+        self.git_log_times = Option::from(value);
+        self
+    }
+
+    pub fn get_commit_subjects(&self, year: i32, month: u32, day: u32) -> Option<&Vec<String>> {
+        let date = NaiveDate::from_ymd_opt(year, month, day)?;
+        self.git_log_subjects.as_ref()?.get(&date)
+    }
+
+    pub fn get_commit_times(
+        &self,
+        year: i32,
+        month: u32,
+        day: u32,
+    ) -> Option<&Vec<DateTime<FixedOffset>>> {
+        let date = NaiveDate::from_ymd_opt(year, month, day)?;
+        self.git_log_times.as_ref()?.get(&date)
+    }
+
     pub fn find_namespace_from_git_path(
         &mut self,
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
-        let reg = regex::Regex::new(r"(?P<namespace>[^/][\w\d()_\-,.]+)/\.git/")?;
+        let reg = regex::Regex::new(r"(?P<namespace>[^/][\w\d()_\-,.]+)/\.(?:git|hg|jj)/")?;
+        let git_path = self.git_path.clone().unwrap_or_default();
 
-        match reg.captures(self.git_path.clone().unwrap().as_str()) {
-            None => {
-                println!("No repositories found at path. Please check that the path is valid.");
-                process::exit(exitcode::DATAERR);
-            }
-            Some(cap) => match cap.name("namespace") {
-                None => {
-                    println!("No matches found for project namespace");
-                    process::exit(exitcode::DATAERR);
-                }
-                Some(capture) => {
-                    self.set_namespace(capture.as_str().parse().unwrap());
-                }
-            },
-        }
+        // callers decide what to do when there isn't a git repository,
+        // as projects can be added without one
+        let capture = reg
+            .captures(&git_path)
+            .ok_or("No repositories found at path. Please check that the path is valid.")?
+            .name("namespace")
+            .ok_or("No matches found for project namespace")?;
+
+        self.set_namespace(capture.as_str().to_string());
 
         Ok(self)
     }
 
+    /// Find the repository containing repo_path, with whichever backend recognises it
     pub fn find_git_path_from_directory_from(
         &mut self,
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
-        let output_path = Command::new("git")
-            .arg("-C")
-            .arg(&self.repo_path.clone().unwrap())
-            .arg("rev-parse")
-            .arg("--show-toplevel")
-            .output()
-            .expect("Failed to find 'git_path'");
+        let repo_path = self.repo_path.clone().unwrap_or_default();
+        let (backend, root) = vcs::find_backend(&repo_path)
+            .ok_or("No git, Mercurial or Jujutsu repository found at path.")?;
 
-        self.find_git_path_from_directory(output_path)?;
+        self.set_vcs(backend.name().to_string());
+        self.set_git_path_from_root(&root);
 
         Ok(self)
     }
@@ -219,39 +394,27 @@ impl Repository {
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
         let path_string: String = crate::utils::trim_output_from_utf8(output_path)?;
 
-        self.set_git_path(path_string.to_owned() + &*String::from("/.git/").to_owned());
+        self.set_git_path_from_root(&path_string);
 
         Ok(self)
     }
 
+    /// The path stored is the backend's data directory, e.g. `/rust/autolog/.git/`
+    pub fn set_git_path_from_root(&mut self, root: &str) -> &mut Self {
+        let data_dir = self.get_vcs_backend().data_dir();
+        self.set_git_path(format!("{}/{}/", root, data_dir))
+    }
+
     pub fn find_repository_details_from(
         &mut self,
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
-        let output_name = Command::new("git")
-            .arg("-C")
-            .arg(&self.repo_path.clone().unwrap())
-            .arg("config")
-            .arg("user.name")
-            .output()
-            .expect("Failed to find 'user.name'");
-
-        let output_email = Command::new("git")
-            .arg("-C")
-            .arg(&self.repo_path.clone().unwrap())
-            .arg("config")
-            .arg("user.email")
-            .output()
-            .expect("Failed to find 'user.email'");
-
-        let repository_service = Command::new("git")
-            .arg("-C")
-            .arg(&self.repo_path.clone().unwrap())
-            .arg("remote")
-            .arg("-v")
-            .output()
-            .expect("Failed to find 'user.email'");
-
-        self.find_repository_details(output_name, output_email, repository_service)?;
+        self.find_git_path_from_directory_from()?;
+
+        let details = self
+            .get_vcs_backend()
+            .read_user_details(&self.repo_path.clone().unwrap())?;
+
+        self.find_repository_details(details)?;
 
         Ok(self)
     }
@@ -302,37 +465,50 @@ impl Repository {
 
     pub fn find_repository_details(
         &mut self,
-        output_name: Output,
-        output_email: Output,
-        output_service: Output,
+        details: VcsUserDetails,
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
-        let service = crate::utils::trim_output_from_utf8(output_service)?;
-        let name = crate::utils::trim_output_from_utf8(output_name)?;
-        let email = crate::utils::trim_output_from_utf8(output_email)?;
+        self.find_service_data_from_output(details.remotes)?;
+        self.set_name(details.name);
+        self.set_email(details.email);
 
-        self.find_service_data_from_output(service)?;
-        self.set_name(name);
-        self.set_email(email);
-
-        self.find_git_path_from_directory_from()?
-            .find_namespace_from_git_path()?;
+        self.find_namespace_from_git_path()?;
 
         Ok(self)
     }
 
+    /// Read the commits from structured `git log` output, see GIT_LOG_FORMAT
     pub fn parse_git_log_dates_from_git_history(&mut self, git_history: String) {
-        let mut year_month_map: GitLogDates = HashMap::new();
+        self.set_values_from_git_log(git_commit::parse_git_log(&git_history));
+    }
 
-        let regex = regex::Regex::new(
-            r"([a-zA-Z]{3}),\s(?P<day>\d{1,2})\s(?P<month>[a-zA-Z]{3})\s(?P<year>\d{4})\s(\d+:?){3}\s([+-]?\d{4})",
-        )
-            .unwrap();
+    /// Keep the commits made by the user, and work out the days they were made on
+    pub fn set_values_from_git_log(&mut self, mut commits: Vec<GitCommit>) -> &mut Self {
+        commits.retain(|commit| self.is_authored_by_user(commit));
+        self.set_values_from_commits(&commits)
+    }
 
-        for cap in regex.captures_iter(&git_history) {
+    /// Work out the days, times, lines changed and commit subjects from the commits
+    pub fn set_values_from_commits(&mut self, commits: &[GitCommit]) -> &mut Self {
+        let mut year_month_map: GitLogDates = HashMap::new();
+        let mut commit_times: GitLogTimes = HashMap::new();
+        let mut lines_changed: HashMap<NaiveDate, u64> = HashMap::new();
+        let mut subjects: HashMap<NaiveDate, Vec<(DateTime<FixedOffset>, &str)>> = HashMap::new();
+        let commit_day = self.get_commit_day();
+
+        for commit in commits {
             // for each year insert the entry
             // if the value is empty, insert a new hashset, or insert a month into the hashset
-            let date_time = DateTime::parse_from_rfc2822(&cap[0]);
-            let date = date_time.unwrap().date_naive();
+            let date_time = commit_day.local_time(commit.author_date);
+            let date = commit_day.date(date_time);
+            commit_times.entry(date).or_default().push(date_time);
+            subjects
+                .entry(date)
+                .or_default()
+                .push((date_time, commit.subject.trim()));
+
+            if let Some(lines) = commit.lines_changed {
+                *lines_changed.entry(date).or_insert(0) += lines;
+            }
 
             let year = date.year();
             let month = date.month();
@@ -361,6 +537,29 @@ impl Repository {
         }
 
         self.set_git_log_dates(year_month_map);
+        self.set_git_log_times(commit_times);
+
+        // subjects are listed in the order the commits were made, once each
+        let subjects: GitLogSubjects = subjects
+            .into_iter()
+            .map(|(date, mut day_subjects)| {
+                day_subjects.sort();
+                let mut unique: Vec<String> = vec![];
+                for (_, subject) in day_subjects {
+                    if !subject.is_empty() && !unique.iter().any(|x| x == subject) {
+                        unique.push(subject.to_string());
+                    }
+                }
+                (date, unique)
+            })
+            .collect();
+        self.git_log_subjects = Option::from(subjects);
+
+        if self.splits_hours_by_lines_changed() {
+            self.git_log_lines_changed = Option::from(lines_changed);
+        }
+
+        self
     }
 
     pub fn mutate_timesheet_entry(
@@ -368,7 +567,7 @@ impl Repository {
         year_string: &String,
         month_u32: &u32,
         day: usize,
-        entry: DayMap,
+        entry: impl IntoIterator<Item = (String, Value)>,
     ) -> Result<&mut Self, Box<dyn std::error::Error>> {
         self.timesheet
             .as_mut()
@@ -408,9 +607,14 @@ impl Repository {
         let month_u32 = check_for_valid_month(&options[3])?;
         let day_string = check_for_valid_day(&options[2], month_u32, year_string.parse().unwrap())?;
 
-        let hour: f64 = options[1].as_ref().unwrap().parse()?;
         let day: usize = day_string.parse()?;
 
+        // only the description might be being edited
+        let hour: f64 = match Self::read_edited_hours(options)? {
+            Some(hour) => hour,
+            None => return Ok(self),
+        };
+
         let is_weekend =
             match self.get_timesheet_entry(year_string, &month_u32, day, "weekend".to_string()) {
                 Ok(result) => result.unwrap(),
@@ -430,12 +634,241 @@ impl Repository {
 
         Ok(self)
     }
+
+    /// The hours passed to edit, which are zero when the days are being cleared
+    fn read_edited_hours(
+        options: &[Option<String>],
+    ) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+        if options.get(9).cloned().flatten().is_some() {
+            return Ok(Some(0.0));
+        }
+
+        match options[1].as_ref() {
+            Some(hour) => Ok(Some(hour.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The days from edit's `--from` date to its `--to` date, which defaults to the first
+    fn read_date_range(
+        options: &[Option<String>],
+    ) -> Result<Option<(NaiveDate, NaiveDate)>, Box<dyn std::error::Error>> {
+        let parse_date = |date: &String| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| format!("'{}' isn't a date in the form yyyy-mm-dd", date))
+        };
+
+        let from = match options.get(6).cloned().flatten() {
+            Some(from) => parse_date(&from)?,
+            None => return Ok(None),
+        };
+        let to = match options.get(7).cloned().flatten() {
+            Some(to) => parse_date(&to)?,
+            None => from,
+        };
+        if to < from {
+            return Err("The --to date can't be before the --from date".into());
+        }
+
+        Ok(Some((from, to)))
+    }
+
+    /// Set the hours on each day from the `from` date to the `to` date. When weekdays are
+    /// passed only those days are changed, otherwise weekends are left as they are unless
+    /// the range is being cleared. Every day changed is marked as edited by the user.
+    pub fn update_hours_on_date_range(
+        &mut self,
+        options: &[Option<String>],
+    ) -> Result<&mut Self, Box<dyn std::error::Error>> {
+        let (from, to) = match Self::read_date_range(options)? {
+            Some(range) => range,
+            None => return Ok(self),
+        };
+
+        let hour = Self::read_edited_hours(options)?.ok_or("Hours not found")?;
+        let is_clearing = options.get(9).cloned().flatten().is_some();
+        let weekdays = match options.get(8).cloned().flatten() {
+            Some(weekdays) => Some(parse_weekdays(&weekdays)?),
+            None => None,
+        };
+
+        // find every day first, so a day without timesheet data doesn't leave the range half edited
+        let mut days: Vec<(NaiveDate, bool)> = vec![];
+        for date in from.iter_days().take_while(|date| date <= &to) {
+            if let Some(weekdays) = weekdays.as_ref()
+                && !weekdays.contains(&date.weekday())
+            {
+                continue;
+            }
+
+            let is_weekend = self
+                .get_timesheet_entry(
+                    &date.year().to_string(),
+                    &date.month(),
+                    date.day() as usize,
+                    "weekend".to_string(),
+                )?
+                .and_then(Value::as_bool)
+                .ok_or_else(|| format!("No timesheet data found for {}", date))?;
+
+            if is_weekend && weekdays.is_none() && !is_clearing {
+                continue;
+            }
+
+            days.push((date, is_weekend));
+        }
+
+        for (date, is_weekend) in days {
+            self.mutate_timesheet_entry(
+                &date.year().to_string(),
+                &date.month(),
+                date.day() as usize,
+                create_single_day_object(is_weekend, hour, true),
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    /// The days an edit changes: the passed day, or the days from `--from` to `--to`
+    pub fn find_days_to_edit(
+        options: &[Option<String>],
+    ) -> Result<Vec<NaiveDate>, Box<dyn std::error::Error>> {
+        if let Some((from, to)) = Self::read_date_range(options)? {
+            return Ok(from.iter_days().take_while(|date| date <= &to).collect());
+        }
+
+        let year_string = check_for_valid_year(&options[4])?;
+        let month_u32 = check_for_valid_month(&options[3])?;
+        let year: i32 = year_string.parse()?;
+        let day = check_for_valid_day(&options[2], month_u32, year)?.parse()?;
+        Ok(NaiveDate::from_ymd_opt(year, month_u32, day)
+            .into_iter()
+            .collect())
+    }
+
+    /// The days chosen by edit's `--reset`: the passed day, the days from `--from` to `--to`,
+    /// the whole month of the passed day, or every day in the repository's timesheet
+    pub fn find_days_to_reset(
+        &self,
+        options: &[Option<String>],
+    ) -> Result<Vec<NaiveDate>, Box<dyn std::error::Error>> {
+        let scope = options
+            .get(10)
+            .cloned()
+            .flatten()
+            .ok_or("Reset not found")?;
+        let range = Self::read_date_range(options)?;
+
+        let days = match (scope.as_str(), range) {
+            (RESET_DAY, _) => Self::find_days_to_edit(options)?,
+            (_, Some(_)) => return Err("Only a day can be reset with --from and --to".into()),
+            (RESET_MONTH, None) => {
+                let year: i32 = check_for_valid_year(&options[4])?.parse()?;
+                let month_u32 = check_for_valid_month(&options[3])?;
+                (1..=get_days_from_month(year, month_u32))
+                    .filter_map(|day| NaiveDate::from_ymd_opt(year, month_u32, day))
+                    .collect()
+            }
+            (RESET_REPOSITORY, None) => {
+                let mut days = vec![];
+                for (year, months) in self.timesheet.iter().flatten() {
+                    for (month, month_days) in months {
+                        let (year, month): (i32, u32) = (year.parse()?, month.parse()?);
+                        days.extend(
+                            (1..=month_days.len() as u32)
+                                .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day)),
+                        );
+                    }
+                }
+                days.sort();
+                days
+            }
+            (scope, None) => return Err(format!("'{}' can't be reset", scope).into()),
+        };
+
+        Ok(days)
+    }
+
+    /// Forget the user's hours and description on each of the days, so they're worked out
+    /// from the git history again. Returns the days that had been edited
+    pub fn reset_edited_days(&mut self, days: &[NaiveDate]) -> Vec<NaiveDate> {
+        let mut reset_days = vec![];
+
+        for date in days {
+            let day_map = self
+                .timesheet
+                .as_mut()
+                .and_then(|timesheet| timesheet.get_mut(&date.year().to_string()))
+                .and_then(|months| months.get_mut(&date.month().to_string()))
+                .and_then(|month| month.get_mut(date.day0() as usize));
+
+            if let Some(day_map) = day_map {
+                let is_edited = [USER_EDITED, DESCRIPTION_EDITED]
+                    .iter()
+                    .any(|flag| day_map.get(*flag) == Some(&Value::Bool(true)));
+
+                if is_edited {
+                    day_map.insert(USER_EDITED.to_string(), Value::Bool(false));
+                    day_map.remove(DESCRIPTION_EDITED);
+                    reset_days.push(*date);
+                }
+            }
+        }
+
+        reset_days
+    }
+
+    /// The hours on a day of the timesheet
+    pub fn get_hours_on_day(&self, date: &NaiveDate) -> Option<f64> {
+        self.get_timesheet_entry(
+            &date.year().to_string(),
+            &date.month(),
+            date.day() as usize,
+            "hours".to_string(),
+        )
+        .ok()
+        .flatten()
+        .and_then(Value::as_f64)
+    }
+
+    /// Replace the description written from the day's commit subjects with the user's own
+    pub fn update_description_on_month_day_entry(
+        &mut self,
+        options: &[Option<String>],
+    ) -> Result<&mut Self, Box<dyn std::error::Error>> {
+        let description = match options.get(5).cloned().flatten() {
+            Some(description) => description,
+            None => return Ok(self),
+        };
+
+        let year_string = check_for_valid_year(&options[4])?;
+        let month_u32 = check_for_valid_month(&options[3])?;
+        let day_string = check_for_valid_day(&options[2], month_u32, year_string.parse().unwrap())?;
+        let day: usize = day_string.parse()?;
+
+        self.mutate_timesheet_entry(
+            year_string,
+            &month_u32,
+            day,
+            [
+                (
+                    "description".to_string(),
+                    Value::String(description.trim().to_string()),
+                ),
+                ("description_edited".to_string(), Value::Bool(true)),
+            ],
+        )?;
+
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::helpers::mocks;
+    use crate::utils::date::date_parser::is_weekend;
     use serde_json::{Map, Number, json};
     use std::os::unix::process::ExitStatusExt;
     use std::process::ExitStatus;
@@ -539,6 +972,177 @@ origin  git@github.com:daveymoores/autolog.git (push)\
         );
     }
 
+    #[test]
+    fn it_updates_hours_on_a_date_range() {
+        let mut ts = Repository {
+            ..Default::default()
+        };
+
+        // 2021-11-01 is a Monday
+        let november: Vec<Map<String, Value>> = (1..=30)
+            .map(|day| {
+                let mut map = Map::new();
+                map.extend(create_single_day_object(
+                    is_weekend(&(2021, 11, 30), day),
+                    8.0,
+                    false,
+                ));
+                map
+            })
+            .collect();
+        ts.set_timesheet(HashMap::from([(
+            "2021".to_string(),
+            HashMap::from([("11".to_string(), november)]),
+        )]));
+
+        let range = |hour: Option<&str>, weekdays: Option<&str>, clear: bool| {
+            let mut options = vec![None; 10];
+            options[1] = hour.map(String::from);
+            options[6] = Some("2021-11-02".to_string());
+            options[7] = Some("2021-11-08".to_string());
+            options[8] = weekdays.map(String::from);
+            options[9] = clear.then(|| "clear".to_string());
+            options
+        };
+        let get_entry = |ts: &Repository, day: usize, entry: &str| {
+            ts.get_timesheet_entry(&"2021".to_string(), &11, day, entry.to_string())
+                .unwrap()
+                .cloned()
+                .unwrap()
+        };
+
+        ts.update_hours_on_date_range(&range(Some("6"), None, false))
+            .unwrap();
+        assert_eq!(get_entry(&ts, 1, "hours"), json!(8.0));
+        assert_eq!(get_entry(&ts, 1, "user_edited"), json!(false));
+        assert_eq!(get_entry(&ts, 2, "hours"), json!(6.0));
+        assert_eq!(get_entry(&ts, 2, "user_edited"), json!(true));
+        // weekends are left as they were
+        assert_eq!(get_entry(&ts, 6, "hours"), json!(8.0));
+        assert_eq!(get_entry(&ts, 8, "hours"), json!(6.0));
+
+        ts.update_hours_on_date_range(&range(Some("4"), Some("tue,sun"), false))
+            .unwrap();
+        assert_eq!(get_entry(&ts, 2, "hours"), json!(4.0));
+        assert_eq!(get_entry(&ts, 3, "hours"), json!(6.0));
+        assert_eq!(get_entry(&ts, 7, "hours"), json!(4.0));
+
+        ts.update_hours_on_date_range(&range(None, None, true))
+            .unwrap();
+        for day in 2..=8 {
+            assert_eq!(get_entry(&ts, day, "hours"), json!(0.0));
+            assert_eq!(get_entry(&ts, day, "user_edited"), json!(true));
+        }
+
+        // a range running past the timesheet data isn't edited at all
+        let mut options = range(Some("6"), None, false);
+        options[7] = Some("2021-12-01".to_string());
+        assert!(ts.update_hours_on_date_range(&options).is_err());
+        for day in 2..=30 {
+            assert_ne!(get_entry(&ts, day, "hours"), json!(6.0));
+        }
+        options[6] = Some("2021-12-02".to_string());
+        assert!(ts.update_hours_on_date_range(&options).is_err());
+    }
+
+    #[test]
+    fn it_resets_edited_days() {
+        let mut ts = Repository {
+            ..Default::default()
+        };
+        ts.set_timesheet(mocks::get_mock_year_map());
+        ts.mutate_timesheet_entry(
+            &"2021".to_string(),
+            &11,
+            2,
+            [("description_edited".to_string(), Value::Bool(true))],
+        )
+        .unwrap();
+
+        let mut options = vec![None; 11];
+        options[2] = Some("1".to_string());
+        options[3] = Some("11".to_string());
+        options[4] = Some("2021".to_string());
+        options[10] = Some(RESET_DAY.to_string());
+        let first = NaiveDate::from_ymd_opt(2021, 11, 1).unwrap();
+        let second = NaiveDate::from_ymd_opt(2021, 11, 2).unwrap();
+        assert_eq!(ts.find_days_to_reset(&options).unwrap(), vec![first]);
+
+        options[6] = Some("2021-10-31".to_string());
+        options[7] = Some("2021-11-02".to_string());
+        assert_eq!(ts.find_days_to_reset(&options).unwrap().len(), 3);
+
+        options[10] = Some(RESET_MONTH.to_string());
+        assert!(ts.find_days_to_reset(&options).is_err());
+        options[6] = None;
+        assert_eq!(ts.find_days_to_reset(&options).unwrap().len(), 30);
+
+        options[10] = Some(RESET_REPOSITORY.to_string());
+        let days = ts.find_days_to_reset(&options).unwrap();
+        assert_eq!(days, vec![first, second]);
+
+        assert_eq!(ts.reset_edited_days(&days), vec![first, second]);
+        assert_eq!(
+            ts.get_timesheet_entry(&"2021".to_string(), &11, 1, "user_edited".to_string())
+                .unwrap(),
+            Some(&Value::Bool(false))
+        );
+        assert_eq!(
+            ts.get_timesheet_entry(
+                &"2021".to_string(),
+                &11,
+                2,
+                "description_edited".to_string()
+            )
+            .unwrap(),
+            None
+        );
+        assert_eq!(ts.get_hours_on_day(&first), Some(0.0));
+        // days that haven't been edited aren't reset again
+        assert!(ts.reset_edited_days(&days).is_empty());
+    }
+
+    #[test]
+    fn it_updates_a_description() {
+        let mut ts = Repository {
+            ..Default::default()
+        };
+
+        let year_map = mocks::get_mock_year_map();
+        ts.set_timesheet(year_map);
+        ts.update_hours_on_month_day_entry(&vec![
+            None,
+            None,
+            Some("2".to_string()),
+            Some("11".to_string()),
+            Some("2021".to_string()),
+            Some(" Wrote the release notes ".to_string()),
+        ])
+        .unwrap()
+        .update_description_on_month_day_entry(&vec![
+            None,
+            None,
+            Some("2".to_string()),
+            Some("11".to_string()),
+            Some("2021".to_string()),
+            Some(" Wrote the release notes ".to_string()),
+        ])
+        .unwrap();
+
+        let get_entry = |entry: &str| {
+            ts.get_timesheet_entry(&"2021".to_string(), &11, 2, entry.to_string())
+                .unwrap()
+                .cloned()
+        };
+        assert_eq!(
+            get_entry("description"),
+            Some(json!("Wrote the release notes"))
+        );
+        assert_eq!(get_entry("description_edited"), Some(json!(true)));
+        // the hours are left as they were
+        assert_eq!(get_entry("hours"), Some(json!(0.0)));
+    }
+
     #[test]
     fn it_sets_values_from_buffer() {
         let mut timesheet = Repository {
@@ -629,48 +1233,45 @@ origin  git@github.com:daveymoores/autolog.git (push)\
             ..Default::default()
         };
 
-        let std_output = "commit c2c1354f6e73073f6eb9a2273c550a38f0e624d7
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Sat, 23 Oct 2021 13:02:36 +0200
-
-    getting month, year and number of days in month from date string
-
-commit bad43e994462238b0470fae8c5af6f1f7d544e18 (origin/feature/redirect-to-onboarding, feature/redirect-to-onboarding)
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Thu, 21 Oct 2021 10:06:14 +0200
-
-    testing that it writes to the config file
-
-commit 6604ce77b0dce8f842ea72ca52b3d39212668389
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Wed, 20 Oct 2021 12:09:16 +0200
-
-    write data to file
-
-commit 9bc3e9720963d6aa06c1fd64cf826c8a0a6570a4
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Wed, 20 Oct 2021 11:06:17 +0200
-
-    initialise if config isn't found
-
-commit 9bc3e9720963d6aa06c1fd64cf826c8a0a6570a4
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Wed, 08 Sep 2021 11:06:17 +0200
-
-    initialise if config isn't found
-
-commit 9bc3e9720963d6aa06c1fd64cf826c8a0a6570a4
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Sat, 1 Aug 2020 11:06:17 +0200
-
-    initialise if config isn't found
-
-commit 9bc3e9720963d6aa06c1fd64cf826c8a0a6570a4
-Author: Davey Moores <daveymoores@gmail.com>
-Date:   Thu, 3 Jan 2019 11:06:17 +0200
-
-    initialise if config isn't found
-".to_string();
+        let std_output = [
+            mocks::create_mock_git_log_record(
+                "Sat, 23 Oct 2021 13:02:36 +0200",
+                "getting month, year and number of days in month from date string",
+                None,
+            ),
+            mocks::create_mock_git_log_record(
+                "Thu, 21 Oct 2021 10:06:14 +0200",
+                "testing that it writes to the config file",
+                None,
+            ),
+            mocks::create_mock_git_log_record(
+                "Wed, 20 Oct 2021 12:09:16 +0200",
+                "write data to file",
+                None,
+            ),
+            // dates in commit messages are ignored
+            mocks::create_mock_git_log_record(
+                "Wed, 20 Oct 2021 11:06:17 +0200",
+                "revert to Date:   Fri, 1 Jan 2021 10:00:00 +0000",
+                None,
+            ),
+            mocks::create_mock_git_log_record(
+                "Wed, 08 Sep 2021 11:06:17 +0200",
+                "initialise if config isn't found",
+                None,
+            ),
+            mocks::create_mock_git_log_record(
+                "Sat, 1 Aug 2020 11:06:17 +0200",
+                "initialise if config isn't found",
+                None,
+            ),
+            mocks::create_mock_git_log_record(
+                "Thu, 3 Jan 2019 11:06:17 +0200",
+                "initialise if config isn't found",
+                None,
+            ),
+        ]
+        .concat();
 
         timesheet.parse_git_log_dates_from_git_history(std_output);
         let x = timesheet.git_log_dates.unwrap();
@@ -697,6 +1298,123 @@ Date:   Thu, 3 Jan 2019 11:06:17 +0200
         assert_eq!(k, expected_array);
     }
 
+    #[test]
+    fn it_collects_commit_times_from_git_history() {
+        let mut repository = Repository {
+            ..Default::default()
+        };
+
+        let std_output = mocks::create_mock_git_log(&[
+            "Thu, 21 Oct 2021 10:06:14 +0200",
+            "Thu, 21 Oct 2021 16:40:02 +0200",
+            "Wed, 20 Oct 2021 12:09:16 +0200",
+        ]);
+
+        repository.parse_git_log_dates_from_git_history(std_output);
+
+        assert_eq!(repository.get_commit_times(2021, 10, 21).unwrap().len(), 2);
+        assert_eq!(repository.get_commit_times(2021, 10, 20).unwrap().len(), 1);
+        assert!(repository.get_commit_times(2021, 10, 19).is_none());
+    }
+
+    #[test]
+    fn it_moves_commits_past_the_day_boundary_to_the_previous_day() {
+        let mut repository = Repository {
+            ..Default::default()
+        };
+        repository.set_day_boundary(Some("Europe/London".to_string()), 4);
+
+        repository.parse_git_log_dates_from_git_history(mocks::create_mock_git_log(&[
+            "Tue, 2 Nov 2021 03:30:00 +0100",
+        ]));
+
+        assert!(repository.get_commit_times(2021, 11, 1).is_some());
+        assert!(repository.get_commit_times(2021, 11, 2).is_none());
+        assert!(repository.git_log_dates.unwrap()[&2021][&11].contains(&1));
+    }
+
+    #[test]
+    fn it_includes_commits_from_other_author_identities() {
+        let mut repository = Repository {
+            name: Option::from("Jim Jones".to_string()),
+            email: Option::from("jim@jones.com".to_string()),
+            ..Default::default()
+        };
+        repository.set_author_identities(
+            AuthorIdentity::parse_lines("jim@work.com\n/^James /").unwrap(),
+            false,
+        );
+
+        let record = |date: &str, name: &str, email: &str| {
+            mocks::create_mock_git_log_record(date, "commit", None)
+                .replace("Jim Jones", name)
+                .replace("jim@jones.com", email)
+        };
+        let std_output = [
+            record(
+                "Mon, 1 Nov 2021 09:00:00 +0000",
+                "Jim Jones",
+                "jim@jones.com",
+            ),
+            record("Tue, 2 Nov 2021 09:00:00 +0000", "Jim", "jim@work.com"),
+            record(
+                "Wed, 3 Nov 2021 09:00:00 +0000",
+                "James Jones",
+                "james@home.com",
+            ),
+            record(
+                "Thu, 4 Nov 2021 09:00:00 +0000",
+                "Someone Else",
+                "someone@else.com",
+            ),
+        ]
+        .concat();
+
+        repository.parse_git_log_dates_from_git_history(std_output);
+
+        let days = &repository.git_log_dates.unwrap()[&2021][&11];
+        assert_eq!(days, &HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn it_collects_lines_changed_from_git_history() {
+        let mut repository = Repository {
+            hours_split: Option::from(HOURS_SPLIT_LINES.to_string()),
+            ..Default::default()
+        };
+
+        let std_output = [
+            mocks::create_mock_git_log_record(
+                "Thu, 21 Oct 2021 10:06:14 +0200",
+                "testing that it writes to the config file",
+                Some("2 files changed, 10 insertions(+), 3 deletions(-)"),
+            ),
+            mocks::create_mock_git_log_record(
+                "Thu, 21 Oct 2021 16:40:02 +0200",
+                "remove a file",
+                Some("1 file changed, 4 deletions(-)"),
+            ),
+            mocks::create_mock_git_log_record(
+                "Wed, 20 Oct 2021 12:09:16 +0200",
+                "write data to file",
+                Some("1 file changed, 1 insertion(+)"),
+            ),
+        ]
+        .concat();
+
+        repository.parse_git_log_dates_from_git_history(std_output);
+        let lines_changed = repository.git_log_lines_changed.unwrap();
+
+        assert_eq!(
+            lines_changed[&NaiveDate::from_ymd_opt(2021, 10, 21).unwrap()],
+            17
+        );
+        assert_eq!(
+            lines_changed[&NaiveDate::from_ymd_opt(2021, 10, 20).unwrap()],
+            1
+        );
+    }
+
     #[test]
     fn it_finds_namespace_from_git_path() {
         let mut timesheet = Repository {
@@ -706,6 +1424,14 @@ Date:   Thu, 3 Jan 2019 11:06:17 +0200
         timesheet.set_git_path("/rust/autolog/.git/".to_string());
         timesheet.find_namespace_from_git_path().unwrap();
         assert_eq!(timesheet.namespace.unwrap(), "autolog".to_string());
+
+        let mut timesheet = Repository {
+            ..Default::default()
+        };
+
+        timesheet.set_git_path("/rust/invoices/.hg/".to_string());
+        timesheet.find_namespace_from_git_path().unwrap();
+        assert_eq!(timesheet.namespace.unwrap(), "invoices".to_string());
     }
 
     #[test]
@@ -740,6 +1466,21 @@ Date:   Thu, 3 Jan 2019 11:06:17 +0200
         );
     }
 
+    #[test]
+    fn it_sets_ref_patterns() {
+        let mut timesheet = Repository {
+            ..Default::default()
+        };
+
+        timesheet.set_ref_patterns(vec!["main".to_string()], vec![]);
+        assert_eq!(timesheet.included_refs, Some(vec!["main".to_string()]));
+        assert_eq!(timesheet.excluded_refs, None);
+        assert!(timesheet.has_ref_patterns());
+
+        timesheet.set_ref_patterns(vec![], vec![]);
+        assert!(!timesheet.has_ref_patterns());
+    }
+
     #[test]
     fn it_sets_namespace() {
         let mut timesheet = Repository {
@@ -0,0 +1,101 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use std::collections::HashSet;
+
+/// Hours worked out from the git history
+pub const SOURCE_GIT: &str = "git";
+/// Hours set by the user with edit or review
+pub const SOURCE_EDIT: &str = "edit";
+/// Edited hours put back to the ones worked out from the git history
+pub const SOURCE_RESET: &str = "reset";
+/// Hours logged as manual entries
+pub const SOURCE_MANUAL: &str = "manual";
+
+/// The days of repositories' timesheets a command changed and where their hours came from.
+/// Hours on any other day changed because the git history was read again
+#[derive(Debug, Clone, Default)]
+pub struct ChangedDays {
+    source: &'static str,
+    days: HashSet<(String, NaiveDate)>,
+}
+
+impl ChangedDays {
+    pub fn new(source: &'static str) -> Self {
+        ChangedDays {
+            source,
+            days: HashSet::new(),
+        }
+    }
+
+    pub fn add(&mut self, repository_id: &str, date: NaiveDate) -> &mut Self {
+        self.days.insert((repository_id.to_string(), date));
+        self
+    }
+
+    pub fn get_source(&self, repository_id: &str, date: &NaiveDate) -> &'static str {
+        match self.days.contains(&(repository_id.to_string(), *date)) {
+            true => self.source,
+            false => SOURCE_GIT,
+        }
+    }
+}
+
+/// A change to the hours of a day on a repository's timesheet. Changes are only ever added,
+/// so they show how a month's hours came to be what they are
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimesheetChange {
+    pub namespace: String,
+    pub date: NaiveDate,
+    /// None when the day was first saved
+    pub old_hours: Option<f64>,
+    pub new_hours: f64,
+    pub source: String,
+    /// When the change was saved, in UTC
+    pub changed_at: NaiveDateTime,
+}
+
+impl TimesheetChange {
+    /// Whether saving the hours over the ones saved before would change them. A day
+    /// saved for the first time only counts when it has some hours
+    pub fn is_change(old_hours: Option<f64>, new_hours: f64) -> bool {
+        match old_hours {
+            Some(old_hours) => (old_hours - new_hours).abs() > f64::EPSILON,
+            None => new_hours != 0.0,
+        }
+    }
+
+    /// The time the change was saved in the local timezone
+    pub fn get_local_changed_at(&self) -> NaiveDateTime {
+        Local.from_utc_datetime(&self.changed_at).naive_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_whether_hours_change() {
+        assert!(TimesheetChange::is_change(Some(8.0), 6.5));
+        assert!(!TimesheetChange::is_change(Some(8.0), 8.0));
+        assert!(TimesheetChange::is_change(None, 8.0));
+        assert!(!TimesheetChange::is_change(None, 0.0));
+    }
+
+    #[test]
+    fn it_finds_the_source_of_changed_days() {
+        let date = NaiveDate::from_ymd_opt(2021, 11, 1).unwrap();
+        let mut changed_days = ChangedDays::new(SOURCE_RESET);
+        changed_days.add("repo1", date);
+
+        assert_eq!(changed_days.get_source("repo1", &date), SOURCE_RESET);
+        assert_eq!(changed_days.get_source("repo2", &date), SOURCE_GIT);
+        assert_eq!(
+            changed_days.get_source("repo1", &date.succ_opt().unwrap()),
+            SOURCE_GIT
+        );
+        assert_eq!(
+            ChangedDays::default().get_source("repo1", &date),
+            SOURCE_GIT
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::data::git_commit::GitCommit;
+use crate::data::git_history::{self, GitScanOptions};
+use crate::data::repository::Repository;
+use crate::data::vcs::{self, VcsBackend, VcsUserDetails};
+use std::error::Error;
+use std::process::Command;
+
+/// Reads history by running git, or with libgit2 when built with the `in-process-git` feature,
+/// storing the commits so only new ones are read next time
+pub struct Git;
+
+fn git_in(path: &str) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(path);
+    command
+}
+
+fn read_trimmed(command: &mut Command) -> Result<String, Box<dyn Error>> {
+    crate::utils::trim_output_from_utf8(command.output()?)
+}
+
+impl VcsBackend for Git {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn data_dir(&self) -> &'static str {
+        ".git"
+    }
+
+    fn find_root(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        #[cfg(feature = "in-process-git")]
+        if let Ok(root) = vcs::libgit2::find_root(path) {
+            return Ok(root);
+        }
+
+        Ok(
+            vcs::read_command_output(git_in(path).args(["rev-parse", "--show-toplevel"]))?
+                .trim()
+                .to_string(),
+        )
+    }
+
+    fn read_user_details(&self, path: &str) -> Result<VcsUserDetails, Box<dyn Error>> {
+        #[cfg(feature = "in-process-git")]
+        if let Ok(details) = vcs::libgit2::read_user_details(path) {
+            return Ok(details);
+        }
+
+        Ok(VcsUserDetails {
+            name: read_trimmed(git_in(path).args(["config", "user.name"]))?,
+            email: read_trimmed(git_in(path).args(["config", "user.email"]))?,
+            remotes: read_trimmed(git_in(path).args(["remote", "-v"]))?,
+        })
+    }
+
+    fn read_history(
+        &self,
+        repository: &Repository,
+        options: &GitScanOptions,
+    ) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+        git_history::read_git_history(repository, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_git_backend_for_a_git_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        assert!(vcs::find_backend(path).is_none_or(|(backend, _)| backend.name() != "git"));
+
+        git_in(path).arg("init").output().unwrap();
+        let (backend, root) = vcs::find_backend(path).unwrap();
+        assert_eq!(backend.name(), "git");
+        assert!(root.ends_with(dir.path().file_name().unwrap().to_str().unwrap()));
+    }
+}
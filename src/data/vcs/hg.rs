@@ -0,0 +1,109 @@
+use crate::data::author_identity::AuthorIdentity;
+use crate::data::git_commit::{self, GitCommit};
+use crate::data::git_history::GitScanOptions;
+use crate::data::repository::Repository;
+use crate::data::vcs::{self, VcsBackend, VcsUserDetails};
+use std::error::Error;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// `hg log` template printing the same records as GIT_LOG_FORMAT. Mercurial only records one
+/// date for a changeset, so it's used as both the author and committer date
+pub const HG_LOG_TEMPLATE: &str = "\\x1e{node}\\x00{person(author)}\\x00{email(author)}\\x00{date|rfc3339date}\\x00{date|rfc3339date}\\x00{desc|firstline}";
+
+/// Reads history by running `hg log`
+pub struct Mercurial;
+
+/// HGPLAIN keeps the user's aliases and output settings from changing what hg prints
+fn hg_in(path: &str) -> Command {
+    let mut command = Command::new("hg");
+    command.env("HGPLAIN", "1").arg("--cwd").arg(path);
+    command
+}
+
+fn diffstat_regex() -> &'static regex::Regex {
+    static REGEX: OnceLock<regex::Regex> = OnceLock::new();
+    REGEX.get_or_init(|| regex::Regex::new(r"(?m)^\d+: \+(\d+)/-(\d+)$").unwrap())
+}
+
+/// Read the lines changed from the `{diffstat}` template keyword, e.g. `3: +10/-2`
+fn parse_diffstat(stat: &str) -> Option<u64> {
+    let cap = diffstat_regex().captures(stat)?;
+    let count = |index: usize| cap[index].parse::<u64>().unwrap_or(0);
+    Some(count(1) + count(2))
+}
+
+impl VcsBackend for Mercurial {
+    fn name(&self) -> &'static str {
+        "hg"
+    }
+
+    fn data_dir(&self) -> &'static str {
+        ".hg"
+    }
+
+    fn find_root(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Ok(vcs::read_command_output(hg_in(path).arg("root"))?
+            .trim()
+            .to_string())
+    }
+
+    fn read_user_details(&self, path: &str) -> Result<VcsUserDetails, Box<dyn Error>> {
+        // the user is configured as `Name <email>`
+        let username = vcs::read_command_output(hg_in(path).args(["config", "ui.username"]))
+            .unwrap_or_default();
+        let identity = AuthorIdentity::parse(&username).ok();
+
+        Ok(VcsUserDetails {
+            name: identity
+                .as_ref()
+                .and_then(|identity| identity.name.clone())
+                .unwrap_or_default(),
+            email: identity
+                .and_then(|identity| identity.email)
+                .unwrap_or_default(),
+            remotes: vcs::read_command_output(hg_in(path).arg("paths")).unwrap_or_default(),
+        })
+    }
+
+    fn read_history(
+        &self,
+        repository: &Repository,
+        options: &GitScanOptions,
+    ) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+        let mut template = HG_LOG_TEMPLATE.to_string();
+        if repository.splits_hours_by_lines_changed() {
+            template.push_str("\\n{diffstat}");
+        }
+        template.push_str("\\n");
+
+        let working_copy = vcs::get_working_copy(repository);
+        let output =
+            vcs::read_command_output(hg_in(&working_copy).args(["log", "--template", &template]))?;
+
+        Ok(vcs::filter_commits(
+            repository,
+            git_commit::parse_log_records(&output, parse_diffstat),
+            options,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_mercurial_log_records() {
+        let output = "\u{1e}a1b2\0Jim Jones\0jim@jones.com\u{0}2021-10-21T10:06:14+02:00\u{0}2021-10-21T10:06:14+02:00\0Add invoices\n3: +10/-2\n\
+            \u{1e}c3d4\0Jim Jones\0jim@jones.com\u{0}2021-10-20T12:09:16+02:00\u{0}2021-10-20T12:09:16+02:00\0\n";
+
+        let commits = git_commit::parse_log_records(output, parse_diffstat);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "a1b2");
+        assert_eq!(commits[0].subject, "Add invoices");
+        assert_eq!(commits[0].lines_changed, Some(12));
+        assert_eq!(commits[0].author_date, commits[0].committer_date);
+        assert_eq!(commits[1].lines_changed, None);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::data::git_commit::{self, GitCommit};
+use crate::data::git_history::GitScanOptions;
+use crate::data::repository::Repository;
+use crate::data::vcs::{self, VcsBackend, VcsUserDetails};
+use std::error::Error;
+use std::process::Command;
+
+/// `jj log` template printing the same records as GIT_LOG_FORMAT
+pub const JJ_LOG_TEMPLATE: &str = r#""\x1e" ++ commit_id ++ "\0" ++ author.name() ++ "\0" ++ author.email() ++ "\0" ++ author.timestamp().format("%Y-%m-%dT%H:%M:%S%:z") ++ "\0" ++ committer.timestamp().format("%Y-%m-%dT%H:%M:%S%:z") ++ "\0" ++ description.first_line()"#;
+
+/// Every change apart from the root, and the empty working copy change that hasn't been
+/// described yet
+pub const JJ_LOG_REVSET: &str = r#"all() ~ root() ~ (empty() & description(exact:""))"#;
+
+/// Reads history by running `jj log`
+pub struct Jujutsu;
+
+/// Reading doesn't snapshot the working copy, so autolog never writes to the repository
+fn jj_in(path: &str) -> Command {
+    let mut command = Command::new("jj");
+    command
+        .current_dir(path)
+        .args(["--no-pager", "--color=never", "--ignore-working-copy"]);
+    command
+}
+
+impl VcsBackend for Jujutsu {
+    fn name(&self) -> &'static str {
+        "jj"
+    }
+
+    fn data_dir(&self) -> &'static str {
+        ".jj"
+    }
+
+    fn find_root(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Ok(vcs::read_command_output(jj_in(path).arg("root"))?
+            .trim()
+            .to_string())
+    }
+
+    fn read_user_details(&self, path: &str) -> Result<VcsUserDetails, Box<dyn Error>> {
+        let read = |args: &[&str]| {
+            vcs::read_command_output(jj_in(path).args(args))
+                .map(|output| output.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        Ok(VcsUserDetails {
+            name: read(&["config", "get", "user.name"]),
+            email: read(&["config", "get", "user.email"]),
+            remotes: read(&["git", "remote", "list"]),
+        })
+    }
+
+    fn read_history(
+        &self,
+        repository: &Repository,
+        options: &GitScanOptions,
+    ) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+        let mut template = JJ_LOG_TEMPLATE.to_string();
+        // jj's diff stat ends with the same summary line as `git --shortstat`
+        if repository.splits_hours_by_lines_changed() {
+            template.push_str(r#" ++ "\n" ++ self.diff().stat(80)"#);
+        }
+        template.push_str(r#" ++ "\n""#);
+
+        let working_copy = vcs::get_working_copy(repository);
+        let mut command = jj_in(&working_copy);
+        command.args(["log", "--no-graph", "-r", JJ_LOG_REVSET, "-T", &template]);
+
+        let output = vcs::read_command_output(&mut command)?;
+
+        Ok(vcs::filter_commits(
+            repository,
+            git_commit::parse_log_records(&output, git_commit::parse_lines_changed),
+            options,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_jujutsu_log_records() {
+        let output = "\u{1e}a1b2\0Jim Jones\0jim@jones.com\u{0}2021-10-21T10:06:14+02:00\u{0}2021-10-21T11:00:00+02:00\0Add invoices\n\
+            src/invoice.rs | 12 ++++++++----\n\
+            1 file changed, 8 insertions(+), 4 deletions(-)\n";
+
+        let commits = git_commit::parse_log_records(output, git_commit::parse_lines_changed);
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].subject, "Add invoices");
+        assert_eq!(commits[0].lines_changed, Some(12));
+        assert_ne!(commits[0].author_date, commits[0].committer_date);
+    }
+}
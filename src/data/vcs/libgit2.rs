@@ -0,0 +1,286 @@
+use crate::data::git_commit::GitCommit;
+use crate::data::git_history::GitScanOptions;
+use crate::data::repository::Repository;
+use crate::data::vcs::VcsUserDetails;
+use chrono::{DateTime, FixedOffset};
+use git2::{Oid, Sort};
+use std::collections::HashSet;
+use std::error::Error;
+
+/// Open the repository with libgit2. When it can't be, e.g. because it uses an extension
+/// libgit2 doesn't support, history is read by running git instead
+pub fn open(repository: &Repository) -> Option<git2::Repository> {
+    git2::Repository::open(repository.git_path.as_ref()?).ok()
+}
+
+/// The root of the working copy containing the path, like `git rev-parse --show-toplevel`
+pub fn find_root(path: &str) -> Result<String, Box<dyn Error>> {
+    let repo = git2::Repository::discover(path)?;
+    let workdir = repo
+        .workdir()
+        .ok_or("Bare repositories don't have a working copy")?;
+
+    Ok(workdir.to_string_lossy().trim_end_matches('/').to_string())
+}
+
+pub fn read_user_details(path: &str) -> Result<VcsUserDetails, Box<dyn Error>> {
+    let repo = git2::Repository::discover(path)?;
+    let config = repo.config()?;
+    // like `git config`, a value that isn't set is read as empty
+    let read = |name: &str| config.get_string(name).unwrap_or_default();
+
+    // written the way `git remote -v` prints them
+    let mut remotes = vec![];
+    for name in repo.remotes()?.iter().flatten() {
+        let remote = repo.find_remote(name)?;
+        if let Some(url) = remote.url() {
+            remotes.push(format!("{}\t{} (fetch)", name, url));
+            remotes.push(format!(
+                "{}\t{} (push)",
+                name,
+                remote.pushurl().unwrap_or(url)
+            ));
+        }
+    }
+
+    Ok(VcsUserDetails {
+        name: read("user.name"),
+        email: read("user.email"),
+        remotes: remotes.join("\n"),
+    })
+}
+
+/// The full names of the repository's refs, like `git for-each-ref`
+pub fn read_ref_names(repo: &git2::Repository) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut names = vec![];
+    for reference in repo.references()? {
+        if let Some(name) = reference?.name() {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+/// The commits at the tips of the refs, or of every ref and HEAD like `git log --all`
+fn find_tips(repo: &git2::Repository, refs: Option<&[String]>) -> Result<Vec<Oid>, git2::Error> {
+    let mut seen = HashSet::new();
+    let mut tips = vec![];
+    let mut add = |reference: git2::Reference| {
+        // refs can point at trees or blobs, which don't have any history
+        if let Ok(commit) = reference.peel_to_commit()
+            && seen.insert(commit.id())
+        {
+            tips.push(commit.id());
+        }
+    };
+
+    match refs {
+        None => {
+            for reference in repo.references()? {
+                add(reference?);
+            }
+            // HEAD is unborn until the first commit
+            if let Ok(head) = repo.head() {
+                add(head);
+            }
+        }
+        Some(refs) => {
+            for name in refs {
+                add(repo.find_reference(name)?);
+            }
+        }
+    }
+
+    Ok(tips)
+}
+
+pub fn read_ref_tips(
+    repo: &git2::Repository,
+    refs: Option<&[String]>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(find_tips(repo, refs)?
+        .iter()
+        .map(|tip| tip.to_string())
+        .collect())
+}
+
+fn to_date_time(time: git2::Time) -> Result<DateTime<FixedOffset>, Box<dyn Error>> {
+    let offset =
+        FixedOffset::east_opt(time.offset_minutes() * 60).ok_or("Invalid commit timezone")?;
+    let date_time = DateTime::from_timestamp(time.seconds(), 0).ok_or("Invalid commit time")?;
+
+    Ok(date_time.with_timezone(&offset))
+}
+
+/// Insertions plus deletions against the parent, like `--shortstat`, which doesn't count
+/// merges or commits without any changes
+fn count_lines_changed(
+    repo: &git2::Repository,
+    commit: &git2::Commit,
+) -> Result<Option<u64>, git2::Error> {
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        1 => Some(commit.parent(0)?.tree()?),
+        _ => return Ok(None),
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+
+    Ok(Some((stats.insertions() + stats.deletions()) as u64).filter(|_| stats.files_changed() > 0))
+}
+
+/// Read the commits reachable from the refs that can't be reached from any of the excluded
+/// commits, as `git log` does with GIT_LOG_FORMAT
+pub fn read_git_log(
+    repo: &git2::Repository,
+    repository: &Repository,
+    refs: Option<&[String]>,
+    excluded_commits: &[String],
+    options: &GitScanOptions,
+) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    for tip in find_tips(repo, refs)? {
+        revwalk.push(tip)?;
+    }
+    // fails when a commit from the last scan has gone, as it does with git
+    for hash in excluded_commits {
+        revwalk.hide(Oid::from_str(hash)?)?;
+    }
+
+    let mailmap = match repository.uses_mailmap() {
+        true => Some(repo.mailmap()?),
+        false => None,
+    };
+
+    let mut commits = vec![];
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let author = match mailmap.as_ref() {
+            Some(mailmap) => commit.author_with_mailmap(mailmap)?,
+            None => commit.author().to_owned(),
+        };
+
+        commits.push(GitCommit {
+            hash: commit.id().to_string(),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            author_date: to_date_time(author.when())?,
+            committer_date: to_date_time(commit.committer().when())?,
+            subject: commit.summary().unwrap_or_default().to_string(),
+            lines_changed: None,
+        });
+    }
+
+    // every author's commits are kept, as they are with `git log`, so they can be stored and
+    // matched against the user's identities when they're read
+    commits.retain(|commit| options.contains(&commit.committer_date));
+
+    // lines changed are only needed when they're used to split hours
+    if repository.splits_hours_by_lines_changed() {
+        for commit in commits.iter_mut() {
+            let git_commit = repo.find_commit(Oid::from_str(&commit.hash)?)?;
+            commit.lines_changed = count_lines_changed(repo, &git_commit)?;
+        }
+    }
+
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks::git;
+
+    #[test]
+    fn it_reads_the_same_commits_as_git() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.name", "Jim Jones"]);
+        git(
+            dir.path(),
+            &["remote", "add", "origin", "git@github.com:jim/autolog.git"],
+        );
+        std::fs::write(dir.path().join("invoice.rs"), "fn main() {}\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "Add invoices"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "Empty commit"],
+        );
+
+        let path = dir.path().to_str().unwrap();
+        assert!(
+            find_root(path)
+                .unwrap()
+                .ends_with(dir.path().file_name().unwrap().to_str().unwrap())
+        );
+
+        let details = read_user_details(path).unwrap();
+        assert_eq!(details.name, "Jim Jones");
+        assert!(
+            details
+                .remotes
+                .contains("origin\tgit@github.com:jim/autolog.git (fetch)")
+        );
+
+        let repository = Repository {
+            git_path: Some(format!("{}/.git/", path)),
+            name: Some("Jim Jones".to_string()),
+            hours_split: Some("lines".to_string()),
+            ..Default::default()
+        };
+        let repo = open(&repository).unwrap();
+        let commits =
+            read_git_log(&repo, &repository, None, &[], &GitScanOptions::default()).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].subject, "Empty commit");
+        assert_eq!(commits[0].lines_changed, None);
+        assert_eq!(commits[1].subject, "Add invoices");
+        assert_eq!(commits[1].lines_changed, Some(1));
+        assert_eq!(commits[1].author_email, "jim@jones.com");
+        assert_eq!(
+            read_ref_tips(&repo, None).unwrap(),
+            vec![commits[0].hash.clone()]
+        );
+    }
+
+    #[test]
+    fn it_reads_commits_from_every_author() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "Add invoices"],
+        );
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "--author=Bob Brown <bob@brown.com>",
+                "-m",
+                "Add reports",
+            ],
+        );
+
+        let repository = Repository {
+            git_path: Some(format!("{}/.git/", dir.path().to_str().unwrap())),
+            name: Some("Jim Jones".to_string()),
+            ..Default::default()
+        };
+        let repo = open(&repository).unwrap();
+        let commits =
+            read_git_log(&repo, &repository, None, &[], &GitScanOptions::default()).unwrap();
+
+        let authors: Vec<&str> = commits
+            .iter()
+            .map(|commit| commit.author_name.as_str())
+            .collect();
+        assert_eq!(authors, vec!["Bob Brown", "Jim Jones"]);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::data::git_commit::GitCommit;
+use crate::data::git_history::GitScanOptions;
+use crate::data::repository::Repository;
+use std::error::Error;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub mod git;
+pub mod hg;
+pub mod jj;
+#[cfg(feature = "in-process-git")]
+pub mod libgit2;
+
+/// The user details configured for a working copy, and the urls of its remotes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VcsUserDetails {
+    pub name: String,
+    pub email: String,
+    pub remotes: String,
+}
+
+/// A version control system that history can be read from. Each repository stores the name
+/// of its backend, and repositories added before there was a choice use git.
+pub trait VcsBackend: Sync {
+    fn name(&self) -> &'static str;
+
+    /// The directory the backend keeps its data in, at the root of a working copy
+    fn data_dir(&self) -> &'static str;
+
+    /// The root of the working copy containing the path
+    fn find_root(&self, path: &str) -> Result<String, Box<dyn Error>>;
+
+    fn read_user_details(&self, path: &str) -> Result<VcsUserDetails, Box<dyn Error>>;
+
+    /// Read the commits made in the repository, leaving any filtering by other author
+    /// identities to the repository
+    fn read_history(
+        &self,
+        repository: &Repository,
+        options: &GitScanOptions,
+    ) -> Result<Vec<GitCommit>, Box<dyn Error>>;
+}
+
+/// Git comes first, so it's used for working copies that more than one backend can read,
+/// such as a jj repository colocated with git
+pub static VCS_BACKENDS: [&dyn VcsBackend; 3] = [&git::Git, &hg::Mercurial, &jj::Jujutsu];
+
+/// The backend with this name, or git when there's no name or it isn't known
+pub fn get_backend(name: Option<&str>) -> &'static dyn VcsBackend {
+    VCS_BACKENDS
+        .iter()
+        .find(|backend| Some(backend.name()) == name)
+        .copied()
+        .unwrap_or(VCS_BACKENDS[0])
+}
+
+/// Find the backend for the working copy containing the path, along with its root
+pub fn find_backend(path: &str) -> Option<(&'static dyn VcsBackend, String)> {
+    VCS_BACKENDS
+        .iter()
+        .find_map(|backend| backend.find_root(path).ok().map(|root| (*backend, root)))
+}
+
+/// The most repositories history is read from at once
+const MAX_HISTORY_WORKERS: usize = 8;
+
+/// The history of a repository, or why it couldn't be read
+pub type HistoryResult = Result<Vec<GitCommit>, String>;
+
+fn read_repository_history(repository: &Repository, options: &GitScanOptions) -> HistoryResult {
+    // projects without git, or without a user to match commits to, don't have any history
+    if !repository.is_git_repository() || repository.name.is_none() {
+        return Ok(vec![]);
+    }
+
+    repository
+        .get_vcs_backend()
+        .read_history(repository, options)
+        .map_err(|err| err.to_string())
+}
+
+/// Read the history of each repository on a few threads at once, returning it in the same
+/// order as the repositories whichever finishes first
+pub fn read_histories(repositories: &[Repository], options: &GitScanOptions) -> Vec<HistoryResult> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(MAX_HISTORY_WORKERS)
+        .min(repositories.len());
+    let next_repository = AtomicUsize::new(0);
+
+    let mut histories: Vec<(usize, HistoryResult)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut histories = vec![];
+                    loop {
+                        let index = next_repository.fetch_add(1, Ordering::Relaxed);
+                        let Some(repository) = repositories.get(index) else {
+                            break;
+                        };
+                        histories.push((index, read_repository_history(repository, options)));
+                    }
+                    histories
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Failed to read repository history"))
+            .collect()
+    });
+
+    histories.sort_by_key(|(index, _)| *index);
+    histories.into_iter().map(|(_, history)| history).collect()
+}
+
+/// The root of the repository's working copy, from the path to its backend's data directory
+pub(crate) fn get_working_copy(repository: &Repository) -> String {
+    let data_dir = format!("/{}/", repository.get_vcs_backend().data_dir());
+    let git_path = repository.git_path.clone().unwrap_or_default();
+    git_path
+        .strip_suffix(&data_dir)
+        .map(String::from)
+        .unwrap_or(git_path)
+}
+
+/// Run a command, returning its output as text when it succeeds
+pub(crate) fn read_command_output(command: &mut Command) -> Result<String, Box<dyn Error>> {
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Mercurial and jj logs aren't filtered by date the way `git log` is, so the commits are
+/// filtered once they've been read. Authors are matched in the same way as the commits read
+/// with `git log`
+pub(crate) fn filter_commits(
+    repository: &Repository,
+    commits: Vec<GitCommit>,
+    options: &GitScanOptions,
+) -> Vec<GitCommit> {
+    commits
+        .into_iter()
+        .filter(|commit| options.contains(&commit.committer_date))
+        .filter(|commit| repository.is_authored_by_user(commit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+    use chrono::{DateTime, NaiveDate};
+
+    fn commit(author_name: &str, committer_date: &str) -> GitCommit {
+        let date = DateTime::parse_from_rfc3339(committer_date).unwrap();
+        GitCommit {
+            hash: committer_date.to_string(),
+            author_name: author_name.to_string(),
+            author_email: "jim@jones.com".to_string(),
+            author_date: date,
+            committer_date: date,
+            subject: String::new(),
+            lines_changed: None,
+        }
+    }
+
+    #[test]
+    fn it_gets_a_backend_by_name() {
+        assert_eq!(get_backend(None).name(), "git");
+        assert_eq!(get_backend(Some("hg")).name(), "hg");
+        assert_eq!(get_backend(Some("jj")).name(), "jj");
+        assert_eq!(get_backend(Some("svn")).name(), "git");
+    }
+
+    #[test]
+    fn it_finds_the_working_copy_from_the_data_directory() {
+        let mut repository = Repository {
+            git_path: Some("/projects/autolog/.hg/".to_string()),
+            vcs: Some("hg".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(get_working_copy(&repository), "/projects/autolog");
+
+        repository.vcs = None;
+        repository.git_path = Some("/projects/autolog/.git/".to_string());
+        assert_eq!(get_working_copy(&repository), "/projects/autolog");
+    }
+
+    #[test]
+    fn it_reads_histories_in_the_order_of_the_repositories() {
+        let dirs: Vec<_> = (0..5).map(|_| tempfile::tempdir().unwrap()).collect();
+        let repositories: Vec<Repository> = dirs
+            .iter()
+            .enumerate()
+            .map(|(index, dir)| {
+                let git = |args: &[&str]| mocks::git(dir.path(), args);
+                git(&["init", "-q"]);
+                for _ in 0..=index {
+                    git(&["commit", "-q", "--allow-empty", "-m", "work"]);
+                }
+
+                Repository {
+                    git_path: Some(format!("{}/.git/", dir.path().to_str().unwrap())),
+                    name: Some("Jim Jones".to_string()),
+                    ..Default::default()
+                }
+            })
+            .chain(std::iter::once(Repository::default()))
+            .collect();
+
+        let histories = read_histories(&repositories, &GitScanOptions::default());
+        let counts: Vec<usize> = histories
+            .iter()
+            .map(|history| history.as_ref().unwrap().len())
+            .collect();
+        assert_eq!(counts, vec![1, 2, 3, 4, 5, 0]);
+    }
+
+    #[test]
+    fn it_filters_commits_by_author_and_window() {
+        let repository = Repository {
+            name: Some("Jim Jones".to_string()),
+            ..Default::default()
+        };
+        let commits = vec![
+            commit("Jim Jones", "2024-01-01T10:00:00+00:00"),
+            commit("Bob Brown", "2024-01-02T10:00:00+00:00"),
+            commit("Jim Jonesy", "2024-01-02T11:00:00+00:00"),
+            commit("Jim Jones", "2024-01-05T10:00:00+00:00"),
+        ];
+        let options = GitScanOptions {
+            until: NaiveDate::from_ymd_opt(2024, 1, 3),
+            ..Default::default()
+        };
+
+        let filtered = filter_commits(&repository, commits, &options);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].hash, "2024-01-01T10:00:00+00:00");
+    }
+}
@@ -1,11 +1,60 @@
 #![allow(unused_imports)]
-use crate::data::client_repositories::ClientRepositories;
+use crate::data::client_repositories::{Approver, ClientRepositories};
 use crate::data::repository::{GitLogDates, Repository};
 use crate::utils::date::date_parser::{TimesheetYears, get_timesheet_map_from_date_hashmap};
-use crate::utils::link::link_builder::TimesheetHoursForMonth;
+use crate::utils::link::link_builder::{
+    Timesheet, TimesheetDocument, TimesheetHoursForMonth, build_document,
+};
 use serde_json::{Map, Number, Value};
 use std::collections::{HashMap, HashSet};
 
+/// Run git in a test repository, committing as Jim Jones
+#[cfg(test)]
+pub fn git(path: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args([
+            "-c",
+            "user.name=Jim Jones",
+            "-c",
+            "user.email=jim@jones.com",
+        ])
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success());
+}
+
+/// A commit as `git log` prints it with GIT_LOG_FORMAT, given the author date in rfc2822
+#[cfg(test)]
+pub fn create_mock_git_log_record(date: &str, subject: &str, shortstat: Option<&str>) -> String {
+    let date = chrono::DateTime::parse_from_rfc2822(date)
+        .unwrap()
+        .to_rfc3339();
+    let mut record = format!(
+        "\u{1e}{}\0Jim Jones\0jim@jones.com\0{}\0{}\0{}\n",
+        nanoid::nanoid!(),
+        date,
+        date,
+        subject
+    );
+    if let Some(shortstat) = shortstat {
+        record.push_str(&format!("\n {}\n", shortstat));
+    }
+    record
+}
+
+/// Structured `git log` output for commits made at each of the rfc2822 dates
+#[cfg(test)]
+pub fn create_mock_git_log(dates: &[&str]) -> String {
+    dates
+        .iter()
+        .map(|date| create_mock_git_log_record(date, "commit", None))
+        .collect()
+}
+
 #[cfg(test)]
 pub fn create_mock_client_repository(client_repository: &mut ClientRepositories) {
     let mut repo = Repository {
@@ -73,8 +122,13 @@ pub fn create_mock_repository() -> Repository {
     // testing utility that returns
     // {2021: {10: {20, 23, 21}, 9: {8}}, 2020: {8: {1}}, 2019: {1: {3}}}
     let date_hashmap: GitLogDates = get_timesheet_hashmap();
-    let timesheet =
-        get_timesheet_map_from_date_hashmap(date_hashmap, &mut Default::default(), vec![], 1);
+    let timesheet = get_timesheet_map_from_date_hashmap(
+        date_hashmap,
+        &mut Default::default(),
+        vec![],
+        &HashMap::new(),
+        1,
+    );
 
     let repository = Repository {
         namespace: Option::from("autolog".to_owned()),
@@ -114,3 +168,33 @@ pub fn generate_project_git_log_dates(days: [u32; 3]) -> GitLogDates {
         ),
     ])
 }
+
+#[cfg(test)]
+pub fn create_mock_timesheet_document(random_path: &str) -> TimesheetDocument {
+    use chrono::{TimeZone, Utc};
+
+    let mut client_repositories = ClientRepositories {
+        requires_approval: Option::from(true),
+        approver: Option::from(Approver {
+            approvers_name: Option::from("Bob Brown".to_string()),
+            approvers_email: Option::from("bob@brown.com".to_string()),
+        }),
+        ..Default::default()
+    };
+    create_mock_client_repository(&mut client_repositories);
+
+    let timesheets = vec![Timesheet {
+        namespace: "autolog".to_string(),
+        timesheet: create_mock_timesheet_hours_for_month(),
+        total_hours: 24.0,
+        project_number: Option::from("PO-1".to_string()),
+    }];
+
+    build_document(
+        Utc.with_ymd_and_hms(2021, 11, 1, 12, 0, 0).unwrap(),
+        random_path,
+        "October, 2021",
+        &timesheets,
+        &client_repositories,
+    )
+}
@@ -1,11 +1,17 @@
 extern crate clap;
 use crate::config;
-use crate::config::{Edit, Init, List, Make, New, Remove, Update};
+use crate::config::{
+    Configure, Edit, Entries, History, Init, List, Lock, Log, Make, New, Remove, Review, Unlock,
+    Update,
+};
 use crate::data::client_repositories::ClientRepositories;
+use crate::data::git_history::{self, GitScanOptions};
 use crate::data::repository;
 use crate::data::repository::Repository;
 use crate::interface::help_prompt::HelpPrompt;
+use crate::utils::date::date_parser;
 use crate::utils::db::db_reader;
+use crate::utils::settings::settings_file::SETTING_KEYS;
 use chrono::prelude::*;
 use clap::{App, Arg, ArgMatches, Error};
 use std::ffi::OsString;
@@ -18,6 +24,13 @@ pub enum Commands {
     Remove,
     Update,
     List,
+    Config,
+    Log,
+    Entries,
+    Review,
+    History,
+    Lock,
+    Unlock,
 }
 
 #[derive(Debug, Default)]
@@ -49,15 +62,15 @@ impl Cli<'_> {
         let hour_arg = Arg::with_name("hour")
             .short("h")
             .long("hour")
+            .visible_alias("hours")
             .value_name("xx")
             .help(
                 "sets the hour value. When the day/month/year \n\
                     isn't set, it defaults to the current day",
             )
-            .required(true);
+            .required_unless_one(&["description", "clear", "reset"]);
 
         let day_arg = Arg::with_name("day")
-            .requires("hour")
             .short("d")
             .long("day")
             .value_name("xx")
@@ -86,12 +99,57 @@ impl Cli<'_> {
                     isn't set, it defaults to the current day",
             );
 
+        let client_arg = Arg::with_name("client")
+            .short("c")
+            .long("client")
+            .value_name("client")
+            .help(
+                "Pass optional client name. Defaults \n\
+                    to client of current directory",
+            );
+
         let app: App = App::new("AUTOLOG")
             .version(env!("CARGO_PKG_VERSION"))
             .author("David Jonathan Moores")
             .about(
                 "Minimal configuration, simple timesheets for sharing via pdf download or unique link.",
-            ).subcommand(
+            )
+            .arg(Arg::with_name("db")
+                .long("db")
+                .value_name("path")
+                .global(true)
+                .help(
+                    "Use the database at this path. Defaults to AUTOLOG_DB, \n\
+                        or autolog.db in the platform data directory",
+                ))
+            .arg(Arg::with_name("full-rescan")
+                .long("full-rescan")
+                .global(true)
+                .help(
+                    "Read the whole git history of each repository again, instead of \n\
+                        only the commits made since it was last read",
+                ))
+            .arg(Arg::with_name("since")
+                .long("since")
+                .value_name("yyyy-mm-dd")
+                .global(true)
+                .validator(Self::validate_date)
+                .help("Only read git history from commits made on or after this day"))
+            .arg(Arg::with_name("until")
+                .long("until")
+                .value_name("yyyy-mm-dd")
+                .global(true)
+                .validator(Self::validate_date)
+                .help("Only read git history from commits made on or before this day"))
+            .arg(Arg::with_name("yes")
+                .long("yes")
+                .visible_alias("non-interactive")
+                .global(true)
+                .help(
+                    "Don't prompt. Confirmations are answered yes, and anything else \n\
+                        has to be passed as a flag, failing when it's missing",
+                ))
+            .subcommand(
             App::new("init")
                 .about("Initialise for current or specified repository")
                 .arg(Arg::with_name("path")
@@ -101,14 +159,109 @@ impl Cli<'_> {
                     .help(
                         "Pass optional 'path' to git repository. Defaults \n\
                             to current directory",
-                    )))
+                    ))
+                .arg(Arg::with_name("project")
+                    .conflicts_with("path")
+                    .long("project")
+                    .value_name("name")
+                    .help(
+                        "Add a project without a git repository, such as design work. \n\
+                            Its days come from the time logged with 'autolog log'",
+                    ))
+                .arg(Arg::with_name("client")
+                    .long("client")
+                    .value_name("name")
+                    .help(
+                        "The client company name. An existing client with this name \n\
+                            is added to, otherwise a new client is created",
+                    ))
+                .arg(Arg::with_name("contact")
+                    .long("contact")
+                    .value_name("name")
+                    .help("The contact person for a new client"))
+                .arg(Arg::with_name("address-file")
+                    .long("address-file")
+                    .value_name("file")
+                    .help("A file containing the address of a new client"))
+                .arg(Arg::with_name("hours")
+                    .long("hours")
+                    .value_name("xx")
+                    .help("Default hours per day (0-24) for a new client. Defaults to 8"))
+                .arg(Arg::with_name("approver-name")
+                    .long("approver-name")
+                    .value_name("name")
+                    .requires("approver-email")
+                    .help("The person who approves timesheets for a new client"))
+                .arg(Arg::with_name("approver-email")
+                    .long("approver-email")
+                    .value_name("email")
+                    .requires("approver-name")
+                    .help("The email of the person who approves timesheets for a new client"))
+                .arg(Arg::with_name("name")
+                    .long("name")
+                    .value_name("name")
+                    .requires("project")
+                    .help("Your name, for a new client added with a project"))
+                .arg(Arg::with_name("email")
+                    .long("email")
+                    .value_name("email")
+                    .requires("project")
+                    .help("Your email, for a new client added with a project")))
             .subcommand(App::new("edit")
-                .about("Change the hours worked value for a given day")
+                .about("Change the hours worked value for a given day or range of days")
                 .arg(&namespace_arg)
                 .arg(&hour_arg)
                 .arg(&day_arg)
                 .arg(&month_arg)
-                .arg(&year_arg))
+                .arg(&year_arg)
+                .arg(Arg::with_name("description")
+                    .long("description")
+                    .value_name("text")
+                    .help(
+                        "Describe the work done that day, replacing the description \n\
+                            written from the day's commit messages",
+                    )
+                    .conflicts_with("from"))
+                .arg(Arg::with_name("from")
+                    .long("from")
+                    .value_name("yyyy-mm-dd")
+                    .conflicts_with("day")
+                    .validator(Self::validate_date)
+                    .help(
+                        "Edit every day from this date instead of a single day. Weekends \n\
+                            are left as they are unless --weekdays includes them",
+                    ))
+                .arg(Arg::with_name("to")
+                    .long("to")
+                    .value_name("yyyy-mm-dd")
+                    .requires("from")
+                    .validator(Self::validate_date)
+                    .help("The last day of the range to edit. Defaults to the --from date"))
+                .arg(Arg::with_name("weekdays")
+                    .long("weekdays")
+                    .value_name("mon,tue")
+                    .requires("from")
+                    .validator(Self::validate_weekdays)
+                    .help("Only edit these days of the week within the range"))
+                .arg(Arg::with_name("clear")
+                    .long("clear")
+                    .conflicts_with("hour")
+                    .help("Set the hours to zero, including on weekends"))
+                .arg(Arg::with_name("reset")
+                    .long("reset")
+                    .value_name("day|month|repo")
+                    .min_values(0)
+                    .possible_values(&[
+                        repository::RESET_DAY,
+                        repository::RESET_MONTH,
+                        repository::RESET_REPOSITORY,
+                    ])
+                    .conflicts_with_all(&["hour", "description", "weekdays", "clear"])
+                    .help(
+                        "Work out the hours and description of the edited days from git \n\
+                            again. Resets the day or range by default, or the whole month \n\
+                            of the day, or every day of the repository",
+                    )))
             .subcommand(App::new("remove")
                 .about("Remove a client or repository")
                 .arg(Arg::with_name("client")
@@ -143,7 +296,130 @@ impl Cli<'_> {
                     .value_name("namespace")
                     .help(
                         "Pass an optional namespace/project name of the git repository",
-                    )))
+                    ))
+                .arg(Arg::with_name("approver-name")
+                    .long("approver-name")
+                    .value_name("name")
+                    .conflicts_with("namespace")
+                    .help("Set the person who approves the client's timesheets"))
+                .arg(Arg::with_name("approver-email")
+                    .long("approver-email")
+                    .value_name("email")
+                    .conflicts_with("namespace")
+                    .help("Set the email of the person who approves the client's timesheets"))
+                .arg(Arg::with_name("client-name")
+                    .long("client-name")
+                    .value_name("name")
+                    .conflicts_with("namespace")
+                    .help("Rename the client"))
+                .arg(Arg::with_name("contact")
+                    .long("contact")
+                    .value_name("name")
+                    .conflicts_with("namespace")
+                    .help("Set the client's contact person"))
+                .arg(Arg::with_name("address-file")
+                    .long("address-file")
+                    .value_name("file")
+                    .conflicts_with("namespace")
+                    .help("Set the client's address from a file"))
+                .arg(Arg::with_name("name")
+                    .long("name")
+                    .value_name("name")
+                    .conflicts_with("namespace")
+                    .help("Set your name on the client's timesheets"))
+                .arg(Arg::with_name("email")
+                    .long("email")
+                    .value_name("email")
+                    .conflicts_with("namespace")
+                    .help("Set your email on the client's timesheets"))
+                .arg(Arg::with_name("hours-estimation")
+                    .long("hours-estimation")
+                    .value_name("mode")
+                    .possible_values(&[repository::HOURS_ESTIMATION_DEFAULT, repository::HOURS_ESTIMATION_COMMITS])
+                    .conflicts_with("namespace")
+                    .help("Credit each day with a commit the default hours, or estimate them from commit times"))
+                .arg(Arg::with_name("lead-in-minutes")
+                    .long("lead-in-minutes")
+                    .value_name("minutes")
+                    .requires("hours-estimation")
+                    .help("Minutes of work before the first commit when estimating from commit times. Defaults to 30"))
+                .arg(Arg::with_name("session-gap-minutes")
+                    .long("session-gap-minutes")
+                    .value_name("minutes")
+                    .requires("hours-estimation")
+                    .help(
+                        "Minutes between commits before a new session starts when estimating \n\
+                            from commit times. Defaults to 120",
+                    ))
+                .arg(Arg::with_name("hours-split")
+                    .long("hours-split")
+                    .value_name("split")
+                    .possible_values(&[
+                        repository::HOURS_SPLIT_EVEN,
+                        repository::HOURS_SPLIT_COMMITS,
+                        repository::HOURS_SPLIT_LINES,
+                        repository::HOURS_SPLIT_SESSIONS,
+                    ])
+                    .conflicts_with("namespace")
+                    .help("How a day's hours are split between repositories committed to that day"))
+                .arg(Arg::with_name("hours-rounding")
+                    .long("hours-rounding")
+                    .value_name("rounding")
+                    .possible_values(&date_parser::HOURS_ROUNDING_OPTIONS)
+                    .conflicts_with("namespace")
+                    .help("How hours are rounded"))
+                .arg(Arg::with_name("timezone")
+                    .long("timezone")
+                    .value_name("timezone")
+                    .conflicts_with("namespace")
+                    .help(
+                        "The timezone used to work out which day a commit counts towards, e.g. \n\
+                            Europe/London. Pass an empty value to use the timezone of each commit",
+                    ))
+                .arg(Arg::with_name("day-start-hour")
+                    .long("day-start-hour")
+                    .value_name("hour")
+                    .conflicts_with("namespace")
+                    .help("The hour (0-23) the working day starts. Earlier commits count towards the previous day"))
+                .arg(Arg::with_name("author-identity")
+                    .long("author-identity")
+                    .value_name("identity")
+                    .multiple(true)
+                    .number_of_values(1)
+                    .conflicts_with("namespace")
+                    .help(
+                        "Another name, email, Name <email> or /regex/ you commit under. Can be \n\
+                            passed more than once, and replaces the identities already set",
+                    ))
+                .arg(Arg::with_name("mailmap")
+                    .long("mailmap")
+                    .value_name("true|false")
+                    .possible_values(&["true", "false"])
+                    .conflicts_with("namespace")
+                    .help("Map names and emails through the repository's .mailmap"))
+                .arg(Arg::with_name("alias")
+                    .long("alias")
+                    .value_name("namespace")
+                    .requires("namespace")
+                    .help("Rename the repository or project"))
+                .arg(Arg::with_name("repo-path")
+                    .long("repo-path")
+                    .value_name("path")
+                    .requires("namespace")
+                    .help("Move the repository to another path"))
+                .arg(Arg::with_name("include-refs")
+                    .long("include-refs")
+                    .value_name("patterns")
+                    .requires("namespace")
+                    .help(
+                        "Only read history from these comma separated branches and refs, e.g. \n\
+                            main,feature/*. Pass an empty value to read from every branch and ref",
+                    ))
+                .arg(Arg::with_name("exclude-refs")
+                    .long("exclude-refs")
+                    .value_name("patterns")
+                    .requires("namespace")
+                    .help("Skip these comma separated branches and refs, e.g. refs/stash")))
             .subcommand(App::new("list")
                 .about("List all clients and associated repositories"))
             .subcommand(App::new("make")
@@ -164,6 +440,154 @@ impl Cli<'_> {
                         "sets the month value. When the month \n\
                     isn't set, it defaults to the current day",
                     ))
+                .arg(&year_arg)
+                .arg(Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .value_name("format")
+                    .possible_values(&["csv", "json", "markdown"])
+                    .help(
+                        "Export the timesheet locally as csv, json or markdown \n\
+                            instead of generating a unique link",
+                    ))
+                .arg(Arg::with_name("output")
+                    .requires("format")
+                    .short("o")
+                    .long("output")
+                    .value_name("file")
+                    .help(
+                        "Write the exported timesheet to a file. Defaults \n\
+                            to stdout",
+                    ))
+                .arg(Arg::with_name("pdf")
+                    .conflicts_with("format")
+                    .long("pdf")
+                    .value_name("file")
+                    .help(
+                        "Render the timesheet to a pdf on this machine \n\
+                            instead of generating a unique link",
+                    ))
+                .arg(Arg::with_name("project-number")
+                    .long("project-number")
+                    .value_name("repo=PO")
+                    .multiple(true)
+                    .number_of_values(1)
+                    .validator(Self::validate_project_number)
+                    .help(
+                        "Set the project/PO number for a repository or project, instead \n\
+                            of being asked for it. Can be passed more than once",
+                    )))
+            .subcommand(App::new("config")
+                .about("View or change settings, such as the endpoint timesheets are shared to")
+                .subcommand(App::new("set")
+                    .about("Change a setting")
+                    .arg(Arg::with_name("key")
+                        .possible_values(&SETTING_KEYS)
+                        .required(true))
+                    .arg(Arg::with_name("value")
+                        .required(true)))
+                .subcommand(App::new("get")
+                    .about("Show the value in use for a setting")
+                    .arg(Arg::with_name("key")
+                        .possible_values(&SETTING_KEYS)
+                        .required(true)))
+                .subcommand(App::new("unset")
+                    .about("Remove a setting so the default is used")
+                    .arg(Arg::with_name("key")
+                        .possible_values(&SETTING_KEYS)
+                        .required(true)))
+                .subcommand(App::new("list")
+                    .about("List the values in use for every setting")))
+            .subcommand(App::new("log")
+                .about("Log time spent on work that doesn't show up in git, such as meetings or calls")
+                .arg(&client_arg)
+                .arg(Arg::with_name("project")
+                    .short("p")
+                    .long("project")
+                    .value_name("project")
+                    .help(
+                        "Pass an optional repository namespace or project name \n\
+                            that the time was spent on",
+                    ))
+                .arg(Arg::with_name("hour")
+                    .short("h")
+                    .long("hour")
+                    .value_name("xx")
+                    .help("sets the hours spent")
+                    .required(true))
+                .arg(&day_arg)
+                .arg(&month_arg)
+                .arg(&year_arg)
+                .arg(Arg::with_name("note")
+                    .long("note")
+                    .value_name("text")
+                    .help("Describe the work, to show on the timesheet")))
+            .subcommand(App::new("entries")
+                .about("List or remove the time logged with 'autolog log'")
+                .arg(&client_arg)
+                .arg(Arg::with_name("month")
+                    .short("m")
+                    .long("month")
+                    .value_name("xx")
+                    .help(
+                        "sets the month value. When the month \n\
+                    isn't set, it defaults to the current day",
+                    ))
+                .arg(&year_arg)
+                .arg(Arg::with_name("remove")
+                    .long("remove")
+                    .value_name("id")
+                    .help("Remove the entry with this id")))
+            .subcommand(App::new("review")
+                .about("Review and edit a month of a client's timesheet in the terminal")
+                .arg(&client_arg)
+                .arg(Arg::with_name("month")
+                    .short("m")
+                    .long("month")
+                    .value_name("xx")
+                    .help(
+                        "sets the month value. When the month \n\
+                    isn't set, it defaults to the current month",
+                    ))
+                .arg(&year_arg))
+            .subcommand(App::new("history")
+                .about("Show every change to the hours of a month of a client's timesheet")
+                .arg(&client_arg)
+                .arg(Arg::with_name("month")
+                    .short("m")
+                    .long("month")
+                    .value_name("xx")
+                    .help(
+                        "sets the month value. When the month \n\
+                    isn't set, it defaults to the current month",
+                    ))
+                .arg(&year_arg))
+            .subcommand(App::new("lock")
+                .about(
+                    "Keep the hours of a month as they are, so history fetched later \n\
+                can't change a month that has been invoiced",
+                )
+                .arg(&client_arg)
+                .arg(Arg::with_name("month")
+                    .short("m")
+                    .long("month")
+                    .value_name("xx")
+                    .help(
+                        "sets the month value. When the month \n\
+                    isn't set, it defaults to the current month",
+                    ))
+                .arg(&year_arg))
+            .subcommand(App::new("unlock")
+                .about("Work out the hours of a locked month from git again")
+                .arg(&client_arg)
+                .arg(Arg::with_name("month")
+                    .short("m")
+                    .long("month")
+                    .value_name("xx")
+                    .help(
+                        "sets the month value. When the month \n\
+                    isn't set, it defaults to the current month",
+                    ))
                 .arg(&year_arg));
 
         // extract the matches
@@ -187,25 +611,29 @@ impl Cli<'_> {
         let day = date_time.day().to_string();
 
         let current_repo_path = db_reader::get_canonical_path(".");
-        let mut temp_repository = Repository {
-            repo_path: Option::from(current_repo_path.clone()),
-            ..Default::default()
-        };
-
-        // get namespace of working repository
-        temp_repository
-            .find_git_path_from_directory_from()
-            .unwrap_or_else(|err| {
-                eprintln!("Error finding git path from project directory: {}", err);
-                std::process::exit(exitcode::CANTCREAT);
-            })
-            .find_namespace_from_git_path()
-            .unwrap_or_else(|err| {
-                eprintln!("Error finding namespace from git path: {}", err);
-                std::process::exit(exitcode::CANTCREAT);
-            });
 
-        let current_repository_namespace: String = temp_repository.namespace.unwrap();
+        // get namespace of working repository, only when a command needs it
+        // so that commands like config can be run outside of a git repository
+        let get_current_repository_namespace = || -> String {
+            let mut temp_repository = Repository {
+                repo_path: Option::from(current_repo_path.clone()),
+                ..Default::default()
+            };
+
+            temp_repository
+                .find_git_path_from_directory_from()
+                .unwrap_or_else(|err| {
+                    eprintln!("Error finding git path from project directory: {}", err);
+                    std::process::exit(exitcode::CANTCREAT);
+                })
+                .find_namespace_from_git_path()
+                .unwrap_or_else(|err| {
+                    eprintln!("Error finding namespace from git path: {}", err);
+                    std::process::exit(exitcode::CANTCREAT);
+                });
+
+            temp_repository.namespace.unwrap()
+        };
 
         if let Some(init) = matches.subcommand_matches("init") {
             // This will onboard so no need to pass the path here
@@ -214,26 +642,96 @@ impl Cli<'_> {
                     .unwrap_or(&current_repo_path)
                     .to_string(),
             ));
+            options.push(init.value_of("project").map(String::from));
+            for name in [
+                "client",
+                "contact",
+                "address-file",
+                "hours",
+                "approver-name",
+                "approver-email",
+                "name",
+                "email",
+            ] {
+                options.push(init.value_of(name).map(String::from));
+            }
             command = Some(Commands::Init);
         } else if let Some(make) = matches.subcommand_matches("make") {
             // set default value of current month
             options.push(make.value_of("client").map(String::from));
             options.push(Some(make.value_of("month").unwrap_or(&month).to_string()));
             options.push(Some(make.value_of("year").unwrap_or(&year).to_string()));
+            options.push(make.value_of("format").map(String::from));
+            options.push(make.value_of("output").map(String::from));
+            options.push(make.value_of("pdf").map(String::from));
+            // one repo=PO pair on each line
+            options.push(
+                make.values_of("project-number")
+                    .map(|values| values.collect::<Vec<&str>>().join("\n")),
+            );
             command = Some(Commands::Make);
         } else if let Some(edit) = matches.subcommand_matches("edit") {
             // this will error out if the preceding date value isn't passed
             // so I can happily set default here knowing that just the day/month/year will make it through
             options.push(Some(
                 edit.value_of("namespace")
-                    .unwrap_or(&current_repository_namespace)
-                    .to_string(),
+                    .map(String::from)
+                    .unwrap_or_else(get_current_repository_namespace),
             ));
-            options.push(Some(edit.value_of("hour").unwrap().to_string()));
+            options.push(edit.value_of("hour").map(String::from));
             options.push(Some(edit.value_of("day").unwrap_or(&day).to_string()));
             options.push(Some(edit.value_of("month").unwrap_or(&month).to_string()));
             options.push(Some(edit.value_of("year").unwrap_or(&year).to_string()));
+            options.push(edit.value_of("description").map(String::from));
+            options.push(edit.value_of("from").map(String::from));
+            options.push(edit.value_of("to").map(String::from));
+            options.push(edit.value_of("weekdays").map(String::from));
+            options.push(edit.is_present("clear").then(|| "clear".to_string()));
+            options.push(edit.is_present("reset").then(|| {
+                edit.value_of("reset")
+                    .unwrap_or(repository::RESET_DAY)
+                    .to_string()
+            }));
             command = Some(Commands::Edit);
+        } else if let Some(log) = matches.subcommand_matches("log") {
+            options.push(log.value_of("client").map(String::from));
+            options.push(log.value_of("project").map(String::from));
+            options.push(log.value_of("hour").map(String::from));
+            options.push(Some(log.value_of("day").unwrap_or(&day).to_string()));
+            options.push(Some(log.value_of("month").unwrap_or(&month).to_string()));
+            options.push(Some(log.value_of("year").unwrap_or(&year).to_string()));
+            options.push(log.value_of("note").map(String::from));
+            command = Some(Commands::Log);
+        } else if let Some(entries) = matches.subcommand_matches("entries") {
+            options.push(entries.value_of("client").map(String::from));
+            options.push(Some(
+                entries.value_of("month").unwrap_or(&month).to_string(),
+            ));
+            options.push(Some(entries.value_of("year").unwrap_or(&year).to_string()));
+            options.push(entries.value_of("remove").map(String::from));
+            command = Some(Commands::Entries);
+        } else if let Some(review) = matches.subcommand_matches("review") {
+            options.push(review.value_of("client").map(String::from));
+            options.push(Some(review.value_of("month").unwrap_or(&month).to_string()));
+            options.push(Some(review.value_of("year").unwrap_or(&year).to_string()));
+            command = Some(Commands::Review);
+        } else if let Some(history) = matches.subcommand_matches("history") {
+            options.push(history.value_of("client").map(String::from));
+            options.push(Some(
+                history.value_of("month").unwrap_or(&month).to_string(),
+            ));
+            options.push(Some(history.value_of("year").unwrap_or(&year).to_string()));
+            command = Some(Commands::History);
+        } else if let Some(lock) = matches.subcommand_matches("lock") {
+            options.push(lock.value_of("client").map(String::from));
+            options.push(Some(lock.value_of("month").unwrap_or(&month).to_string()));
+            options.push(Some(lock.value_of("year").unwrap_or(&year).to_string()));
+            command = Some(Commands::Lock);
+        } else if let Some(unlock) = matches.subcommand_matches("unlock") {
+            options.push(unlock.value_of("client").map(String::from));
+            options.push(Some(unlock.value_of("month").unwrap_or(&month).to_string()));
+            options.push(Some(unlock.value_of("year").unwrap_or(&year).to_string()));
+            command = Some(Commands::Unlock);
         } else if let Some(remove) = matches.subcommand_matches("remove") {
             options.push(Some(remove.value_of("client").unwrap().to_string()));
             options.push(remove.value_of("namespace").map(String::from));
@@ -241,9 +739,51 @@ impl Cli<'_> {
         } else if let Some(update) = matches.subcommand_matches("update") {
             options.push(Some(update.value_of("client").unwrap().to_string()));
             options.push(update.value_of("namespace").map(String::from));
+            for name in [
+                "approver-name",
+                "approver-email",
+                "client-name",
+                "contact",
+                "address-file",
+                "name",
+                "email",
+                "hours-estimation",
+                "lead-in-minutes",
+                "session-gap-minutes",
+                "hours-split",
+                "hours-rounding",
+                "timezone",
+                "day-start-hour",
+            ] {
+                options.push(update.value_of(name).map(String::from));
+            }
+            options.push(
+                update
+                    .values_of("author-identity")
+                    .map(|values| values.collect::<Vec<&str>>().join("\n")),
+            );
+            for name in [
+                "mailmap",
+                "alias",
+                "repo-path",
+                "include-refs",
+                "exclude-refs",
+            ] {
+                options.push(update.value_of(name).map(String::from));
+            }
             command = Some(Commands::Update);
         } else if matches.subcommand_matches("list").is_some() {
             command = Some(Commands::List);
+        } else if let Some(config) = matches.subcommand_matches("config") {
+            // with no subcommand, list the settings
+            let (action, action_matches) = match config.subcommand() {
+                (name, Some(action_matches)) => (name, Some(action_matches)),
+                _ => ("list", None),
+            };
+            options.push(Some(action.to_string()));
+            options.push(action_matches.and_then(|x| x.value_of("key").map(String::from)));
+            options.push(action_matches.and_then(|x| x.value_of("value").map(String::from)));
+            command = Some(Commands::Config);
         } else {
             return Err(Error {
                 message: "No matches for inputs".to_string(),
@@ -259,15 +799,74 @@ impl Cli<'_> {
         })
     }
 
+    /// Global args can be passed before or after the subcommand
+    fn get_global_value(matches: &ArgMatches, name: &str) -> Option<String> {
+        matches
+            .value_of(name)
+            .or_else(|| matches.subcommand().1.and_then(|sub| sub.value_of(name)))
+            .map(String::from)
+    }
+
+    fn is_global_flag_present(matches: &ArgMatches, name: &str) -> bool {
+        matches.is_present(name)
+            || matches
+                .subcommand()
+                .1
+                .is_some_and(|sub| sub.is_present(name))
+    }
+
+    fn validate_date(value: String) -> Result<(), String> {
+        NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+            .map(|_| ())
+            .map_err(|_| format!("'{}' isn't a date in the form yyyy-mm-dd", value))
+    }
+
+    fn validate_weekdays(value: String) -> Result<(), String> {
+        date_parser::parse_weekdays(&value)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    fn validate_project_number(value: String) -> Result<(), String> {
+        match value.split_once('=') {
+            Some((repo, number)) if !repo.trim().is_empty() && !number.trim().is_empty() => Ok(()),
+            _ => Err(format!("'{}' isn't in the form repo=PO", value)),
+        }
+    }
+
+    fn get_scan_options(matches: &ArgMatches) -> GitScanOptions {
+        let get_date = |name: &str| {
+            Self::get_global_value(matches, name)
+                .and_then(|value| NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok())
+        };
+
+        GitScanOptions {
+            full_rescan: Self::is_global_flag_present(matches, "full-rescan"),
+            since: get_date("since"),
+            until: get_date("until"),
+        }
+    }
+
     pub fn run(&self) -> Result<(), clap::Error> {
         let mut config: config::Config = config::Config::new();
         let mut repository = repository::Repository::new();
         let mut client_repositories = ClientRepositories::new();
         let matches = &self.matches;
+
+        if let Some(db_path) = Self::get_global_value(matches, "db") {
+            db_reader::set_db_path_override(&db_path);
+        }
+
+        git_history::set_scan_options(Self::get_scan_options(matches));
+        crate::utils::set_non_interactive(Self::is_global_flag_present(matches, "yes"));
+
         let cli: Cli = self.parse_commands(matches)?;
 
         // pass the path for init so that I already know it if user is being onboarded
-        if let Some(Commands::Init) = &cli.command {
+        // projects without a git repository don't have a path
+        if let Some(Commands::Init) = &cli.command
+            && cli.options.get(1).is_none_or(|project| project.is_none())
+        {
             if let Some(path) = cli.options.get(0).and_then(|path| path.clone()) {
                 repository.set_repo_path(path);
             }
@@ -285,7 +884,19 @@ impl Cli<'_> {
 
     pub fn run_command<T>(cli: Cli<'_>, config: &mut T, prompt: &mut HelpPrompt)
     where
-        T: Init + Make + Edit + Update + Remove + List,
+        T: Init
+            + Make
+            + Edit
+            + Update
+            + Remove
+            + List
+            + Configure
+            + Log
+            + Entries
+            + Review
+            + History
+            + Lock
+            + Unlock,
     {
         match cli.command {
             None => {
@@ -298,6 +909,13 @@ impl Cli<'_> {
                 Commands::Remove => config.remove(cli.options, prompt),
                 Commands::Update => config.update(cli.options, prompt),
                 Commands::List => config.list(prompt),
+                Commands::Config => config.configure(cli.options),
+                Commands::Log => config.log(cli.options, prompt),
+                Commands::Entries => config.entries(cli.options, prompt),
+                Commands::Review => config.review(cli.options, prompt),
+                Commands::History => config.history(cli.options, prompt),
+                Commands::Lock => config.lock(cli.options, prompt),
+                Commands::Unlock => config.unlock(cli.options, prompt),
             },
         }
     }
@@ -329,7 +947,19 @@ mod tests {
     where
         I: Iterator<Item = T>,
         T: Into<OsString> + Clone,
-        K: Init + Make + Edit + Update + Remove + List,
+        K: Init
+            + Make
+            + Edit
+            + Update
+            + Remove
+            + List
+            + Configure
+            + Log
+            + Entries
+            + Review
+            + History
+            + Lock
+            + Unlock,
     {
         let cli = Cli::new_from(commands).unwrap();
         let new_cli = cli.parse_commands(&cli.matches);
@@ -388,6 +1018,48 @@ mod tests {
         }
     }
 
+    impl Configure for MockConfig {
+        fn configure(&self, _options: Vec<Option<String>>) {
+            assert!(true);
+        }
+    }
+
+    impl Log for MockConfig {
+        fn log(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
+    impl Entries for MockConfig {
+        fn entries(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
+    impl Review for MockConfig {
+        fn review(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
+    impl History for MockConfig {
+        fn history(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
+    impl Lock for MockConfig {
+        fn lock(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
+    impl Unlock for MockConfig {
+        fn unlock(&self, _options: Vec<Option<String>>, _prompt: &mut HelpPrompt) {
+            assert!(true);
+        }
+    }
+
     #[test]
     fn calls_config_init_with_a_init_command() {
         call_command_from_mock_config(["exename", "init"].iter(), MockConfig::new());
@@ -470,7 +1142,8 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["/this/is/a/path".to_string()]);
+        assert_eq!(values[..2], ["/this/is/a/path", "None"]);
+        assert!(values[2..].iter().all(|value| value == "None"));
         assert_eq!(result.command.unwrap().clone(), Commands::Init);
     }
 
@@ -484,7 +1157,18 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["None".to_string(), month, year]);
+        assert_eq!(
+            values,
+            vec![
+                "None".to_string(),
+                month,
+                year,
+                "None".to_string(),
+                "None".to_string(),
+                "None".to_string(),
+                "None".to_string()
+            ]
+        );
         assert_eq!(result.command.unwrap().clone(), Commands::Make);
     }
 
@@ -496,7 +1180,175 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["Alphabet", "10", "2020"]);
+        assert_eq!(
+            values,
+            vec!["Alphabet", "10", "2020", "None", "None", "None", "None"]
+        );
+    }
+
+    #[test]
+    fn returns_a_passed_format_and_output_for_make() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "make",
+                "-m10",
+                "--format=csv",
+                "--output=october.csv",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(values[3..], ["csv", "october.csv", "None", "None"]);
+    }
+
+    #[test]
+    fn returns_a_passed_pdf_path_for_make() {
+        let cli: Cli =
+            Cli::new_from(["exename", "make", "-m10", "--pdf=october.pdf"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(values[5], "october.pdf");
+    }
+
+    #[test]
+    fn returns_the_global_db_path_before_or_after_the_subcommand() {
+        let cli: Cli = Cli::new_from(["exename", "--db=/tmp/a.db", "list"].iter()).unwrap();
+        assert_eq!(
+            Cli::get_global_value(&cli.matches, "db"),
+            Some("/tmp/a.db".to_string())
+        );
+
+        let cli: Cli = Cli::new_from(["exename", "list", "--db=/tmp/b.db"].iter()).unwrap();
+        assert_eq!(
+            Cli::get_global_value(&cli.matches, "db"),
+            Some("/tmp/b.db".to_string())
+        );
+
+        let cli: Cli = Cli::new_from(["exename", "list"].iter()).unwrap();
+        assert_eq!(Cli::get_global_value(&cli.matches, "db"), None);
+    }
+
+    #[test]
+    fn returns_the_git_scan_options() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "--full-rescan",
+                "make",
+                "--since=2024-01-01",
+                "--until=2024-03-31",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            Cli::get_scan_options(&cli.matches),
+            GitScanOptions {
+                full_rescan: true,
+                since: NaiveDate::from_ymd_opt(2024, 1, 1),
+                until: NaiveDate::from_ymd_opt(2024, 3, 31),
+            }
+        );
+
+        let cli: Cli = Cli::new_from(["exename", "make"].iter()).unwrap();
+        assert_eq!(
+            Cli::get_scan_options(&cli.matches),
+            GitScanOptions::default()
+        );
+
+        assert!(Cli::new_from(["exename", "make", "--since=last week"].iter()).is_err());
+    }
+
+    #[test]
+    fn calls_config_configure_with_a_config_command() {
+        call_command_from_mock_config(["exename", "config"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_log_with_a_log_command() {
+        call_command_from_mock_config(["exename", "log", "-h2"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_entries_with_an_entries_command() {
+        call_command_from_mock_config(["exename", "entries"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_review_with_a_review_command() {
+        call_command_from_mock_config(["exename", "review"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_history_with_a_history_command() {
+        call_command_from_mock_config(["exename", "history"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_lock_with_a_lock_command() {
+        call_command_from_mock_config(["exename", "lock"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn calls_config_unlock_with_an_unlock_command() {
+        call_command_from_mock_config(["exename", "unlock"].iter(), MockConfig::new());
+    }
+
+    #[test]
+    fn returns_a_passed_key_and_value_for_config_set() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "config",
+                "set",
+                "share.endpoint",
+                "http://localhost:8080",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(result.command, Some(Commands::Config));
+        assert_eq!(values, ["set", "share.endpoint", "http://localhost:8080"]);
+    }
+
+    #[test]
+    fn returns_list_for_config_without_a_subcommand() {
+        let cli: Cli = Cli::new_from(["exename", "config"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values, ["list", "None", "None"]);
+    }
+
+    #[test]
+    fn returns_an_error_when_an_unknown_setting_is_passed_to_config() {
+        let result = Cli::new_from(["exename", "config", "set", "share.unknown", "value"].iter());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn returns_an_error_when_a_pdf_and_format_are_passed_to_make() {
+        let result = Cli::new_from(["exename", "make", "--pdf=a.pdf", "--format=csv"].iter());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn returns_an_error_when_an_unknown_format_is_passed_to_make() {
+        let result = Cli::new_from(["exename", "make", "--format=xlsx"].iter());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn returns_an_error_when_an_output_is_passed_to_make_without_a_format() {
+        let result = Cli::new_from(["exename", "make", "--output=october.csv"].iter());
+        assert!(result.is_err());
     }
 
     #[test]
@@ -530,7 +1382,257 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["autolog", "5", "15", "12", "2021"]);
+        assert_eq!(
+            values,
+            vec![
+                "autolog", "5", "15", "12", "2021", "None", "None", "None", "None", "None", "None"
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_a_passed_range_for_edit() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "edit",
+                "-n=autolog",
+                "--from=2026-10-01",
+                "--to=2026-10-15",
+                "--hours=6",
+                "--weekdays=mon,tue",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[1], "6");
+        assert_eq!(
+            values[6..],
+            ["2026-10-01", "2026-10-15", "mon,tue", "None", "None"]
+        );
+
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "edit",
+                "-n=autolog",
+                "--from=2026-10-01",
+                "--clear",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[1], "None");
+        assert_eq!(values[9], "clear");
+
+        assert!(Cli::new_from(["exename", "edit", "--clear", "-h5"].iter()).is_err());
+        assert!(Cli::new_from(["exename", "edit", "--to=2026-10-15", "-h5"].iter()).is_err());
+        assert!(
+            Cli::new_from(
+                [
+                    "exename",
+                    "edit",
+                    "--from=2026-10-01",
+                    "-h5",
+                    "--weekdays=mon,x"
+                ]
+                .iter()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn returns_a_passed_reset_for_edit() {
+        let reset = |args: &[&str]| {
+            let cli: Cli =
+                Cli::new_from(["exename", "edit", "-n=autolog"].iter().chain(args)).unwrap();
+            let new_cli = cli.parse_commands(&cli.matches);
+            unwrap_iter_with_option::<String>(new_cli.unwrap().options)[10].clone()
+        };
+
+        assert_eq!(reset(&["--reset", "-d2"]), "day");
+        assert_eq!(reset(&["--reset", "--from=2026-10-01"]), "day");
+        assert_eq!(reset(&["--reset=month", "-d1", "-m10"]), "month");
+        assert_eq!(reset(&["--reset=repo"]), "repo");
+
+        assert!(Cli::new_from(["exename", "edit", "--reset=week"].iter()).is_err());
+        assert!(Cli::new_from(["exename", "edit", "--reset", "-h5"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_a_passed_description_for_edit() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "edit",
+                "-n=autolog",
+                "-d15",
+                "--description=Release planning",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[0], "autolog");
+        assert_eq!(values[1], "None");
+        assert_eq!(values[5], "Release planning");
+
+        assert!(Cli::new_from(["exename", "edit", "-d15"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_a_passed_project_for_init() {
+        let cli: Cli = Cli::new_from(["exename", "init", "--project=design"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[1], "design");
+
+        assert!(Cli::new_from(["exename", "init", "-p=.", "--project=design"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_the_passed_client_details_for_init() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "--yes",
+                "init",
+                "--client=Alphabet",
+                "--contact=Jim Jones",
+                "--address-file=address.txt",
+                "--hours=7.5",
+                "--approver-name=Sally Smith",
+                "--approver-email=sally@alphabet.com",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert!(Cli::is_global_flag_present(&cli.matches, "yes"));
+
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(
+            values[2..],
+            [
+                "Alphabet",
+                "Jim Jones",
+                "address.txt",
+                "7.5",
+                "Sally Smith",
+                "sally@alphabet.com",
+                "None",
+                "None"
+            ]
+        );
+
+        let cli: Cli = Cli::new_from(["exename", "init", "--non-interactive"].iter()).unwrap();
+        assert!(Cli::is_global_flag_present(&cli.matches, "yes"));
+
+        assert!(Cli::new_from(["exename", "init", "--approver-name=Sally Smith"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_the_passed_project_numbers_for_make() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "make",
+                "--project-number=autolog=PO-1",
+                "--project-number",
+                "design=PO 2",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[6], "autolog=PO-1\ndesign=PO 2");
+
+        assert!(Cli::new_from(["exename", "make", "--project-number=PO-1"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_a_passed_value_for_log() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "log",
+                "-c=tomato",
+                "-p=design",
+                "-h1.5",
+                "-d15",
+                "-m12",
+                "-y2021",
+                "--note=Kick-off call",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(
+            values,
+            vec![
+                "tomato",
+                "design",
+                "1.5",
+                "15",
+                "12",
+                "2021",
+                "Kick-off call"
+            ]
+        );
+
+        assert!(Cli::new_from(["exename", "log", "-d15"].iter()).is_err());
+    }
+
+    #[test]
+    fn returns_a_passed_value_for_entries() {
+        let cli: Cli =
+            Cli::new_from(["exename", "entries", "-c=tomato", "--remove=abc123"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[0], "tomato");
+        assert_eq!(values[3], "abc123");
+    }
+
+    #[test]
+    fn returns_a_passed_value_for_review() {
+        let cli: Cli =
+            Cli::new_from(["exename", "review", "-c=tomato", "-m10", "-y2026"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(result.command, Some(Commands::Review));
+        assert_eq!(values, ["tomato", "10", "2026"]);
+    }
+
+    #[test]
+    fn returns_a_passed_value_for_history() {
+        let cli: Cli =
+            Cli::new_from(["exename", "history", "--client=tomato", "-m3"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(result.command, Some(Commands::History));
+        assert_eq!(values[..2], ["tomato", "3"]);
+    }
+
+    #[test]
+    fn returns_a_passed_value_for_lock() {
+        let cli: Cli =
+            Cli::new_from(["exename", "lock", "--client=tomato", "-m9", "-y2026"].iter()).unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let result = new_cli.unwrap();
+        let values = unwrap_iter_with_option::<String>(result.options);
+        assert_eq!(result.command, Some(Commands::Lock));
+        assert_eq!(values, ["tomato", "9", "2026"]);
     }
 
     #[test]
@@ -570,7 +1672,8 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["tomato", "None"]);
+        assert_eq!(values[..2], ["tomato", "None"]);
+        assert!(values[2..].iter().all(|value| value == "None"));
     }
 
     #[test]
@@ -581,7 +1684,116 @@ mod tests {
         let new_cli = cli.parse_commands(&cli.matches);
         let result = new_cli.unwrap();
         let values = unwrap_iter_with_option::<String>(result.options);
-        assert_eq!(values, vec!["tomato", "genius"]);
+        assert_eq!(values[..2], ["tomato", "genius"]);
+        assert!(values[2..].iter().all(|value| value == "None"));
+    }
+
+    #[test]
+    fn returns_the_passed_client_details_for_update() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "--yes",
+                "update",
+                "--client=tomato",
+                "--approver-email=sally@alphabet.com",
+                "--hours-estimation=commits",
+                "--lead-in-minutes=15",
+                "--hours-rounding=up_0.5",
+                "--timezone=Europe/London",
+                "--author-identity=Jim <jim@home.com>",
+                "--author-identity",
+                "jim@work.com",
+                "--mailmap=false",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(
+            values,
+            [
+                "tomato",
+                "None",
+                "None",
+                "sally@alphabet.com",
+                "None",
+                "None",
+                "None",
+                "None",
+                "None",
+                "commits",
+                "15",
+                "None",
+                "None",
+                "up_0.5",
+                "Europe/London",
+                "None",
+                "Jim <jim@home.com>\njim@work.com",
+                "false",
+                "None",
+                "None",
+                "None",
+                "None"
+            ]
+        );
+
+        assert!(
+            Cli::new_from(["exename", "update", "--client=tomato", "--hours-split=days"].iter())
+                .is_err()
+        );
+        assert!(
+            Cli::new_from(
+                [
+                    "exename",
+                    "update",
+                    "--client=tomato",
+                    "--lead-in-minutes=15"
+                ]
+                .iter()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn returns_the_passed_repository_details_for_update() {
+        let cli: Cli = Cli::new_from(
+            [
+                "exename",
+                "update",
+                "--client=tomato",
+                "--namespace=genius",
+                "--alias=ingenious",
+                "--include-refs=main,feature/*",
+                "--exclude-refs=",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let new_cli = cli.parse_commands(&cli.matches);
+        let values = unwrap_iter_with_option::<String>(new_cli.unwrap().options);
+        assert_eq!(values[..2], ["tomato", "genius"]);
+        assert_eq!(values[18..], ["ingenious", "None", "main,feature/*", ""]);
+
+        // repository details need a namespace, and client details can't have one
+        assert!(
+            Cli::new_from(["exename", "update", "--client=tomato", "--alias=x"].iter()).is_err()
+        );
+        assert!(
+            Cli::new_from(
+                [
+                    "exename",
+                    "update",
+                    "--client=tomato",
+                    "--namespace=genius",
+                    "--contact=Jim"
+                ]
+                .iter()
+            )
+            .is_err()
+        );
     }
 
     #[test]
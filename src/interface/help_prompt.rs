@@ -1,8 +1,20 @@
+use crate::data::author_identity::AuthorIdentity;
 use crate::data::client_repositories::ClientRepositories;
-use crate::data::repository::Repository;
+use crate::data::manual_entry::ManualEntry;
+use crate::data::repository::{
+    HOURS_ESTIMATION_COMMITS, HOURS_ESTIMATION_DEFAULT, HOURS_SPLIT_COMMITS, HOURS_SPLIT_EVEN,
+    HOURS_SPLIT_LINES, HOURS_SPLIT_SESSIONS, Repository,
+};
+use crate::data::timesheet_history::TimesheetChange;
+use crate::utils::date::date_parser::{
+    DEFAULT_COMMIT_LEAD_IN_MINUTES, DEFAULT_COMMIT_SESSION_GAP_MINUTES, HOURS_ROUNDING_OPTIONS,
+    is_valid_timezone,
+};
 use crate::utils::db::db_reader;
+use crate::utils::is_non_interactive;
 use ansi_term::Style;
 use ascii_table::AsciiTable;
+use chrono::NaiveDate;
 /// Help prompt handles all of the interactions with the user.
 /// It writes to the std output, and returns input data or a boolean
 use dialoguer::{Confirm, Editor, Input, Select};
@@ -12,11 +24,107 @@ use std::error::Error;
 
 pub type ConfigurationDoc = Vec<ClientRepositories>;
 
+/// Answers to the prompts passed as flags. A prompt with an answer isn't shown, and
+/// without a terminal the prompts that don't have a default need one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptAnswers {
+    pub client: Option<String>,
+    pub contact: Option<String>,
+    pub address_file: Option<String>,
+    pub hours: Option<String>,
+    pub approver_name: Option<String>,
+    pub approver_email: Option<String>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// Pairs of repository namespace and project number
+    pub project_numbers: Vec<(String, String)>,
+    pub client_name: Option<String>,
+    pub hours_estimation: Option<String>,
+    pub lead_in_minutes: Option<String>,
+    pub session_gap_minutes: Option<String>,
+    pub hours_split: Option<String>,
+    pub hours_rounding: Option<String>,
+    pub timezone: Option<String>,
+    pub day_start_hour: Option<String>,
+    /// One identity on each line
+    pub author_identities: Option<String>,
+    pub mailmap: Option<String>,
+    pub namespace_alias: Option<String>,
+    pub repo_path: Option<String>,
+    pub included_refs: Option<String>,
+    pub excluded_refs: Option<String>,
+}
+
+impl PromptAnswers {
+    /// Read from the init options, see Cli::parse_commands
+    pub fn from_init_options(options: &[Option<String>]) -> Self {
+        let option = |index: usize| options.get(index).cloned().flatten();
+
+        PromptAnswers {
+            client: option(2),
+            contact: option(3),
+            address_file: option(4),
+            hours: option(5),
+            approver_name: option(6),
+            approver_email: option(7),
+            name: option(8),
+            email: option(9),
+            ..Default::default()
+        }
+    }
+
+    /// Read from the update options, see Cli::parse_commands
+    pub fn from_update_options(options: &[Option<String>]) -> Self {
+        let option = |index: usize| options.get(index).cloned().flatten();
+
+        PromptAnswers {
+            approver_name: option(2),
+            approver_email: option(3),
+            client_name: option(4),
+            contact: option(5),
+            address_file: option(6),
+            name: option(7),
+            email: option(8),
+            hours_estimation: option(9),
+            lead_in_minutes: option(10),
+            session_gap_minutes: option(11),
+            hours_split: option(12),
+            hours_rounding: option(13),
+            timezone: option(14),
+            day_start_hour: option(15),
+            author_identities: option(16),
+            mailmap: option(17),
+            namespace_alias: option(18),
+            repo_path: option(19),
+            included_refs: option(20),
+            excluded_refs: option(21),
+            ..Default::default()
+        }
+    }
+
+    /// Read from the repo=PO pairs passed to make, one on each line
+    pub fn from_project_numbers(project_numbers: Option<&String>) -> Self {
+        PromptAnswers {
+            project_numbers: project_numbers
+                .map(|value| {
+                    value
+                        .lines()
+                        .filter_map(|pair| pair.split_once('='))
+                        .map(|(repo, number)| (repo.trim().to_string(), number.trim().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
 //TODO - consider using termion https://docs.rs/termion/1.5.6/termion/
 #[derive(Debug)]
 pub struct HelpPrompt<'a> {
     repository: &'a mut Repository,
     client_repositories: &'a mut ClientRepositories,
+    answers: PromptAnswers,
 }
 
 pub trait Onboarding {
@@ -62,6 +170,28 @@ impl<'a> HelpPrompt<'a> {
         Self {
             repository,
             client_repositories,
+            answers: PromptAnswers::default(),
+        }
+    }
+
+    pub fn set_answers(&mut self, answers: PromptAnswers) -> &mut Self {
+        self.answers = answers;
+        self
+    }
+
+    /// The answer passed as a flag, otherwise the answer to the prompt. When running
+    /// non-interactively there's nobody to ask, so the flag has to be passed
+    fn answer_or_prompt(
+        answer: &Option<String>,
+        flag: &str,
+        prompt: impl FnOnce() -> std::io::Result<String>,
+    ) -> Result<String, Box<dyn Error>> {
+        match answer {
+            Some(value) => Ok(value.clone()),
+            None if is_non_interactive() => {
+                Err(format!("{} is required when running non-interactively", flag).into())
+            }
+            None => Ok(prompt()?),
         }
     }
 
@@ -128,6 +258,14 @@ impl<'a> HelpPrompt<'a> {
         println!("{}", text);
     }
 
+    pub fn project_already_initialised(namespace: &str) {
+        println!(
+            "\u{1F916} A project or repository called '{}' has already been added.",
+            namespace
+        );
+        crate::utils::exit_process();
+    }
+
     pub fn client_or_repository_not_found() {
         println!("\n\u{1F916} Client or repository not found.");
         crate::utils::exit_process();
@@ -140,15 +278,193 @@ impl<'a> HelpPrompt<'a> {
         ));
     }
 
+    pub fn show_export_success(month_year_string: &str, path: &str) {
+        Self::print_question(&format!(
+            "Timesheet for {} written to {} \u{1F389}",
+            month_year_string, path
+        ));
+    }
+
+    pub fn show_month_locked(client_name: &str, month_year_string: &str, was_locked: bool) {
+        match was_locked {
+            true => Self::print_question(&format!(
+                "{} is already locked for {}",
+                month_year_string, client_name
+            )),
+            false => Self::print_question(&format!(
+                "{} is locked for {}. Its hours won't change until it's unlocked \u{1F512}",
+                month_year_string, client_name
+            )),
+        }
+    }
+
+    pub fn show_month_unlocked(client_name: &str, month_year_string: &str, was_locked: bool) {
+        match was_locked {
+            true => Self::print_question(&format!(
+                "{} is unlocked for {}. Its hours will be worked out from git again \u{1F513}",
+                month_year_string, client_name
+            )),
+            false => Self::print_question(&format!(
+                "{} isn't locked for {}",
+                month_year_string, client_name
+            )),
+        }
+    }
+
+    /// Written to stderr, as the timesheet may have been exported to stdout
+    pub fn show_locked_after_make(month_year_string: &str) {
+        let text = Self::dim_text(&format!(
+            "\u{1F512} {} is now locked so its hours won't change. Run 'autolog unlock' to change it.",
+            month_year_string
+        ));
+        eprintln!("{}", text);
+    }
+
+    pub fn show_setting_updated(key: &str, value: &str) {
+        Self::print_question(&format!("{} is now {} \u{1F389}", key, value));
+    }
+
+    pub fn show_settings(settings_path: &std::path::Path, rows: Vec<(String, String)>) {
+        Self::print_question(&format!("Settings ({})", settings_path.display()));
+        let ascii_table = AsciiTable::default();
+        let data: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|(key, value)| vec![Self::dim_text(&key), value])
+            .collect();
+        ascii_table.print(data);
+    }
+
+    pub fn show_manual_entry_added(client_name: &str, entry: &ManualEntry) {
+        Self::print_question(&format!(
+            "Logged {} hours for {} on {} \u{1F389}",
+            entry.hours,
+            client_name,
+            entry.date.format("%a %-d %b %Y")
+        ));
+    }
+
+    pub fn show_manual_entry_removed(entry: &ManualEntry) {
+        Self::print_question(&format!(
+            "Removed {} hours logged on {}",
+            entry.hours,
+            entry.date.format("%a %-d %b %Y")
+        ));
+    }
+
+    pub fn show_manual_entries(
+        client_name: &str,
+        month_year_string: &str,
+        entries: Vec<&ManualEntry>,
+    ) {
+        Self::print_question(&format!(
+            "Time logged for {} in {}",
+            client_name, month_year_string
+        ));
+
+        if entries.is_empty() {
+            println!("No time logged");
+            return;
+        }
+
+        let ascii_table = AsciiTable::default();
+        let rows: Vec<Vec<String>> = entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    Self::dim_text(&entry.id),
+                    entry.date.format("%a %-d %b").to_string(),
+                    entry.hours.to_string(),
+                    entry.get_namespace().to_string(),
+                    entry.note.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        ascii_table.print(rows);
+    }
+
+    pub fn show_timesheet_history(
+        client_name: &str,
+        month_year_string: &str,
+        changes: Vec<TimesheetChange>,
+    ) {
+        Self::print_question(&format!(
+            "Changes to the hours for {} in {}",
+            client_name, month_year_string
+        ));
+
+        if changes.is_empty() {
+            println!("No changes saved");
+            return;
+        }
+
+        let ascii_table = AsciiTable::default();
+        let rows: Vec<Vec<String>> = changes
+            .iter()
+            .map(|change| {
+                vec![
+                    Self::dim_text(
+                        &change
+                            .get_local_changed_at()
+                            .format("%-d %b %Y %H:%M")
+                            .to_string(),
+                    ),
+                    change.date.format("%a %-d %b").to_string(),
+                    change.namespace.clone(),
+                    change
+                        .old_hours
+                        .map(|hours| hours.to_string())
+                        .unwrap_or_default(),
+                    "\u{2192}".to_string(),
+                    change.new_hours.to_string(),
+                    change.source.clone(),
+                ]
+            })
+            .collect();
+        ascii_table.print(rows);
+    }
+
+    /// Show the hours each reset day had and will have once it's worked out from the git
+    /// history again, and confirm the reset
+    pub fn confirm_reset_days(
+        namespace: &str,
+        changes: Vec<(NaiveDate, Option<f64>, Option<f64>)>,
+    ) -> Result<bool, Box<dyn Error>> {
+        Self::print_question(&format!(
+            "These edited days of {} will be worked out from git again:",
+            namespace
+        ));
+
+        let hours = |hours: Option<f64>| hours.map(|hours| hours.to_string()).unwrap_or_default();
+        let ascii_table = AsciiTable::default();
+        let rows: Vec<Vec<String>> = changes
+            .into_iter()
+            .map(|(date, before, after)| {
+                vec![
+                    date.format("%a %-d %b %Y").to_string(),
+                    Self::dim_text(&hours(before)),
+                    "\u{2192}".to_string(),
+                    hours(after),
+                ]
+            })
+            .collect();
+        ascii_table.print(rows);
+
+        Self::print_question("Reset these days?");
+        crate::utils::confirm()
+    }
+
+    fn is_valid_email(value: &str) -> bool {
+        let re = Regex::new(r"^([a-zA-Z0-9_\-.]+)@([a-zA-Z0-9_\-.]+)\.([a-zA-Z]{2,5})$").unwrap();
+        re.is_match(value)
+    }
+
     fn take_and_validate_email(initial_text: Option<&str>) -> futures::io::Result<String> {
         let text = initial_text.unwrap_or_default();
 
         Input::new()
             .with_initial_text(text)
             .validate_with(|input: &String| -> Result<(), &str> {
-                let re = Regex::new(r"^([a-zA-Z0-9_\-.]+)@([a-zA-Z0-9_\-.]+)\.([a-zA-Z]{2,5})$")
-                    .unwrap();
-                if re.is_match(input) {
+                if Self::is_valid_email(input) {
                     Ok(())
                 } else {
                     Err("This is not a mail address")
@@ -161,6 +477,15 @@ impl<'a> HelpPrompt<'a> {
         &mut self,
         options: Vec<Option<String>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        // details passed as flags are changed without asking which to update
+        if self.update_from_answers(options[1].is_some())? {
+            return Ok(());
+        }
+
+        if is_non_interactive() {
+            return Err("Pass the details to change as flags when running non-interactively, see 'autolog update --help'".into());
+        }
+
         let client = self.client_repositories.client.as_ref().unwrap();
         let user = self.client_repositories.user.as_ref().unwrap();
 
@@ -171,7 +496,11 @@ impl<'a> HelpPrompt<'a> {
                 &options[0].as_ref().unwrap()
             ));
 
-            let opt = vec!["Namespace", "Repository path"];
+            // projects without git only have a name to change
+            let opt = match self.repository.is_git_repository() {
+                true => vec!["Namespace", "Repository path", "Branches and refs"],
+                false => vec!["Namespace"],
+            };
             let selection: usize = Select::new().items(&opt).interact()?;
             let value = opt[selection];
 
@@ -192,8 +521,13 @@ impl<'a> HelpPrompt<'a> {
                         .set_repo_path(input)
                         .find_repository_details_from()?;
                 }
+                "Branches and refs" => {
+                    self.prompt_for_ref_patterns()?;
+                }
                 _ => {}
             };
+            self.client_repositories
+                .update_repository(&*self.repository);
         } else {
             Self::print_question(&format!(
                 "Updating client '{}'. What would you like to update?",
@@ -208,6 +542,11 @@ impl<'a> HelpPrompt<'a> {
                 "Client address",
                 "User name",
                 "User email",
+                "Hours estimation",
+                "Hours split between repositories",
+                "Hours rounding",
+                "Timezone and day boundary",
+                "Author identities",
             ];
             let selection: usize = Select::new().items(&opt).interact()?;
             let value = opt[selection];
@@ -277,6 +616,21 @@ impl<'a> HelpPrompt<'a> {
                     self.client_repositories.set_user_email(input);
                     self.client_repositories.set_is_user_alias(true);
                 }
+                "Hours estimation" => {
+                    self.prompt_for_hours_estimation()?;
+                }
+                "Hours split between repositories" => {
+                    self.prompt_for_hours_split()?;
+                }
+                "Hours rounding" => {
+                    self.prompt_for_hours_rounding()?;
+                }
+                "Timezone and day boundary" => {
+                    self.prompt_for_day_boundary()?;
+                }
+                "Author identities" => {
+                    self.prompt_for_author_identities()?;
+                }
                 _ => {}
             };
         }
@@ -284,6 +638,207 @@ impl<'a> HelpPrompt<'a> {
         Ok(())
     }
 
+    /// Change the details passed as flags to update, returning false when none were passed.
+    /// They're checked in the same way as the answers to the prompts
+    fn update_from_answers(&mut self, is_repository: bool) -> Result<bool, Box<dyn Error>> {
+        let answers = self.answers.clone();
+
+        if is_repository {
+            if answers.namespace_alias.is_none()
+                && answers.repo_path.is_none()
+                && answers.included_refs.is_none()
+                && answers.excluded_refs.is_none()
+            {
+                return Ok(false);
+            }
+
+            let changes_git_details = answers.repo_path.is_some()
+                || answers.included_refs.is_some()
+                || answers.excluded_refs.is_some();
+            if changes_git_details && !self.repository.is_git_repository() {
+                return Err("Projects without git only have a namespace to change".into());
+            }
+
+            if let Some(alias) = answers.namespace_alias {
+                self.repository.set_namespace_alias(alias);
+            }
+            if let Some(path) = answers.repo_path {
+                self.repository
+                    .set_repo_path(path)
+                    .find_repository_details_from()?;
+            }
+            if answers.included_refs.is_some() || answers.excluded_refs.is_some() {
+                let included = answers
+                    .included_refs
+                    .map(|value| Self::split_ref_patterns(&value))
+                    .unwrap_or_else(|| self.repository.included_refs.clone().unwrap_or_default());
+                let excluded = answers
+                    .excluded_refs
+                    .map(|value| Self::split_ref_patterns(&value))
+                    .unwrap_or_else(|| self.repository.excluded_refs.clone().unwrap_or_default());
+                self.set_ref_patterns(included, excluded);
+            }
+            self.client_repositories
+                .update_repository(&*self.repository);
+
+            return Ok(true);
+        }
+
+        if answers == PromptAnswers::default() {
+            return Ok(false);
+        }
+
+        let check_email = |email: String, flag: &str| -> Result<String, Box<dyn Error>> {
+            match Self::is_valid_email(&email) {
+                true => Ok(email),
+                false => Err(format!("{} '{}' isn't an email address", flag, email).into()),
+            }
+        };
+        let parse_minutes = |minutes: Option<String>, flag: &str, default: i64| {
+            minutes.map_or(Ok(default), |minutes| {
+                minutes
+                    .trim()
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|minutes| (0..=1440).contains(minutes))
+                    .ok_or(format!(
+                        "{} '{}' isn't a number of minutes between 0 and 1440",
+                        flag, minutes
+                    ))
+            })
+        };
+
+        // check everything before changing anything, so a bad flag doesn't half update the client
+        let approver_email = answers
+            .approver_email
+            .map(|email| check_email(email, "--approver-email"))
+            .transpose()?;
+        let email = answers
+            .email
+            .map(|email| check_email(email, "--email"))
+            .transpose()?;
+        let address = answers
+            .address_file
+            .map(|path| {
+                std::fs::read_to_string(&path)
+                    .map(|address| address.trim().to_string())
+                    .map_err(|err| format!("Couldn't read the address file '{}': {}", path, err))
+            })
+            .transpose()?;
+        let lead_in_minutes = parse_minutes(
+            answers.lead_in_minutes,
+            "--lead-in-minutes",
+            DEFAULT_COMMIT_LEAD_IN_MINUTES,
+        )?;
+        let session_gap_minutes = parse_minutes(
+            answers.session_gap_minutes,
+            "--session-gap-minutes",
+            DEFAULT_COMMIT_SESSION_GAP_MINUTES,
+        )?;
+        let timezone = answers
+            .timezone
+            .map(|timezone| match timezone.trim() {
+                "" => Ok(None),
+                timezone if is_valid_timezone(timezone) => Ok(Some(timezone.to_string())),
+                timezone => Err(format!("--timezone '{}' isn't a known timezone", timezone)),
+            })
+            .transpose()?;
+        let day_start_hour = answers
+            .day_start_hour
+            .map(|hour| {
+                hour.trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|hour| *hour <= 23)
+                    .ok_or(format!(
+                        "--day-start-hour '{}' isn't an hour between 0 and 23",
+                        hour
+                    ))
+            })
+            .transpose()?;
+        let author_identities = answers
+            .author_identities
+            .map(|identities| AuthorIdentity::parse_lines(&identities))
+            .transpose()?;
+        let use_mailmap = answers.mailmap.map(|value| value == "true");
+
+        if let Some(name) = answers.approver_name {
+            self.client_repositories.set_approvers_name(name);
+            self.client_repositories.set_requires_approval(true);
+        }
+        if let Some(email) = approver_email {
+            self.client_repositories.set_approvers_email(email);
+            self.client_repositories.set_requires_approval(true);
+        }
+        if let Some(client_name) = answers.client_name {
+            self.client_repositories.update_client_name(client_name);
+        }
+        if let Some(contact) = answers.contact {
+            self.client_repositories
+                .update_client_contact_person(contact);
+        }
+        if let Some(address) = address {
+            self.client_repositories.update_client_address(address);
+        }
+        if let Some(name) = answers.name {
+            self.client_repositories.set_user_name(name);
+            self.client_repositories.set_is_user_alias(true);
+        }
+        if let Some(email) = email {
+            self.client_repositories.set_user_email(email);
+            self.client_repositories.set_is_user_alias(true);
+        }
+        match answers.hours_estimation.as_deref() {
+            Some(HOURS_ESTIMATION_COMMITS) => {
+                self.client_repositories.update_hours_estimation(
+                    HOURS_ESTIMATION_COMMITS.to_string(),
+                    Some(lead_in_minutes),
+                    Some(session_gap_minutes),
+                );
+            }
+            Some(mode) => {
+                self.client_repositories
+                    .update_hours_estimation(mode.to_string(), None, None);
+            }
+            None => {}
+        }
+        if let Some(hours_split) = answers.hours_split {
+            self.client_repositories.update_hours_split(hours_split);
+        }
+        if let Some(hours_rounding) = answers.hours_rounding {
+            self.client_repositories
+                .update_hours_rounding(hours_rounding);
+        }
+
+        // the timezone and day start hour are set together, so keep the one that wasn't passed
+        let repository = self
+            .client_repositories
+            .repositories
+            .as_ref()
+            .and_then(|repos| repos.first());
+        let current_timezone = repository.and_then(|repo| repo.timezone.clone());
+        let current_day_start_hour = repository.and_then(|repo| repo.day_start_hour).unwrap_or(0);
+        let current_identities = repository
+            .and_then(|repo| repo.author_identities.clone())
+            .unwrap_or_default();
+        let current_use_mailmap = repository.is_some_and(|repo| repo.uses_mailmap());
+
+        if timezone.is_some() || day_start_hour.is_some() {
+            self.client_repositories.update_day_boundary(
+                timezone.unwrap_or(current_timezone),
+                day_start_hour.unwrap_or(current_day_start_hour),
+            );
+        }
+        if author_identities.is_some() || use_mailmap.is_some() {
+            self.client_repositories.update_author_identities(
+                author_identities.unwrap_or(current_identities),
+                use_mailmap.unwrap_or(current_use_mailmap),
+            );
+        }
+
+        Ok(true)
+    }
+
     pub fn prompt_for_client_then_onboard(
         &mut self,
         deserialized_config: &mut ConfigurationDoc,
@@ -303,8 +858,13 @@ impl<'a> HelpPrompt<'a> {
         let no_client_value = "Create a new client".to_string();
         clients.push(no_client_value.clone());
 
-        Self::print_question("Would you like to add it to any of these existing clients?");
-        let selection: usize = Select::new().items(&clients).interact()?;
+        let selection: usize = match self.find_client_answer(&clients) {
+            Some(selection) => selection,
+            None => {
+                Self::print_question("Would you like to add it to any of these existing clients?");
+                Select::new().items(&clients).interact()?
+            }
+        };
         let client_name = &clients[selection];
 
         // if this is a new client, onboard as normal
@@ -327,12 +887,140 @@ impl<'a> HelpPrompt<'a> {
                 .set_client_address(unwrapped_client.client_address.clone())
                 .set_client_contact_person(unwrapped_client.client_contact_person.clone());
 
+            // new repositories work out their hours in the same way as the rest of the client
+            if let Some(existing_repo) =
+                client.repositories.as_ref().and_then(|repos| repos.first())
+            {
+                if let Some(mode) = existing_repo.hours_estimation.as_ref() {
+                    self.repository.set_hours_estimation(
+                        mode.clone(),
+                        existing_repo.commit_lead_in_minutes,
+                        existing_repo.commit_session_gap_minutes,
+                    );
+                }
+                if let Some(hours_split) = existing_repo.hours_split.as_ref() {
+                    self.repository.set_hours_split(hours_split.clone());
+                }
+                if let Some(hours_rounding) = existing_repo.hours_rounding.as_ref() {
+                    self.repository.set_hours_rounding(hours_rounding.clone());
+                }
+                if let Some(identities) = existing_repo.author_identities.as_ref() {
+                    self.repository
+                        .set_author_identities(identities.clone(), existing_repo.uses_mailmap());
+                }
+                if let Some(day_start_hour) = existing_repo.day_start_hour {
+                    self.repository
+                        .set_day_boundary(existing_repo.timezone.clone(), day_start_hour);
+                }
+            }
+
             self.existing_client_onboarding(deserialized_config)?;
         }
 
         Ok(())
     }
 
+    /// Add a project without a git repository to a new or existing client. Its days only
+    /// come from the time logged against it with 'autolog log'
+    pub fn prompt_for_client_then_onboard_project(
+        &mut self,
+        deserialized_config: &ConfigurationDoc,
+        namespace: String,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::print_question(&format!(
+            "\u{1F916} Initialising new project '{}' without a git repository.",
+            namespace
+        ));
+
+        let mut clients: Vec<String> = deserialized_config
+            .iter()
+            .map(|client| client.get_client_name().unwrap().clone())
+            .collect();
+
+        let no_client_value = "Create a new client".to_string();
+        clients.push(no_client_value.clone());
+
+        let selection: usize = match self.find_client_answer(&clients) {
+            Some(selection) => selection,
+            None if clients.len() > 1 => {
+                Self::print_question("Would you like to add it to any of these existing clients?");
+                Select::new().items(&clients).interact()?
+            }
+            None => 0,
+        };
+        let client_name = &clients[selection];
+
+        if client_name == &no_client_value {
+            self.add_user_details()?
+                .add_client_details()?
+                .prompt_for_manager_approval()?;
+        } else {
+            let client = deserialized_config
+                .iter()
+                .find(|client| &client.get_client_name().unwrap() == client_name)
+                .unwrap();
+
+            self.client_repositories.set_values_from_buffer(client);
+
+            let unwrapped_client = client.client.as_ref().unwrap();
+
+            self.repository
+                .set_client_id(unwrapped_client.id.clone())
+                .set_client_name(unwrapped_client.client_name.clone())
+                .set_client_address(unwrapped_client.client_address.clone())
+                .set_client_contact_person(unwrapped_client.client_contact_person.clone());
+
+            if let Some(user) = client.user.as_ref() {
+                self.repository
+                    .set_user_id(user.id.clone())
+                    .set_name(user.name.clone())
+                    .set_email(user.email.clone());
+            }
+        }
+
+        self.repository
+            .set_namespace(namespace)
+            .set_repository_id(nanoid!());
+        self.show_details();
+
+        Ok(())
+    }
+
+    /// The client passed with --client, where the last of the clients is a new client. When
+    /// it isn't passed and there's nobody to ask, a new client is created and needs its details
+    fn find_client_answer(&self, clients: &[String]) -> Option<usize> {
+        let new_client = clients.len() - 1;
+        match self.answers.client.as_ref() {
+            Some(name) => Some(
+                clients[..new_client]
+                    .iter()
+                    .position(|client| client.to_lowercase() == name.to_lowercase())
+                    .unwrap_or(new_client),
+            ),
+            None if is_non_interactive() => Some(new_client),
+            None => None,
+        }
+    }
+
+    /// Without a git repository the user details can't be read from the git config
+    fn add_user_details(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        let input = Self::answer_or_prompt(&self.answers.name, "--name", || {
+            Self::print_question("Your name");
+            Input::new().interact_text()
+        })?;
+        self.repository.set_name(input);
+
+        let input = Self::answer_or_prompt(&self.answers.email, "--email", || {
+            Self::print_question("Your email");
+            Self::take_and_validate_email(Option::None)
+        })?;
+        self.repository.set_email(input);
+
+        self.repository.set_user_id(nanoid!());
+
+        Ok(self)
+    }
+
     fn print_question(text: &str) {
         println!("\n{}", Style::new().bold().paint(text));
     }
@@ -365,6 +1053,11 @@ impl<'a> HelpPrompt<'a> {
             println!("{}", Style::new().dimmed().paint(version));
         }
 
+        if is_non_interactive() {
+            self.repository.set_repo_path(path);
+            return Ok(self);
+        }
+
         let current_repo_path = db_reader::get_canonical_path(".");
         if path == current_repo_path {
             Self::print_question("Initialise for current repository?");
@@ -463,8 +1156,12 @@ impl<'a> HelpPrompt<'a> {
                 let email = &user.email;
                 let is_alias = &user.is_alias;
 
-                // if the user details differ, prompt for alias
-                if self.repository.has_different_user_details(name, email) & !is_alias {
+                // if the user details differ, prompt for alias. There's no flag for one, so
+                // running non-interactively uses the repository's details
+                if self.repository.has_different_user_details(name, email)
+                    & !is_alias
+                    & !is_non_interactive()
+                {
                     self.prompt_for_setting_user_alias(name.to_string(), email.to_string())?;
                 }
             }
@@ -473,19 +1170,35 @@ impl<'a> HelpPrompt<'a> {
         Ok(self)
     }
 
-    pub fn add_client_details(&mut self) -> Result<&mut Self, std::io::Error> {
-        Self::print_question("Client company name");
-        let input: String = Input::new().interact_text()?;
+    pub fn add_client_details(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        let input = Self::answer_or_prompt(&self.answers.client, "--client", || {
+            Self::print_question("Client company name");
+            Input::new().interact_text()
+        })?;
         self.repository.set_client_name(input);
 
-        Self::print_question("Client contact person");
-        let input: String = Input::new().interact_text()?;
+        let input = Self::answer_or_prompt(&self.answers.contact, "--contact", || {
+            Self::print_question("Client contact person");
+            Input::new().interact_text()
+        })?;
         self.repository.set_client_contact_person(input);
 
-        Self::print_question("Would you like to add a Client address?");
-        if Confirm::new().default(true).interact()? {
-            if let Some(input) = Editor::new().edit("Enter an address").unwrap() {
-                self.repository.set_client_address(input);
+        match self.answers.address_file.as_ref() {
+            Some(path) => {
+                let address = std::fs::read_to_string(path)
+                    .map_err(|err| format!("Couldn't read the address file '{}': {}", path, err))?;
+                self.repository
+                    .set_client_address(address.trim().to_string());
+            }
+            // the address is optional
+            None if is_non_interactive() => {}
+            None => {
+                Self::print_question("Would you like to add a Client address?");
+                if Confirm::new().default(true).interact()? {
+                    if let Some(input) = Editor::new().edit("Enter an address").unwrap() {
+                        self.repository.set_client_address(input);
+                    }
+                }
             }
         }
         self.repository.set_client_id(nanoid!());
@@ -493,7 +1206,26 @@ impl<'a> HelpPrompt<'a> {
         Ok(self)
     }
 
-    pub fn specify_default_hours(&mut self) -> Result<&mut Self, std::io::Error> {
+    pub fn specify_default_hours(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        if let Some(hours) = self.answers.hours.as_ref() {
+            let hours = hours
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|hours| (0.0..=24.0).contains(hours))
+                .ok_or(format!(
+                    "--hours '{}' isn't a number between 0 and 24",
+                    hours
+                ))?;
+            self.repository.set_default_hours(hours);
+            return Ok(self);
+        }
+
+        if is_non_interactive() {
+            self.repository.set_default_hours(8.0);
+            return Ok(self);
+        }
+
         loop {
             Self::print_question("Default hours per day (0-24)");
 
@@ -520,6 +1252,274 @@ impl<'a> HelpPrompt<'a> {
         }
     }
 
+    fn prompt_for_minutes(text: &str, default: i64) -> Result<i64, std::io::Error> {
+        loop {
+            Self::print_question(text);
+
+            let input: String = Input::new()
+                .default(default.to_string())
+                .show_default(true)
+                .interact_text()?;
+
+            match input.trim().parse::<i64>() {
+                Ok(minutes) if (0..=1440).contains(&minutes) => return Ok(minutes),
+                _ => {
+                    eprintln!("Invalid input. Please enter a number of minutes between 0 and 1440.")
+                }
+            }
+        }
+    }
+
+    pub fn prompt_for_hours_estimation(&mut self) -> Result<&mut Self, std::io::Error> {
+        Self::print_question("How should the hours worked each day be worked out?");
+
+        let opt = vec![
+            "Default hours for each day with a commit",
+            "Estimate from commit times",
+        ];
+        let selection: usize = Select::new().items(&opt).default(0).interact()?;
+
+        if selection == 0 {
+            self.client_repositories.update_hours_estimation(
+                HOURS_ESTIMATION_DEFAULT.to_string(),
+                None,
+                None,
+            );
+            return Ok(self);
+        }
+
+        println!(
+            "{}",
+            Self::dim_text(
+                "Each session of commits is counted from its first to its last commit, plus a lead-in before the first commit."
+            )
+        );
+        let lead_in_minutes = Self::prompt_for_minutes(
+            "Minutes of work before the first commit",
+            DEFAULT_COMMIT_LEAD_IN_MINUTES,
+        )?;
+        let session_gap_minutes = Self::prompt_for_minutes(
+            "Minutes between commits before a new session starts",
+            DEFAULT_COMMIT_SESSION_GAP_MINUTES,
+        )?;
+
+        self.client_repositories.update_hours_estimation(
+            HOURS_ESTIMATION_COMMITS.to_string(),
+            Some(lead_in_minutes),
+            Some(session_gap_minutes),
+        );
+
+        Ok(self)
+    }
+
+    pub fn prompt_for_hours_split(&mut self) -> Result<&mut Self, std::io::Error> {
+        Self::print_question(
+            "When repositories share a day, how should its hours be split between them?",
+        );
+
+        let opt = [
+            ("Evenly", HOURS_SPLIT_EVEN),
+            ("By number of commits", HOURS_SPLIT_COMMITS),
+            ("By lines changed", HOURS_SPLIT_LINES),
+            (
+                "By session time estimated from commit times",
+                HOURS_SPLIT_SESSIONS,
+            ),
+        ];
+        let items: Vec<&str> = opt.iter().map(|(label, _)| *label).collect();
+        let selection: usize = Select::new().items(&items).default(0).interact()?;
+
+        self.client_repositories
+            .update_hours_split(opt[selection].1.to_string());
+
+        Ok(self)
+    }
+
+    pub fn prompt_for_hours_rounding(&mut self) -> Result<&mut Self, std::io::Error> {
+        Self::print_question("How should hours be rounded?");
+        println!(
+            "{}",
+            Self::dim_text(
+                "Days shared between repositories are split in these increments and still add up to your default hours."
+            )
+        );
+
+        let labels = [
+            "No rounding",
+            "Nearest quarter hour",
+            "Nearest half hour",
+            "Nearest hour",
+            "Up to the quarter hour",
+            "Up to the half hour",
+            "Up to the hour",
+        ];
+        let selection: usize = Select::new().items(&labels).default(3).interact()?;
+
+        self.client_repositories
+            .update_hours_rounding(HOURS_ROUNDING_OPTIONS[selection].to_string());
+
+        Ok(self)
+    }
+
+    pub fn prompt_for_day_boundary(&mut self) -> Result<&mut Self, std::io::Error> {
+        let repository = self
+            .client_repositories
+            .repositories
+            .as_ref()
+            .and_then(|repos| repos.first());
+        let current_timezone = repository
+            .and_then(|repo| repo.timezone.clone())
+            .unwrap_or_default();
+        let current_day_start_hour = repository.and_then(|repo| repo.day_start_hour).unwrap_or(0);
+
+        let timezone = loop {
+            Self::print_question("Timezone (e.g. Europe/London)");
+            println!(
+                "{}",
+                Self::dim_text("Leave blank to use the timezone each commit was made in.")
+            );
+
+            let input: String = Input::new()
+                .with_initial_text(&current_timezone)
+                .allow_empty(true)
+                .interact_text()?;
+            let input = input.trim().to_string();
+
+            if input.is_empty() {
+                break None;
+            } else if is_valid_timezone(&input) {
+                break Some(input);
+            } else {
+                eprintln!("'{}' isn't a known timezone. Please try again.", input);
+            }
+        };
+
+        let day_start_hour = loop {
+            Self::print_question("Hour the working day starts (0-23)");
+            println!(
+                "{}",
+                Self::dim_text("Commits made before this hour count towards the previous day.")
+            );
+
+            let input: String = Input::new()
+                .default(current_day_start_hour.to_string())
+                .show_default(true)
+                .interact_text()?;
+
+            match input.trim().parse::<u32>() {
+                Ok(hour) if hour <= 23 => break hour,
+                _ => eprintln!("Invalid input. Please enter an hour between 0 and 23."),
+            }
+        };
+
+        self.client_repositories
+            .update_day_boundary(timezone, day_start_hour);
+
+        Ok(self)
+    }
+
+    pub fn prompt_for_author_identities(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        let repository = self
+            .client_repositories
+            .repositories
+            .as_ref()
+            .and_then(|repos| repos.first());
+        let current_identities = repository
+            .and_then(|repo| repo.author_identities.as_ref())
+            .map(|identities| {
+                identities
+                    .iter()
+                    .map(|identity| identity.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        let current_use_mailmap = repository.is_some_and(|repo| repo.uses_mailmap());
+
+        Self::print_question("Other names and emails you commit under");
+        println!(
+            "{}",
+            Self::dim_text(
+                "One per line, as a name, an email, Name <email>, or a /regex/ matched against Name <email>."
+            )
+        );
+
+        let identities = loop {
+            let input = match Editor::new().edit(&current_identities)? {
+                Some(input) => input,
+                None => current_identities.clone(),
+            };
+
+            match AuthorIdentity::parse_lines(&input) {
+                Ok(identities) => break identities,
+                Err(err) => {
+                    eprintln!("{}. Please try again.", err);
+                    if !Confirm::new().default(true).interact()? {
+                        return Ok(self);
+                    }
+                }
+            }
+        };
+
+        Self::print_question("Map names and emails through the repository's .mailmap?");
+        let use_mailmap = Confirm::new().default(current_use_mailmap).interact()?;
+
+        self.client_repositories
+            .update_author_identities(identities, use_mailmap);
+
+        Ok(self)
+    }
+
+    fn split_ref_patterns(input: &str) -> Vec<String> {
+        input
+            .split(',')
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    }
+
+    fn set_ref_patterns(&mut self, included: Vec<String>, excluded: Vec<String>) {
+        self.repository
+            .set_ref_patterns(included.clone(), excluded.clone());
+        if let Some(repository_id) = self.repository.id.clone() {
+            self.client_repositories
+                .update_ref_patterns(&repository_id, included, excluded);
+        }
+    }
+
+    pub fn prompt_for_ref_patterns(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        let join = |patterns: &Option<Vec<String>>| patterns.clone().unwrap_or_default().join(", ");
+
+        Self::print_question("Only read history from these branches and refs");
+        println!(
+            "{}",
+            Self::dim_text(
+                "Comma separated, e.g. main, feature/*. Leave blank to read from every branch and ref."
+            )
+        );
+        let included: String = Input::new()
+            .with_initial_text(join(&self.repository.included_refs))
+            .allow_empty(true)
+            .interact_text()?;
+
+        Self::print_question("Skip these branches and refs");
+        println!(
+            "{}",
+            Self::dim_text("Comma separated, e.g. refs/stash, experiment/*.")
+        );
+        let excluded: String = Input::new()
+            .with_initial_text(join(&self.repository.excluded_refs))
+            .allow_empty(true)
+            .interact_text()?;
+
+        self.set_ref_patterns(
+            Self::split_ref_patterns(&included),
+            Self::split_ref_patterns(&excluded),
+        );
+
+        Ok(self)
+    }
+
     pub fn prompt_for_manager_approval(&mut self) -> Result<&mut Self, Box<dyn Error>> {
         let prompt_for_approver = match self.client_repositories.requires_approval {
             None => true,
@@ -527,24 +1527,43 @@ impl<'a> HelpPrompt<'a> {
         };
 
         if prompt_for_approver {
-            Self::print_question("Do timesheets under this client require approval?");
-            println!(
-                "{}",
-                Self::dim_text(
-                    "(This will enable signing functionality, see https://autolog.dev/documentation#approvals)",
-                )
-            );
+            // passing an approver answers yes, and running non-interactively without one no
+            let requires_approval = if self.answers.approver_name.is_some()
+                || self.answers.approver_email.is_some()
+            {
+                true
+            } else if is_non_interactive() {
+                false
+            } else {
+                Self::print_question("Do timesheets under this client require approval?");
+                println!(
+                    "{}",
+                    Self::dim_text(
+                        "(This will enable signing functionality, see https://autolog.dev/documentation#approvals)",
+                    )
+                );
+                Confirm::new().default(true).interact()?
+            };
 
-            if Confirm::new().default(true).interact()? {
-                Self::print_question("Approvers name");
-                let input: String = Input::new().interact_text()?;
+            if requires_approval {
+                let input =
+                    Self::answer_or_prompt(&self.answers.approver_name, "--approver-name", || {
+                        Self::print_question("Approvers name");
+                        Input::new().interact_text()
+                    })?;
                 if input.is_empty() {
                     return Err("Approvers name cannot be empty".into());
                 }
                 self.client_repositories.set_approvers_name(input);
 
-                Self::print_question("Approvers email");
-                let input: String = Input::new().interact_text()?;
+                let input = Self::answer_or_prompt(
+                    &self.answers.approver_email,
+                    "--approver-email",
+                    || {
+                        Self::print_question("Approvers email");
+                        Input::new().interact_text()
+                    },
+                )?;
                 if input.is_empty() {
                     return Err("Approvers email cannot be empty".into());
                 }
@@ -565,6 +1584,33 @@ impl<'a> HelpPrompt<'a> {
         Ok(self)
     }
 
+    fn is_same_namespace(a: &str, b: &str) -> bool {
+        a.to_lowercase() == b.to_lowercase()
+    }
+
+    /// Set the project numbers passed with --project-number, failing if any of them are for
+    /// a repository or project the client doesn't have
+    pub fn set_passed_project_numbers(&mut self) -> Result<&mut Self, Box<dyn Error>> {
+        let repositories = self.client_repositories.repositories.as_mut().unwrap();
+
+        for (namespace, number) in self.answers.project_numbers.iter() {
+            let repository = repositories
+                .iter_mut()
+                .find(|repo| {
+                    repo.namespace
+                        .as_ref()
+                        .is_some_and(|x| Self::is_same_namespace(x, namespace))
+                })
+                .ok_or(format!(
+                    "'{}' isn't a repository or project under this client",
+                    namespace
+                ))?;
+            repository.set_project_number(number.clone());
+        }
+
+        Ok(self)
+    }
+
     pub fn add_project_numbers(&mut self) -> Result<&mut Self, Box<dyn Error>> {
         println!(
             "{}",
@@ -574,6 +1620,13 @@ impl<'a> HelpPrompt<'a> {
             ))
         );
 
+        self.set_passed_project_numbers()?;
+
+        // running non-interactively keeps the project numbers already stored
+        if is_non_interactive() {
+            return Ok(self);
+        }
+
         for i in 0..self
             .client_repositories
             .repositories
@@ -581,12 +1634,22 @@ impl<'a> HelpPrompt<'a> {
             .unwrap()
             .len()
         {
+            let namespace = self.client_repositories.repositories.as_ref().unwrap()[i]
+                .namespace
+                .clone()
+                .unwrap();
+            if self
+                .answers
+                .project_numbers
+                .iter()
+                .any(|(x, _)| Self::is_same_namespace(x, &namespace))
+            {
+                continue;
+            }
+
             Self::print_question(&format!(
                 "Does '{}' require a project/PO number?",
-                self.client_repositories.repositories.as_ref().unwrap()[i]
-                    .namespace
-                    .as_ref()
-                    .unwrap()
+                namespace
             ));
             if Confirm::new().default(true).interact()? {
                 Self::print_question("Project number");
@@ -751,7 +1814,13 @@ impl<'a> HelpPrompt<'a> {
                     let ascii_table = AsciiTable::default();
                     let rows: Vec<Vec<String>> = repositories
                         .iter()
-                        .map(|repo| vec![repo.namespace.clone().unwrap()])
+                        .map(|repo| {
+                            let kind = match repo.is_git_repository() {
+                                true => "git",
+                                false => "no git",
+                            };
+                            vec![repo.namespace.clone().unwrap(), Self::dim_text(kind)]
+                        })
                         .collect();
 
                     ascii_table.print(rows);
@@ -766,3 +1835,118 @@ impl<'a> HelpPrompt<'a> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::New;
+    use crate::helpers::mocks;
+
+    #[test]
+    fn it_reads_prompt_answers_from_options() {
+        let options: Vec<Option<String>> = [
+            Some("/path"),
+            None,
+            Some("Alphabet"),
+            Some("John Smith"),
+            None,
+            Some("7.5"),
+            None,
+            None,
+            None,
+            None,
+        ]
+        .iter()
+        .map(|x| x.map(String::from))
+        .collect();
+
+        let answers = PromptAnswers::from_init_options(&options);
+        assert_eq!(answers.client, Some("Alphabet".to_string()));
+        assert_eq!(answers.contact, Some("John Smith".to_string()));
+        assert_eq!(answers.hours, Some("7.5".to_string()));
+        assert_eq!(answers.approver_name, None);
+
+        let answers =
+            PromptAnswers::from_project_numbers(Some(&"autolog=PO-1\ndesign = PO 2".to_string()));
+        assert_eq!(
+            answers.project_numbers,
+            vec![
+                ("autolog".to_string(), "PO-1".to_string()),
+                ("design".to_string(), "PO 2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn it_updates_a_client_from_passed_answers() {
+        let mut repository = Repository::new();
+        let mut client_repositories = ClientRepositories::new();
+        mocks::create_mock_client_repository(&mut client_repositories);
+
+        let mut prompt = HelpPrompt::new(&mut repository, &mut client_repositories);
+        assert!(!prompt.update_from_answers(false).unwrap());
+
+        prompt.set_answers(PromptAnswers {
+            approver_email: Some("sally@alphabet.com".to_string()),
+            hours_estimation: Some(HOURS_ESTIMATION_COMMITS.to_string()),
+            session_gap_minutes: Some("60".to_string()),
+            day_start_hour: Some("4".to_string()),
+            author_identities: Some("jim@work.com".to_string()),
+            ..Default::default()
+        });
+        assert!(prompt.update_from_answers(false).unwrap());
+
+        let client_repositories = prompt.client_repositories();
+        assert_eq!(client_repositories.get_approver().1, "sally@alphabet.com");
+        assert_eq!(client_repositories.requires_approval, Some(true));
+        let repo = &client_repositories.repositories.as_ref().unwrap()[0];
+        assert_eq!(
+            repo.hours_estimation.as_deref(),
+            Some(HOURS_ESTIMATION_COMMITS)
+        );
+        assert_eq!(
+            repo.commit_lead_in_minutes,
+            Some(DEFAULT_COMMIT_LEAD_IN_MINUTES)
+        );
+        assert_eq!(repo.commit_session_gap_minutes, Some(60));
+        assert_eq!(repo.day_start_hour, Some(4));
+        assert_eq!(repo.author_identities.as_ref().unwrap().len(), 1);
+
+        // nothing is changed when one of the answers is invalid
+        prompt.set_answers(PromptAnswers {
+            client_name: Some("Tomato".to_string()),
+            timezone: Some("Mars/Olympus".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            prompt.update_from_answers(false).unwrap_err().to_string(),
+            "--timezone 'Mars/Olympus' isn't a known timezone"
+        );
+        assert_eq!(
+            prompt.client_repositories().get_client_name(),
+            Some("alphabet".to_string())
+        );
+    }
+
+    #[test]
+    fn it_sets_passed_project_numbers() {
+        let mut repository = Repository::new();
+        let mut client_repositories = ClientRepositories::new();
+        mocks::create_mock_client_repository(&mut client_repositories);
+
+        let mut prompt = HelpPrompt::new(&mut repository, &mut client_repositories);
+        prompt.set_answers(PromptAnswers::from_project_numbers(Some(
+            &"Autolog=PO-1".to_string(),
+        )));
+        prompt.set_passed_project_numbers().unwrap();
+        assert_eq!(
+            prompt.client_repositories().repositories.as_ref().unwrap()[0].project_number,
+            Some("PO-1".to_string())
+        );
+
+        prompt.set_answers(PromptAnswers::from_project_numbers(Some(
+            &"figma=PO-2".to_string(),
+        )));
+        assert!(prompt.set_passed_project_numbers().is_err());
+    }
+}
@@ -1,2 +1,3 @@
 pub mod cli;
 pub mod help_prompt;
+pub mod review;
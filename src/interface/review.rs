@@ -0,0 +1,420 @@
+use crate::data::repository::Repository;
+use crate::data::timesheet_history::{self, ChangedDays};
+use crate::utils::date::date_parser::{create_single_day_object, get_days_from_month, is_weekend};
+use chrono::NaiveDate;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::error::Error;
+
+const HELP_TEXT: &str = "\u{2190}\u{2191}\u{2192}\u{2193} move  0-9 type hours  enter set  \
+    esc cancel  s save and quit  q quit without saving";
+
+/// The hours on a day of a repository's timesheet, and whether the user set them
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewCell {
+    pub hours: f64,
+    pub user_edited: bool,
+}
+
+/// A month of a client's timesheet laid out with a row for each day and a column for each
+/// repository, which is edited with the keyboard before being written back
+#[derive(Debug, Clone)]
+pub struct ReviewGrid {
+    pub year: i32,
+    pub month: u32,
+    pub namespaces: Vec<String>,
+    pub weekends: Vec<bool>,
+    /// A column of days for each repository
+    pub cells: Vec<Vec<ReviewCell>>,
+    /// The index of each column's repository in the client's repositories
+    repository_indexes: Vec<usize>,
+    /// The cells changed in this review, as (day, column)
+    changed: HashSet<(usize, usize)>,
+    pub cursor: (usize, usize),
+    pub input: Option<String>,
+    pub message: Option<String>,
+}
+
+impl ReviewGrid {
+    /// Lay out the repositories that have timesheet data for the month
+    pub fn from_repositories(repositories: &[Repository], year: i32, month: u32) -> Self {
+        let days_in_month = get_days_from_month(year, month) as usize;
+        let mut grid = ReviewGrid {
+            year,
+            month,
+            namespaces: vec![],
+            weekends: (1..=days_in_month as u32)
+                .map(|day| is_weekend(&(year, month, days_in_month as u32), day))
+                .collect(),
+            cells: vec![],
+            repository_indexes: vec![],
+            changed: HashSet::new(),
+            cursor: (0, 0),
+            input: None,
+            message: None,
+        };
+
+        for (index, repository) in repositories.iter().enumerate() {
+            let days = repository
+                .timesheet
+                .as_ref()
+                .and_then(|timesheet| timesheet.get(&year.to_string()))
+                .and_then(|months| months.get(&month.to_string()))
+                .filter(|days| days.len() == days_in_month);
+
+            if let Some(days) = days {
+                grid.namespaces.push(
+                    repository
+                        .namespace_alias
+                        .clone()
+                        .or_else(|| repository.namespace.clone())
+                        .unwrap_or_default(),
+                );
+                grid.cells.push(
+                    days.iter()
+                        .map(|day| ReviewCell {
+                            hours: day.get("hours").and_then(Value::as_f64).unwrap_or(0.0),
+                            user_edited: day.get("user_edited") == Some(&Value::Bool(true)),
+                        })
+                        .collect(),
+                );
+                grid.repository_indexes.push(index);
+            }
+        }
+
+        grid
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.changed.is_empty()
+    }
+
+    pub fn is_changed(&self, day: usize, column: usize) -> bool {
+        self.changed.contains(&(day, column))
+    }
+
+    pub fn day_total(&self, day: usize) -> f64 {
+        self.cells.iter().map(|column| column[day].hours).sum()
+    }
+
+    pub fn repository_total(&self, column: usize) -> f64 {
+        self.cells[column].iter().map(|cell| cell.hours).sum()
+    }
+
+    pub fn total(&self) -> f64 {
+        (0..self.cells.len())
+            .map(|column| self.repository_total(column))
+            .sum()
+    }
+
+    /// Move the cursor by a number of days and columns, staying inside the grid
+    pub fn move_cursor(&mut self, days: isize, columns: isize) {
+        let clamp = |value: usize, by: isize, len: usize| {
+            value.saturating_add_signed(by).min(len.saturating_sub(1))
+        };
+        self.cursor = (
+            clamp(self.cursor.0, days, self.weekends.len()),
+            clamp(self.cursor.1, columns, self.cells.len()),
+        );
+        self.input = None;
+    }
+
+    /// Typing a digit or point starts entering the hours for the cell under the cursor
+    pub fn type_char(&mut self, character: char) {
+        if character.is_ascii_digit() || character == '.' {
+            self.input.get_or_insert_with(String::new).push(character);
+            self.message = None;
+        }
+    }
+
+    pub fn delete_char(&mut self) {
+        if let Some(input) = self.input.as_mut() {
+            input.pop();
+        }
+    }
+
+    /// Set the cell under the cursor to the hours that have been typed
+    pub fn set_input(&mut self) {
+        let input = match self.input.take() {
+            Some(input) => input,
+            None => return,
+        };
+
+        match input.parse::<f64>() {
+            Ok(hours) if (0.0..=24.0).contains(&hours) => {
+                let (day, column) = self.cursor;
+                self.cells[column][day] = ReviewCell {
+                    hours,
+                    user_edited: true,
+                };
+                self.changed.insert((day, column));
+                self.message = None;
+            }
+            _ => self.message = Some(format!("'{}' isn't a number of hours from 0 to 24", input)),
+        }
+    }
+
+    /// Write the changed cells to the repositories' timesheets, marking them as edited by
+    /// the user so they're kept when the history is read again. Returns the days changed
+    pub fn apply_to(&self, repositories: &mut [Repository]) -> Result<ChangedDays, Box<dyn Error>> {
+        let year_string = self.year.to_string();
+        let mut changed_days = ChangedDays::new(timesheet_history::SOURCE_EDIT);
+
+        for &(day, column) in &self.changed {
+            let repository = &mut repositories[self.repository_indexes[column]];
+            repository.mutate_timesheet_entry(
+                &year_string,
+                &self.month,
+                day + 1,
+                create_single_day_object(self.weekends[day], self.cells[column][day].hours, true),
+            )?;
+
+            if let Some(repository_id) = &repository.id {
+                let date = NaiveDate::from_ymd_opt(self.year, self.month, day as u32 + 1)
+                    .ok_or("Invalid timesheet day")?;
+                changed_days.add(repository_id, date);
+            }
+        }
+
+        Ok(changed_days)
+    }
+}
+
+/// Open the grid full screen until it's closed. Returns true when the changes should be saved
+pub fn run(grid: &mut ReviewGrid) -> Result<bool, Box<dyn Error>> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run_terminal(&mut terminal, grid);
+    ratatui::restore();
+
+    result
+}
+
+fn run_terminal(
+    terminal: &mut DefaultTerminal,
+    grid: &mut ReviewGrid,
+) -> Result<bool, Box<dyn Error>> {
+    let mut table_state = TableState::default();
+
+    loop {
+        table_state.select(Some(grid.cursor.0));
+        terminal.draw(|frame| draw(frame, grid, &mut table_state))?;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => grid.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => grid.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => grid.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => grid.move_cursor(0, 1),
+            KeyCode::Enter => grid.set_input(),
+            KeyCode::Backspace => grid.delete_char(),
+            KeyCode::Esc if grid.input.is_some() => grid.input = None,
+            KeyCode::Char('s') => return Ok(grid.has_changes()),
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+            KeyCode::Char(character) => grid.type_char(character),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, grid: &ReviewGrid, table_state: &mut TableState) {
+    let [table_area, status_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+
+    let title = format!(
+        " {} {} ",
+        chrono::Month::try_from(grid.month as u8)
+            .map(|month| month.name())
+            .unwrap_or_default(),
+        grid.year
+    );
+
+    let mut header = vec![Cell::from("")];
+    header.extend(
+        grid.namespaces
+            .iter()
+            .map(|namespace| Cell::from(namespace.as_str())),
+    );
+    header.push(Cell::from("Total"));
+
+    let rows = grid.weekends.iter().enumerate().map(|(day, &weekend)| {
+        let date = NaiveDate::from_ymd_opt(grid.year, grid.month, day as u32 + 1);
+        let mut cells = vec![Cell::from(
+            date.map(|date| date.format("%a %e").to_string())
+                .unwrap_or_default(),
+        )];
+
+        for (column, days) in grid.cells.iter().enumerate() {
+            let cell = &days[day];
+            let is_cursor = grid.cursor == (day, column);
+            let text = match (is_cursor, grid.input.as_ref()) {
+                (true, Some(input)) => format!("{}_", input),
+                _ => cell.hours.to_string(),
+            };
+
+            // edited hours stand out from the ones worked out from git
+            let mut style = match (grid.is_changed(day, column), cell.user_edited) {
+                (true, _) => Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+                (false, true) => Style::new().fg(Color::Yellow),
+                (false, false) => Style::new(),
+            };
+            if is_cursor {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            cells.push(Cell::from(text).style(style));
+        }
+        cells.push(Cell::from(grid.day_total(day).to_string()));
+
+        let row = Row::new(cells);
+        match weekend {
+            true => row.style(Style::new().add_modifier(Modifier::DIM)),
+            false => row,
+        }
+    });
+
+    let mut footer = vec![Cell::from("Total")];
+    footer.extend(
+        (0..grid.cells.len()).map(|column| Cell::from(grid.repository_total(column).to_string())),
+    );
+    footer.push(Cell::from(grid.total().to_string()));
+
+    let mut widths = vec![Constraint::Length(7)];
+    widths.extend(
+        grid.namespaces
+            .iter()
+            .map(|namespace| Constraint::Length(namespace.len().clamp(6, 24) as u16)),
+    );
+    widths.push(Constraint::Length(6));
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::new().add_modifier(Modifier::BOLD)))
+        .footer(Row::new(footer).style(Style::new().add_modifier(Modifier::BOLD)))
+        .block(Block::bordered().title(title));
+    frame.render_stateful_widget(table, table_area, table_state);
+
+    let status = match grid.message.as_ref() {
+        Some(message) => Line::styled(message.as_str(), Style::new().fg(Color::Red)),
+        None => Line::styled(HELP_TEXT, Style::new().add_modifier(Modifier::DIM)),
+    };
+    frame.render_widget(Paragraph::new(status), status_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Map;
+    use std::collections::HashMap;
+
+    fn create_repository(namespace: &str, hours: f64) -> Repository {
+        // November 2021 starts on a Monday
+        let days = (1..=30)
+            .map(|day| {
+                let mut map = Map::new();
+                map.extend(create_single_day_object(
+                    day % 7 == 6 || day % 7 == 0,
+                    hours,
+                    day == 3,
+                ));
+                map
+            })
+            .collect();
+
+        Repository {
+            id: Some(format!("repo-{}", namespace)),
+            namespace: Some(namespace.to_string()),
+            timesheet: Some(HashMap::from([(
+                "2021".to_string(),
+                HashMap::from([("11".to_string(), days)]),
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_lays_out_the_repositories_with_the_month() {
+        let repositories = vec![
+            create_repository("autolog", 8.0),
+            Repository {
+                namespace: Some("design".to_string()),
+                ..Default::default()
+            },
+            create_repository("website", 2.0),
+        ];
+        let grid = ReviewGrid::from_repositories(&repositories, 2021, 11);
+
+        assert_eq!(grid.namespaces, vec!["autolog", "website"]);
+        assert_eq!(grid.weekends.len(), 30);
+        assert!(grid.weekends[5] && grid.weekends[6] && !grid.weekends[7]);
+        assert!(grid.cells[0][2].user_edited);
+        assert!(!grid.cells[0][3].user_edited);
+        assert_eq!(grid.day_total(0), 10.0);
+        assert_eq!(grid.repository_total(1), 60.0);
+        assert_eq!(grid.total(), 300.0);
+        assert!(ReviewGrid::from_repositories(&repositories, 2021, 12).is_empty());
+    }
+
+    #[test]
+    fn it_edits_and_applies_hours() {
+        let mut repositories = vec![create_repository("autolog", 8.0)];
+        repositories.push(create_repository("website", 2.0));
+        let mut grid = ReviewGrid::from_repositories(&repositories, 2021, 11);
+
+        grid.move_cursor(-1, 5);
+        assert_eq!(grid.cursor, (0, 1));
+        grid.move_cursor(1, 0);
+        "45".chars().for_each(|character| grid.type_char(character));
+        grid.set_input();
+        assert!(grid.message.is_some());
+        assert!(!grid.has_changes());
+
+        "6.x5"
+            .chars()
+            .for_each(|character| grid.type_char(character));
+        grid.set_input();
+        assert_eq!(grid.cells[1][1].hours, 6.5);
+        assert_eq!(grid.day_total(1), 14.5);
+        assert!(grid.is_changed(1, 1));
+
+        let changed_days = grid.apply_to(&mut repositories).unwrap();
+        let date = NaiveDate::from_ymd_opt(2021, 11, 2).unwrap();
+        assert_eq!(
+            changed_days.get_source("repo-website", &date),
+            timesheet_history::SOURCE_EDIT
+        );
+        assert_eq!(
+            changed_days.get_source("repo-autolog", &date),
+            timesheet_history::SOURCE_GIT
+        );
+        let get_entry = |repository: &Repository, entry: &str| {
+            repository
+                .get_timesheet_entry(&"2021".to_string(), &11, 2, entry.to_string())
+                .unwrap()
+                .cloned()
+        };
+        assert_eq!(
+            get_entry(&repositories[1], "hours"),
+            Some(serde_json::json!(6.5))
+        );
+        assert_eq!(
+            get_entry(&repositories[1], "user_edited"),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(
+            get_entry(&repositories[0], "user_edited"),
+            Some(Value::Bool(false))
+        );
+    }
+}
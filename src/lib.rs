@@ -0,0 +1,6 @@
+pub mod config;
+pub mod data;
+pub mod helpers;
+pub mod interface;
+pub mod server;
+pub mod utils;
@@ -1,9 +1,5 @@
+use autolog::interface;
 use std::process;
-mod config;
-mod data;
-mod helpers;
-mod interface;
-mod utils;
 
 fn main() {
     let cli = interface::cli::Cli::new();
@@ -0,0 +1,3 @@
+pub mod page_builder;
+pub mod router;
+pub mod timesheet_store;
@@ -0,0 +1,192 @@
+use crate::utils::link::link_builder::{TimesheetDocument, get_day_description};
+use chrono::{Datelike, NaiveDate};
+
+const STYLES: &str = "body{font-family:-apple-system,Helvetica,Arial,sans-serif;color:#222;\
+max-width:860px;margin:40px auto;padding:0 20px}table{border-collapse:collapse;width:100%;\
+margin-bottom:24px}th,td{text-align:left;padding:6px 8px;border-bottom:1px solid #ddd}\
+td.hours,th.hours{text-align:right}.details p{margin:2px 0}.approved{color:#1a7f37}\
+button{font-size:16px;padding:8px 16px;cursor:pointer}";
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// The month_year string is in the form "October, 2021"
+fn parse_month_year(month_year: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("1 {}", month_year), "%d %B, %Y").ok()
+}
+
+fn render_details(document: &TimesheetDocument) -> String {
+    let mut details = String::new();
+
+    if let Some(user) = document.user.as_ref() {
+        details.push_str(&format!(
+            "<p><strong>{}</strong> &lt;{}&gt;</p>",
+            escape_html(&user.name),
+            escape_html(&user.email)
+        ));
+    }
+
+    if let Some(client) = document.client.as_ref() {
+        details.push_str(&format!(
+            "<p>Client: {}</p>",
+            escape_html(&client.client_name)
+        ));
+        if !client.client_contact_person.is_empty() {
+            details.push_str(&format!(
+                "<p>Contact: {}</p>",
+                escape_html(&client.client_contact_person)
+            ));
+        }
+        if !client.client_address.is_empty() {
+            details.push_str(&format!(
+                "<p>{}</p>",
+                escape_html(&client.client_address).replace('\n', "<br>")
+            ));
+        }
+    }
+
+    details
+}
+
+fn render_timesheets(document: &TimesheetDocument) -> String {
+    let first_of_month = parse_month_year(&document.month_year);
+    let mut tables = String::new();
+
+    for timesheet in document.timesheets.iter() {
+        tables.push_str(&format!("<h2>{}</h2>", escape_html(&timesheet.namespace)));
+        if let Some(project_number) = timesheet.project_number.as_ref() {
+            tables.push_str(&format!(
+                "<p>Project number: {}</p>",
+                escape_html(project_number)
+            ));
+        }
+
+        tables.push_str(
+            "<table><tr><th>Date</th><th class=\"hours\">Hours</th><th>Description</th></tr>",
+        );
+        for (index, day) in timesheet.timesheet.iter().enumerate() {
+            let hours = day.get("hours").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            if hours == 0.0 {
+                continue;
+            }
+
+            let date = first_of_month
+                .and_then(|date| {
+                    NaiveDate::from_ymd_opt(date.year(), date.month(), index as u32 + 1)
+                })
+                .map(|date| date.format("%a %d %b").to_string())
+                .unwrap_or_else(|| (index + 1).to_string());
+
+            tables.push_str(&format!(
+                "<tr><td>{}</td><td class=\"hours\">{}</td><td>{}</td></tr>",
+                date,
+                hours,
+                escape_html(get_day_description(day).unwrap_or(""))
+            ));
+        }
+        tables.push_str(&format!(
+            "<tr><th>Total</th><th class=\"hours\">{}</th><th></th></tr></table>",
+            timesheet.total_hours
+        ));
+    }
+
+    tables
+}
+
+fn render_approval(document: &TimesheetDocument) -> String {
+    if !document.requires_approval.unwrap_or(false) {
+        return String::new();
+    }
+
+    if document.approved {
+        return "<p class=\"approved\"><strong>This timesheet has been approved.</strong></p>"
+            .to_string();
+    }
+
+    let approvers_name = document
+        .approver
+        .as_ref()
+        .and_then(|approver| approver.approvers_name.as_deref())
+        .unwrap_or("the approver");
+
+    format!(
+        "<form method=\"post\" action=\"/{}/approve\">\
+        <p>Awaiting approval from {}.</p>\
+        <button type=\"submit\">Approve timesheet</button></form>",
+        escape_html(&document.random_path),
+        escape_html(approvers_name)
+    )
+}
+
+pub fn render_timesheet_page(document: &TimesheetDocument) -> String {
+    let total_hours: f64 = document
+        .timesheets
+        .iter()
+        .map(|timesheet| timesheet.total_hours)
+        .sum();
+
+    format!(
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+        <title>Timesheet - {month_year}</title><style>{styles}</style></head><body>\
+        <h1>Timesheet: {month_year}</h1><div class=\"details\">{details}</div>\
+        {timesheets}<p><strong>Total hours: {total_hours}</strong></p>{approval}\
+        </body></html>",
+        month_year = escape_html(&document.month_year),
+        styles = STYLES,
+        details = render_details(document),
+        timesheets = render_timesheets(document),
+        total_hours = total_hours,
+        approval = render_approval(document),
+    )
+}
+
+pub fn render_not_found_page() -> String {
+    format!(
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+        <title>Timesheet not found</title><style>{}</style></head><body>\
+        <h1>Timesheet not found</h1>\
+        <p>This timesheet doesn't exist or the link has expired.</p></body></html>",
+        STYLES
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+
+    #[test]
+    fn it_escapes_html() {
+        assert_eq!(
+            escape_html("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn it_renders_a_timesheet_awaiting_approval() {
+        let page = render_timesheet_page(&mocks::create_mock_timesheet_document("abc123"));
+
+        assert!(page.contains("<h1>Timesheet: October, 2021</h1>"));
+        assert!(page.contains("<td>Fri 01 Oct</td><td class=\"hours\">8</td>"));
+        assert!(page.contains("Total hours: 24"));
+        assert!(page.contains("action=\"/abc123/approve\""));
+    }
+
+    #[test]
+    fn it_renders_an_approved_timesheet() {
+        let mut document = mocks::create_mock_timesheet_document("abc123");
+        document.approved = true;
+        let page = render_timesheet_page(&document);
+
+        assert!(page.contains("This timesheet has been approved."));
+        assert!(!page.contains("/abc123/approve"));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::server::page_builder::{render_not_found_page, render_timesheet_page};
+use crate::server::timesheet_store::TimesheetStore;
+use crate::utils::link::link_builder::TimesheetDocument;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+// Routes requests for the self-hosted share server. The api route accepts the same
+// TimesheetDocument json that `autolog make` sends, and every other path is treated as
+// the random path of a shared timesheet.
+
+pub struct ServerConfig {
+    pub api_endpoint: String,
+    pub api_key: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ServerResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+    pub location: Option<String>,
+}
+
+impl ServerResponse {
+    fn html(status: u16, body: String) -> Self {
+        ServerResponse {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body,
+            location: None,
+        }
+    }
+
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        ServerResponse {
+            status,
+            content_type: "application/json",
+            body: body.to_string(),
+            location: None,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, json!({ "error": message }))
+    }
+
+    fn redirect(location: String) -> Self {
+        ServerResponse {
+            status: 303,
+            content_type: "text/plain; charset=utf-8",
+            body: String::new(),
+            location: Some(location),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::html(404, render_not_found_page())
+    }
+}
+
+fn is_valid_random_path(random_path: &str) -> bool {
+    !random_path.is_empty() && random_path.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn store_document(
+    store: &TimesheetStore,
+    config: &ServerConfig,
+    authorization: Option<&str>,
+    body: &str,
+    now: DateTime<Utc>,
+) -> ServerResponse {
+    if authorization != Some(format!("Bearer {}", config.api_key).as_str()) {
+        return ServerResponse::error(401, "Unauthorized");
+    }
+
+    let document: TimesheetDocument = match serde_json::from_str(body) {
+        Ok(document) => document,
+        Err(err) => return ServerResponse::error(400, &format!("Invalid timesheet: {}", err)),
+    };
+
+    if !is_valid_random_path(&document.random_path) {
+        return ServerResponse::error(400, "Invalid timesheet: random_path must be alphanumeric");
+    }
+
+    match store.insert(&document, now) {
+        Ok(true) => ServerResponse::json(201, json!({ "random_path": document.random_path })),
+        Ok(false) => ServerResponse::error(409, "A timesheet already exists at this path"),
+        Err(err) => ServerResponse::error(500, &err.to_string()),
+    }
+}
+
+fn show_timesheet(store: &TimesheetStore, random_path: &str, now: DateTime<Utc>) -> ServerResponse {
+    match store.find(random_path, now) {
+        Ok(Some(document)) => ServerResponse::html(200, render_timesheet_page(&document)),
+        Ok(None) => ServerResponse::not_found(),
+        Err(err) => ServerResponse::error(500, &err.to_string()),
+    }
+}
+
+fn approve_timesheet(
+    store: &TimesheetStore,
+    random_path: &str,
+    now: DateTime<Utc>,
+) -> ServerResponse {
+    match store.find(random_path, now) {
+        Ok(Some(document)) if !document.requires_approval.unwrap_or(false) => {
+            return ServerResponse::error(400, "This timesheet doesn't require approval");
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => return ServerResponse::not_found(),
+        Err(err) => return ServerResponse::error(500, &err.to_string()),
+    }
+
+    match store.approve(random_path, now) {
+        Ok(Some(_)) => ServerResponse::redirect(format!("/{}", random_path)),
+        Ok(None) => ServerResponse::not_found(),
+        Err(err) => ServerResponse::error(500, &err.to_string()),
+    }
+}
+
+pub fn handle_request(
+    store: &TimesheetStore,
+    config: &ServerConfig,
+    method: &str,
+    url: &str,
+    authorization: Option<&str>,
+    body: &str,
+    now: DateTime<Utc>,
+) -> ServerResponse {
+    let path = url.split('?').next().unwrap_or("").trim_matches('/');
+    let api_endpoint = config.api_endpoint.trim_matches('/');
+
+    // The cli sends the document as the body of a GET request, so accept either method
+    if path == api_endpoint {
+        return match method {
+            "GET" | "POST" => store_document(store, config, authorization, body, now),
+            _ => ServerResponse::error(405, "Method not allowed"),
+        };
+    }
+
+    if path == "health" {
+        return ServerResponse::json(200, json!({ "status": "ok" }));
+    }
+
+    match (method, path.split_once('/')) {
+        ("POST", Some((random_path, "approve"))) if is_valid_random_path(random_path) => {
+            approve_timesheet(store, random_path, now)
+        }
+        ("GET", None) if is_valid_random_path(path) => show_timesheet(store, path, now),
+        _ => ServerResponse::not_found(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+    use chrono::Duration;
+
+    fn get_config() -> ServerConfig {
+        ServerConfig {
+            api_endpoint: "api/timesheet".to_string(),
+            api_key: "secret".to_string(),
+        }
+    }
+
+    fn post_document(store: &TimesheetStore, random_path: &str) -> ServerResponse {
+        let document = mocks::create_mock_timesheet_document(random_path);
+        handle_request(
+            store,
+            &get_config(),
+            "GET",
+            "/api/timesheet",
+            Some("Bearer secret"),
+            &serde_json::to_string(&document).unwrap(),
+            document.creation_date,
+        )
+    }
+
+    fn now() -> DateTime<Utc> {
+        mocks::create_mock_timesheet_document("").creation_date
+    }
+
+    #[test]
+    fn it_stores_a_timesheet_document() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let response = post_document(&store, "abc123");
+
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, "{\"random_path\":\"abc123\"}");
+        assert_eq!(post_document(&store, "abc123").status, 409);
+    }
+
+    #[test]
+    fn it_rejects_a_document_without_the_bearer_key() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let response = handle_request(
+            &store,
+            &get_config(),
+            "POST",
+            "/api/timesheet",
+            Some("Bearer wrong"),
+            "{}",
+            now(),
+        );
+
+        assert_eq!(response.status, 401);
+    }
+
+    #[test]
+    fn it_rejects_a_document_that_cant_be_parsed() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let response = handle_request(
+            &store,
+            &get_config(),
+            "POST",
+            "/api/timesheet",
+            Some("Bearer secret"),
+            "{\"random_path\":\"abc123\"}",
+            now(),
+        );
+
+        assert_eq!(response.status, 400);
+    }
+
+    #[test]
+    fn it_serves_the_timesheet_page_until_it_expires() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        post_document(&store, "abc123");
+
+        let response = handle_request(&store, &get_config(), "GET", "/abc123", None, "", now());
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("Timesheet: October, 2021"));
+
+        let expired = now() + Duration::seconds(60);
+        let response = handle_request(&store, &get_config(), "GET", "/abc123", None, "", expired);
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn it_approves_a_timesheet() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        post_document(&store, "abc123");
+
+        let response = handle_request(
+            &store,
+            &get_config(),
+            "POST",
+            "/abc123/approve",
+            None,
+            "",
+            now(),
+        );
+        assert_eq!(response.status, 303);
+        assert_eq!(response.location, Some("/abc123".to_string()));
+        assert!(store.find("abc123", now()).unwrap().unwrap().approved);
+    }
+
+    #[test]
+    fn it_returns_not_found_for_unknown_paths() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let config = get_config();
+
+        assert_eq!(
+            handle_request(&store, &config, "GET", "/missing", None, "", now()).status,
+            404
+        );
+        assert_eq!(
+            handle_request(&store, &config, "GET", "/../etc", None, "", now()).status,
+            404
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::utils::link::link_builder::TimesheetDocument;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+
+/// Stores shared timesheets for the self-hosted server. Documents are kept as the
+/// json they were sent as, and expire a fixed number of seconds after they're stored,
+/// in the same way the hosted service expires them. The creation date is sent by the
+/// client, so it isn't trusted for the expiry.
+pub struct TimesheetStore {
+    conn: Connection,
+    expire_time_seconds: i64,
+}
+
+impl TimesheetStore {
+    pub fn open(path: &Path, expire_time_seconds: i64) -> Result<Self> {
+        let conn =
+            Connection::open(path).context(format!("Failed to open database at {:?}", path))?;
+        Self::from_connection(conn, expire_time_seconds)
+    }
+
+    pub fn open_in_memory(expire_time_seconds: i64) -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, expire_time_seconds)
+    }
+
+    fn from_connection(conn: Connection, expire_time_seconds: i64) -> Result<Self> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS shared_timesheets (
+                random_path TEXT PRIMARY KEY,
+                document TEXT NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(TimesheetStore {
+            conn,
+            expire_time_seconds,
+        })
+    }
+
+    /// Remove every document that has outlived the expiry time
+    pub fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        Ok(self.conn.execute(
+            "DELETE FROM shared_timesheets WHERE expires_at <= ?",
+            params![now.timestamp()],
+        )?)
+    }
+
+    /// Store a document, returning false if its path is already taken
+    pub fn insert(&self, document: &TimesheetDocument, now: DateTime<Utc>) -> Result<bool> {
+        self.purge_expired(now)?;
+
+        let expires_at = now.timestamp() + self.expire_time_seconds;
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO shared_timesheets (random_path, document, expires_at)
+             VALUES (?, ?, ?)",
+            params![
+                document.random_path,
+                serde_json::to_string(document)?,
+                expires_at
+            ],
+        )?;
+
+        Ok(inserted == 1)
+    }
+
+    pub fn find(&self, random_path: &str, now: DateTime<Utc>) -> Result<Option<TimesheetDocument>> {
+        let document: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT document FROM shared_timesheets WHERE random_path = ? AND expires_at > ?",
+                params![random_path, now.timestamp()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match document {
+            Some(document) => Ok(Some(serde_json::from_str(&document)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Mark a document as approved, returning the updated document if it exists
+    pub fn approve(
+        &self,
+        random_path: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<TimesheetDocument>> {
+        let mut document = match self.find(random_path, now)? {
+            Some(document) => document,
+            None => return Ok(None),
+        };
+
+        document.approved = true;
+        self.conn.execute(
+            "UPDATE shared_timesheets SET document = ? WHERE random_path = ?",
+            params![serde_json::to_string(&document)?, random_path],
+        )?;
+
+        Ok(Some(document))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+    use chrono::Duration;
+
+    #[test]
+    fn it_finds_a_stored_document_until_it_expires() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let document = mocks::create_mock_timesheet_document("abc123");
+        let now = document.creation_date;
+
+        assert!(store.insert(&document, now).unwrap());
+        assert!(store.find("abc123", now).unwrap().is_some());
+        assert!(
+            store
+                .find("abc123", now + Duration::seconds(60))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn it_expires_a_document_from_when_it_was_stored() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let mut document = mocks::create_mock_timesheet_document("abc123");
+        let now = document.creation_date;
+        document.creation_date = now + Duration::days(365 * 100);
+
+        assert!(store.insert(&document, now).unwrap());
+        assert!(
+            store
+                .find("abc123", now + Duration::seconds(60))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn it_refuses_to_overwrite_a_document() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let document = mocks::create_mock_timesheet_document("abc123");
+        let now = document.creation_date;
+
+        assert!(store.insert(&document, now).unwrap());
+        assert!(!store.insert(&document, now).unwrap());
+    }
+
+    #[test]
+    fn it_approves_a_document() {
+        let store = TimesheetStore::open_in_memory(60).unwrap();
+        let document = mocks::create_mock_timesheet_document("abc123");
+        let now = document.creation_date;
+
+        store.insert(&document, now).unwrap();
+        assert!(store.approve("abc123", now).unwrap().unwrap().approved);
+        assert!(store.find("abc123", now).unwrap().unwrap().approved);
+        assert!(store.approve("missing", now).unwrap().is_none());
+    }
+}
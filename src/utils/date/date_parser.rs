@@ -1,5 +1,8 @@
-use crate::data::repository::{GitLogDates, Repository};
-use chrono::{NaiveDate, TimeZone, Utc};
+use crate::data::repository::{
+    GitLogDates, HOURS_SPLIT_COMMITS, HOURS_SPLIT_LINES, HOURS_SPLIT_SESSIONS, Repository,
+};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Weekday};
+use chrono_tz::Tz;
 use regex::Regex;
 use serde_json::{Map, Number, Value};
 use std::collections::{HashMap, HashSet};
@@ -8,12 +11,82 @@ use std::error::Error;
 use std::io::ErrorKind;
 use std::{io, process};
 
+/// The rounding policies that can be set for a client
+pub const HOURS_ROUNDING_OPTIONS: [&str; 7] = [
+    "none",
+    "nearest_0.25",
+    "nearest_0.5",
+    "nearest_1",
+    "up_0.25",
+    "up_0.5",
+    "up_1",
+];
+
+/// How worked hours are rounded. Hours are always kept to a precision of a hundredth
+/// of an hour, and clients without a policy are rounded to the nearest whole hour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HoursRounding {
+    None,
+    Nearest(f64),
+    Up(f64),
+}
+
+impl Default for HoursRounding {
+    fn default() -> Self {
+        HoursRounding::Nearest(1.0)
+    }
+}
+
+impl HoursRounding {
+    pub fn from_setting(setting: Option<&str>) -> Self {
+        let setting = match setting {
+            Some("none") => return HoursRounding::None,
+            Some(setting) => setting,
+            None => return HoursRounding::default(),
+        };
+
+        let increment = |value: &str| value.parse::<f64>().ok().filter(|x| *x >= 0.01);
+        match setting.split_once('_') {
+            Some(("nearest", value)) => increment(value).map(HoursRounding::Nearest),
+            Some(("up", value)) => increment(value).map(HoursRounding::Up),
+            _ => None,
+        }
+        .unwrap_or_default()
+    }
+
+    pub fn increment(&self) -> f64 {
+        match self {
+            HoursRounding::None => 0.01,
+            HoursRounding::Nearest(increment) | HoursRounding::Up(increment) => *increment,
+        }
+    }
+
+    pub fn round(&self, hours: f64) -> f64 {
+        let increments = hours / self.increment();
+        let increments = match self {
+            // allow for floating point error so whole increments aren't pushed up
+            HoursRounding::Up(_) => (increments - 1e-9).ceil(),
+            _ => increments.round(),
+        };
+        round_to_hundredths(increments * self.increment())
+    }
+}
+
+fn round_to_hundredths(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
+
+fn to_hundredths(hours: f64) -> i64 {
+    (hours * 100.0).round() as i64
+}
+
 fn return_worked_hours_from_worked_days(
     worked_days: &[u32],
     day: &u32,
     adjacent_days_in_month: &[HashSet<u32>],
     default_hours: Option<f64>,
     repo_index: usize,
+    increment: f64,
 ) -> f64 {
     if !worked_days.contains(day) {
         return 0.0;
@@ -31,24 +104,194 @@ fn return_worked_hours_from_worked_days(
         return total_hours;
     }
 
-    let base_hours = (total_hours / total_occurrences as f64).floor();
-    let remaining_hours = total_hours - (base_hours * total_occurrences as f64);
+    // Split the hours in whole increments, working in hundredths of an hour. What's left
+    // over is handed out an increment at a time from the first repository worked on that
+    // day, so the split always adds up to the day's hours.
+    let total = to_hundredths(total_hours);
+    let increment = to_hundredths(increment).max(1);
+    let occurrences = total_occurrences as i64;
+    let base = total / occurrences / increment * increment;
+    let remaining = total - base * occurrences;
+    let rank = adjacent_days_in_month
+        .iter()
+        .take(repo_index)
+        .filter(|month| month.contains(day))
+        .count() as i64;
+    let extra = (remaining - rank * increment).clamp(0, increment);
+
+    (base + extra) as f64 / 100.0
+}
+
+/// Each repository's share of the work done on a date, in the same order as the repositories
+pub type DayWeights = HashMap<NaiveDate, Vec<f64>>;
+
+/// Weigh the work done in each repository on each day using the split set for the client.
+/// No weights are returned for an even split.
+pub fn get_day_weights(repositories: &[Repository]) -> DayWeights {
+    let mut day_weights: DayWeights = HashMap::new();
+    let hours_split = repositories
+        .iter()
+        .find_map(|repository| repository.hours_split.as_deref());
 
-    if repo_index < remaining_hours as usize {
-        base_hours + 1.0
+    for (index, repository) in repositories.iter().enumerate() {
+        let commit_times = match repository.git_log_times.as_ref() {
+            Some(commit_times) => commit_times,
+            None => continue,
+        };
+
+        for (date, times) in commit_times {
+            let weight = match hours_split {
+                Some(HOURS_SPLIT_COMMITS) => times.len() as f64,
+                Some(HOURS_SPLIT_LINES) => repository
+                    .git_log_lines_changed
+                    .as_ref()
+                    .and_then(|lines_changed| lines_changed.get(date))
+                    .map_or(0.0, |lines| *lines as f64),
+                Some(HOURS_SPLIT_SESSIONS) => estimate_hours_from_commit_times(
+                    times,
+                    repository
+                        .commit_lead_in_minutes
+                        .unwrap_or(DEFAULT_COMMIT_LEAD_IN_MINUTES),
+                    repository
+                        .commit_session_gap_minutes
+                        .unwrap_or(DEFAULT_COMMIT_SESSION_GAP_MINUTES),
+                ),
+                _ => return HashMap::new(),
+            };
+
+            day_weights
+                .entry(*date)
+                .or_insert_with(|| vec![0.0; repositories.len()])[index] = weight;
+        }
+    }
+
+    day_weights
+}
+
+/// Split the hours between repositories in proportion to their weights. The split is made
+/// in whole increments, with the increments left over after rounding down going to the
+/// largest remainders. Any part of an increment left over goes to the next repository in
+/// line, so the shares always add up to the total.
+fn split_hours_by_weight(
+    total_hours: f64,
+    weights: &[f64],
+    repo_index: usize,
+    increment: f64,
+) -> Option<f64> {
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight <= 0.0 || repo_index >= weights.len() {
+        return None;
+    }
+
+    let total = to_hundredths(total_hours);
+    let increment = to_hundredths(increment).max(1);
+    let total_units = total / increment;
+    let part_unit = total % increment;
+
+    let shares: Vec<f64> = weights
+        .iter()
+        .map(|weight| total_units as f64 * weight / total_weight)
+        .collect();
+    let mut units: Vec<i64> = shares.iter().map(|share| share.floor() as i64).collect();
+
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by(|a, b| {
+        let remainder_a = shares[*a] - units[*a] as f64;
+        let remainder_b = shares[*b] - units[*b] as f64;
+        remainder_b.total_cmp(&remainder_a).then(a.cmp(b))
+    });
+
+    let left_over = (total_units - units.iter().sum::<i64>()) as usize;
+    for index in by_remainder.iter().take(left_over) {
+        units[*index] += 1;
+    }
+
+    // the part of an increment goes to the next repository in line for an increment
+    let next_in_line = by_remainder[left_over % by_remainder.len()];
+    let part = if repo_index == next_in_line {
+        part_unit
     } else {
-        base_hours
+        0
+    };
+
+    Some((units[repo_index] * increment + part) as f64 / 100.0)
+}
+
+pub const DEFAULT_COMMIT_LEAD_IN_MINUTES: i64 = 30;
+pub const DEFAULT_COMMIT_SESSION_GAP_MINUTES: i64 = 120;
+
+/// Estimate the hours worked on a day from its commit times. Commits are grouped into
+/// sessions, starting a new session whenever the gap between two commits is longer than
+/// the session gap. Each session runs from its first to its last commit, plus the lead-in
+/// to account for the work done before the first commit was made.
+pub fn estimate_hours_from_commit_times(
+    commit_times: &[DateTime<FixedOffset>],
+    lead_in_minutes: i64,
+    session_gap_minutes: i64,
+) -> f64 {
+    let mut commit_times = commit_times.to_vec();
+    commit_times.sort();
+
+    let mut commit_times = commit_times.iter();
+    let mut session_start = match commit_times.next() {
+        Some(time) => *time,
+        None => return 0.0,
+    };
+    let mut session_end = session_start;
+    let mut worked_minutes = 0;
+
+    for time in commit_times {
+        if (*time - session_end).num_minutes() > session_gap_minutes {
+            worked_minutes += (session_end - session_start).num_minutes() + lead_in_minutes;
+            session_start = *time;
+        }
+        session_end = *time;
+    }
+    worked_minutes += (session_end - session_start).num_minutes() + lead_in_minutes;
+
+    let hours = (worked_minutes as f64 / 60.0).min(24.0);
+    (hours * 100.0).round() / 100.0
+}
+
+/// Works out which day a commit counts towards. Commit times are moved into the client's
+/// timezone when one is set, otherwise they keep the offset they were committed with.
+/// Commits made before the day start hour count towards the previous day, so a late night
+/// finishing at 2am is credited to the day it started.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommitDay {
+    timezone: Option<Tz>,
+    day_start_hour: u32,
+}
+
+impl CommitDay {
+    pub fn new(timezone: Option<&str>, day_start_hour: Option<u32>) -> Self {
+        CommitDay {
+            timezone: timezone.and_then(|timezone| timezone.parse::<Tz>().ok()),
+            day_start_hour: day_start_hour.unwrap_or(0).min(23),
+        }
+    }
+
+    pub fn local_time(&self, date_time: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self.timezone {
+            Some(timezone) => date_time.with_timezone(&timezone).fixed_offset(),
+            None => date_time,
+        }
+    }
+
+    pub fn date(&self, date_time: DateTime<FixedOffset>) -> NaiveDate {
+        (self.local_time(date_time).naive_local() - Duration::hours(self.day_start_hour as i64))
+            .date()
     }
 }
 
-pub fn is_weekend(date_tuple: &(i32, u32, u32), day: u32) -> bool {
-    let day_of_week_index = Utc
-        .with_ymd_and_hms(date_tuple.0, date_tuple.1, day, 0, 0, 0)
-        .unwrap()
-        .format("%u")
-        .to_string();
+pub fn is_valid_timezone(timezone: &str) -> bool {
+    timezone.parse::<Tz>().is_ok()
+}
 
-    matches!(day_of_week_index.parse().unwrap(), 6 | 7)
+pub fn is_weekend(date_tuple: &(i32, u32, u32), day: u32) -> bool {
+    // the weekday belongs to the calendar day itself, so no timezone is involved
+    let date = NaiveDate::from_ymd_opt(date_tuple.0, date_tuple.1, day).unwrap();
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
 }
 
 pub type DayMap = [(String, Value); 3];
@@ -68,28 +311,122 @@ fn set_day_map(weekend: bool, hours: f64, edited: bool, day_map: &mut Map<String
     day_map.extend(create_single_day_object(weekend, hours, edited));
 }
 
+/// Subjects git writes for merges, which don't say anything about the work that was done
+const MERGE_SUBJECT_PREFIXES: [&str; 4] = [
+    "Merge branch ",
+    "Merge pull request ",
+    "Merge remote-tracking branch ",
+    "Merge tag ",
+];
+
+/// Describe a day's work from the subjects of the commits made on it
+pub fn describe_commit_subjects(subjects: &[String]) -> Option<String> {
+    let subjects: Vec<&str> = subjects
+        .iter()
+        .map(|subject| subject.trim())
+        .filter(|subject| {
+            !subject.is_empty()
+                && !MERGE_SUBJECT_PREFIXES
+                    .iter()
+                    .any(|prefix| subject.starts_with(prefix))
+        })
+        .collect();
+
+    match subjects.is_empty() {
+        true => None,
+        false => Some(subjects.join("; ")),
+    }
+}
+
+/// A description the user has written is kept. Otherwise it's written from the day's commit
+/// subjects, or kept as it was when the git history hasn't been read
+fn set_day_description(
+    repository: &Repository,
+    date_tuple: (i32, u32, u32),
+    day_map: &mut Map<String, Value>,
+) {
+    let get_entry = |entry: &str| {
+        repository
+            .get_timesheet_entry(
+                &date_tuple.0.to_string(),
+                &date_tuple.1,
+                date_tuple.2 as usize,
+                entry.to_string(),
+            )
+            .ok()
+            .flatten()
+            .cloned()
+    };
+
+    let is_description_edited = get_entry("description_edited") == Some(Value::Bool(true));
+    let description = if is_description_edited || repository.git_log_subjects.is_none() {
+        get_entry("description").and_then(|value| value.as_str().map(String::from))
+    } else {
+        repository
+            .get_commit_subjects(date_tuple.0, date_tuple.1, date_tuple.2)
+            .and_then(|subjects| describe_commit_subjects(subjects))
+    };
+
+    if let Some(description) = description {
+        day_map.insert("description".to_string(), Value::String(description));
+    }
+    if is_description_edited {
+        day_map.insert("description_edited".to_string(), Value::Bool(true));
+    }
+}
+
 fn parse_hours_from_date(
     date_tuple: (i32, u32, u32),
     worked_days: Vec<u32>,
     repository: &mut Repository,
     adjacent_days_in_month: Vec<HashSet<u32>>,
+    day_weights: &DayWeights,
     repo_index: usize,
 ) -> Vec<Map<String, Value>> {
     // iterate through the number of days in the month
     // for each day return the calendar day
     // if its a Weekend or day that isn't worked, set to zero, otherwise 8
     let mut vector = vec![];
+    let rounding = HoursRounding::from_setting(repository.hours_rounding.as_deref());
 
     for day in 1..date_tuple.2 + 1 {
         let is_weekend: bool = is_weekend(&date_tuple, day);
         let mut day_map = Map::new();
-        let hours_worked = return_worked_hours_from_worked_days(
-            &worked_days,
-            &day,
-            &adjacent_days_in_month,
-            repository.default_hours,
-            repo_index,
-        );
+        let commit_times = repository
+            .get_commit_times(date_tuple.0, date_tuple.1, day)
+            .filter(|_| repository.estimates_hours_from_commits());
+        let hours_worked = match commit_times {
+            Some(commit_times) => rounding.round(estimate_hours_from_commit_times(
+                commit_times,
+                repository
+                    .commit_lead_in_minutes
+                    .unwrap_or(DEFAULT_COMMIT_LEAD_IN_MINUTES),
+                repository
+                    .commit_session_gap_minutes
+                    .unwrap_or(DEFAULT_COMMIT_SESSION_GAP_MINUTES),
+            )),
+            None => NaiveDate::from_ymd_opt(date_tuple.0, date_tuple.1, day)
+                .and_then(|date| day_weights.get(&date))
+                .filter(|_| worked_days.contains(&day))
+                .and_then(|weights| {
+                    split_hours_by_weight(
+                        repository.default_hours.unwrap_or(8.0),
+                        weights,
+                        repo_index,
+                        rounding.increment(),
+                    )
+                })
+                .unwrap_or_else(|| {
+                    return_worked_hours_from_worked_days(
+                        &worked_days,
+                        &day,
+                        &adjacent_days_in_month,
+                        repository.default_hours,
+                        repo_index,
+                        rounding.increment(),
+                    )
+                }),
+        };
 
         // Each day denotes whether it is a Weekend, what the hours worked are
         // and whether it has been manually edited by the user to prevent these
@@ -144,6 +481,8 @@ fn parse_hours_from_date(
             }
         }
 
+        set_day_description(repository, (date_tuple.0, date_tuple.1, day), &mut day_map);
+
         vector.push(day_map);
     }
 
@@ -153,27 +492,30 @@ fn parse_hours_from_date(
 pub type TimesheetMonths = HashMap<String, Vec<Map<String, Value>>>;
 pub type TimesheetYears = HashMap<String, HashMap<String, Vec<Map<String, Value>>>>;
 
+/// The days worked in the month in each of the other repositories, in the same order as the
+/// repositories so the ones before the current repository can be found
 fn get_adjacent_git_log_days_for_month<'a>(
     adjacent_git_log_days: Vec<GitLogDates>,
     year: &'a i32,
     month: &'a u32,
 ) -> Vec<HashSet<u32>> {
-    let mut repo_days = vec![];
-    for log_day in adjacent_git_log_days.iter() {
-        if let Some(year) = log_day.get(year) {
-            if let Some(month) = year.get(month) {
-                repo_days.push(month.clone());
-            }
-        }
-    }
-
-    repo_days
+    adjacent_git_log_days
+        .iter()
+        .map(|log_day| {
+            log_day
+                .get(year)
+                .and_then(|year| year.get(month))
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect()
 }
 
 pub fn get_timesheet_map_from_date_hashmap(
     git_log_dates: GitLogDates,
     repository: &mut Repository,
     adjacent_git_log_dates: Vec<GitLogDates>,
+    day_weights: &DayWeights,
     repo_index: usize,
 ) -> TimesheetYears {
     let timesheet_years: TimesheetYears = git_log_dates
@@ -197,6 +539,7 @@ pub fn get_timesheet_map_from_date_hashmap(
                         worked_days,
                         repository,
                         adjacent_days_in_month,
+                        day_weights,
                         repo_index,
                     );
                     (month.to_string(), worked_hours_for_month)
@@ -280,11 +623,25 @@ pub fn check_for_valid_year(year: &Option<String>) -> Result<&String, Box<dyn Er
     Ok(year_string)
 }
 
+/// Read a comma separated list of weekdays, such as `mon,tue` or `monday,friday`
+pub fn parse_weekdays(weekdays: &str) -> Result<Vec<Weekday>, Box<dyn Error>> {
+    weekdays
+        .split(',')
+        .map(|weekday| {
+            weekday
+                .trim()
+                .parse::<Weekday>()
+                .map_err(|_| format!("'{}' isn't a day of the week", weekday.trim()).into())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::repository::GitLogDates;
-    use serde_json::{Map, Number, Value};
+    use crate::data::repository::{GitLogDates, HOURS_ESTIMATION_COMMITS, HOURS_SPLIT_COMMITS};
+    use crate::helpers::mocks;
+    use serde_json::{Map, Number, Value, json};
     use std::collections::{HashMap, HashSet};
 
     #[test]
@@ -296,7 +653,8 @@ mod tests {
                 &2,
                 &adjacent_days_in_month,
                 Option::None,
-                1
+                1,
+                1.0
             ),
             0.0
         );
@@ -306,7 +664,8 @@ mod tests {
                 &22,
                 &adjacent_days_in_month,
                 Option::None,
-                1
+                1,
+                1.0
             ),
             8.0
         );
@@ -316,7 +675,8 @@ mod tests {
                 &4,
                 &adjacent_days_in_month,
                 Option::None,
-                1
+                1,
+                1.0
             ),
             4.0
         );
@@ -326,7 +686,8 @@ mod tests {
                 &3,
                 &adjacent_days_in_month,
                 Option::None,
-                1
+                1,
+                1.0
             ),
             3.0
         );
@@ -344,6 +705,15 @@ mod tests {
         assert_eq!(is_weekend(&(2021, 11, 23), 23), false);
     }
 
+    #[test]
+    fn it_parses_weekdays() {
+        assert_eq!(
+            parse_weekdays("mon, Tue,friday").unwrap(),
+            vec![Weekday::Mon, Weekday::Tue, Weekday::Fri]
+        );
+        assert!(parse_weekdays("mon,someday").is_err());
+    }
+
     #[test]
     fn it_parses_hours_from_date() {
         let adjacent_days_in_month = vec![HashSet::from([1, 2, 3]), HashSet::from([2, 3, 4])];
@@ -373,6 +743,7 @@ mod tests {
             vec![1, 4, 6],
             &mut Default::default(),
             adjacent_days_in_month,
+            &HashMap::new(),
             1,
         );
 
@@ -395,6 +766,317 @@ mod tests {
         assert_eq!(day_vec.len(), 31);
     }
 
+    fn commit_time(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc2822(time).unwrap()
+    }
+
+    #[test]
+    fn it_estimates_hours_from_commit_times() {
+        assert_eq!(estimate_hours_from_commit_times(&[], 30, 120), 0.0);
+        assert_eq!(
+            estimate_hours_from_commit_times(
+                &[commit_time("Thu, 21 Oct 2021 10:00:00 +0200")],
+                30,
+                120
+            ),
+            0.5
+        );
+        // a single session from 9:30 to 13:00, given in any order
+        assert_eq!(
+            estimate_hours_from_commit_times(
+                &[
+                    commit_time("Thu, 21 Oct 2021 13:00:00 +0200"),
+                    commit_time("Thu, 21 Oct 2021 10:00:00 +0200"),
+                    commit_time("Thu, 21 Oct 2021 11:30:00 +0200"),
+                ],
+                30,
+                120
+            ),
+            3.5
+        );
+        // two sessions, 9:30 to 12:00 and 15:30 to 17:15
+        assert_eq!(
+            estimate_hours_from_commit_times(
+                &[
+                    commit_time("Thu, 21 Oct 2021 10:00:00 +0200"),
+                    commit_time("Thu, 21 Oct 2021 12:00:00 +0200"),
+                    commit_time("Thu, 21 Oct 2021 16:00:00 +0200"),
+                    commit_time("Thu, 21 Oct 2021 17:15:00 +0200"),
+                ],
+                30,
+                120
+            ),
+            4.25
+        );
+    }
+
+    #[test]
+    fn it_parses_hours_from_commit_times_when_estimating() {
+        let mut repository = Repository {
+            ..Default::default()
+        };
+        repository.parse_git_log_dates_from_git_history(mocks::create_mock_git_log(&[
+            "Mon, 1 Nov 2021 09:00:00 +0000",
+            "Mon, 1 Nov 2021 11:00:00 +0000",
+            "Tue, 2 Nov 2021 09:00:00 +0000",
+        ]));
+        repository.set_hours_estimation(HOURS_ESTIMATION_COMMITS.to_string(), Some(60), None);
+
+        let day_vec = parse_hours_from_date(
+            (2021, 11, 30),
+            vec![1, 2],
+            &mut repository,
+            vec![HashSet::from([1, 2])],
+            &HashMap::new(),
+            0,
+        );
+
+        assert_eq!(day_vec[0].get("hours").unwrap().as_f64().unwrap(), 3.0);
+        assert_eq!(day_vec[1].get("hours").unwrap().as_f64().unwrap(), 1.0);
+        assert_eq!(day_vec[2].get("hours").unwrap().as_f64().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn it_describes_days_from_commit_subjects() {
+        let mut repository = Repository {
+            ..Default::default()
+        };
+        repository.parse_git_log_dates_from_git_history(
+            [
+                mocks::create_mock_git_log_record(
+                    "Mon, 1 Nov 2021 11:00:00 +0000",
+                    "Fix invoice totals",
+                    None,
+                ),
+                mocks::create_mock_git_log_record(
+                    "Mon, 1 Nov 2021 10:00:00 +0000",
+                    "Merge branch 'main' into invoices",
+                    None,
+                ),
+                mocks::create_mock_git_log_record(
+                    "Mon, 1 Nov 2021 09:00:00 +0000",
+                    "Add invoices",
+                    None,
+                ),
+                mocks::create_mock_git_log_record(
+                    "Tue, 2 Nov 2021 09:00:00 +0000",
+                    "Add invoices",
+                    None,
+                ),
+            ]
+            .concat(),
+        );
+
+        let day_vec = parse_hours_from_date(
+            (2021, 11, 30),
+            vec![1, 2],
+            &mut repository,
+            vec![],
+            &HashMap::new(),
+            0,
+        );
+        assert_eq!(
+            day_vec[0].get("description"),
+            Some(&json!("Add invoices; Fix invoice totals"))
+        );
+        assert_eq!(day_vec[2].get("description"), None);
+
+        // a description written by the user isn't replaced
+        let mut timesheet = HashMap::new();
+        timesheet.insert(
+            "2021".to_string(),
+            HashMap::from([("11".to_string(), day_vec)]),
+        );
+        repository.set_timesheet(timesheet);
+        repository
+            .update_description_on_month_day_entry(&[
+                None,
+                None,
+                Some("2".to_string()),
+                Some("11".to_string()),
+                Some("2021".to_string()),
+                Some("Invoice review with the client".to_string()),
+            ])
+            .unwrap();
+
+        let day_vec = parse_hours_from_date(
+            (2021, 11, 30),
+            vec![1, 2],
+            &mut repository,
+            vec![],
+            &HashMap::new(),
+            0,
+        );
+        assert_eq!(
+            day_vec[0].get("description"),
+            Some(&json!("Add invoices; Fix invoice totals"))
+        );
+        assert_eq!(
+            day_vec[1].get("description"),
+            Some(&json!("Invoice review with the client"))
+        );
+        assert_eq!(day_vec[1].get("description_edited"), Some(&json!(true)));
+    }
+
+    #[test]
+    fn it_splits_hours_by_weight() {
+        assert_eq!(
+            split_hours_by_weight(8.0, &[20.0, 1.0], 0, 0.01),
+            Some(7.62)
+        );
+        assert_eq!(
+            split_hours_by_weight(8.0, &[20.0, 1.0], 1, 0.01),
+            Some(0.38)
+        );
+        // the hundredths left over go to the largest remainders
+        let shares: Vec<f64> = (0..3)
+            .map(|index| split_hours_by_weight(8.0, &[1.0, 1.0, 1.0], index, 0.01).unwrap())
+            .collect();
+        assert_eq!(shares, vec![2.67, 2.67, 2.66]);
+        assert_eq!(split_hours_by_weight(8.0, &[0.0, 0.0], 0, 0.01), None);
+    }
+
+    #[test]
+    fn it_splits_hours_by_commit_count() {
+        let mut repositories: Vec<Repository> = vec![Default::default(), Default::default()];
+        repositories[0].parse_git_log_dates_from_git_history(mocks::create_mock_git_log(&[
+            "Mon, 1 Nov 2021 09:00:00 +0000",
+            "Mon, 1 Nov 2021 10:00:00 +0000",
+            "Mon, 1 Nov 2021 11:00:00 +0000",
+        ]));
+        repositories[1].parse_git_log_dates_from_git_history(mocks::create_mock_git_log(&[
+            "Mon, 1 Nov 2021 12:00:00 +0000",
+        ]));
+        for repository in repositories.iter_mut() {
+            repository.set_hours_split(HOURS_SPLIT_COMMITS.to_string());
+        }
+
+        let day_weights = get_day_weights(&repositories);
+        assert_eq!(
+            day_weights[&NaiveDate::from_ymd_opt(2021, 11, 1).unwrap()],
+            vec![3.0, 1.0]
+        );
+
+        let day_vec = parse_hours_from_date(
+            (2021, 11, 30),
+            vec![1],
+            &mut repositories[0],
+            vec![HashSet::from([1])],
+            &day_weights,
+            0,
+        );
+        assert_eq!(day_vec[0].get("hours").unwrap().as_f64().unwrap(), 6.0);
+    }
+
+    #[test]
+    fn it_returns_no_weights_for_an_even_split() {
+        let mut repository: Repository = Default::default();
+        repository.parse_git_log_dates_from_git_history(mocks::create_mock_git_log(&[
+            "Mon, 1 Nov 2021 12:00:00 +0000",
+        ]));
+        assert!(get_day_weights(&[repository]).is_empty());
+    }
+
+    #[test]
+    fn it_splits_fractional_hours_in_increments() {
+        let adjacent_days_in_month = vec![HashSet::from([1])];
+        let split = |increment: f64| -> Vec<f64> {
+            (0..2)
+                .map(|index| {
+                    return_worked_hours_from_worked_days(
+                        &[1],
+                        &1,
+                        &adjacent_days_in_month,
+                        Some(7.5),
+                        index,
+                        increment,
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(split(1.0), vec![4.0, 3.5]);
+        assert_eq!(split(0.5), vec![4.0, 3.5]);
+        assert_eq!(split(0.25), vec![3.75, 3.75]);
+        assert_eq!(split(0.01), vec![3.75, 3.75]);
+
+        assert_eq!(
+            split_hours_by_weight(7.5, &[20.0, 1.0], 0, 0.25),
+            Some(7.25)
+        );
+        assert_eq!(
+            split_hours_by_weight(7.5, &[20.0, 1.0], 1, 0.25),
+            Some(0.25)
+        );
+        assert_eq!(split_hours_by_weight(7.4, &[1.0, 1.0], 0, 0.25), Some(3.75));
+        assert_eq!(split_hours_by_weight(7.4, &[1.0, 1.0], 1, 0.25), Some(3.65));
+    }
+
+    #[test]
+    fn it_rounds_hours_with_a_policy() {
+        assert_eq!(
+            HoursRounding::from_setting(None),
+            HoursRounding::Nearest(1.0)
+        );
+        assert_eq!(
+            HoursRounding::from_setting(Some("none")),
+            HoursRounding::None
+        );
+        assert_eq!(
+            HoursRounding::from_setting(Some("up_0.25")),
+            HoursRounding::Up(0.25)
+        );
+        assert_eq!(
+            HoursRounding::from_setting(Some("nearest_0.5")),
+            HoursRounding::Nearest(0.5)
+        );
+
+        assert_eq!(HoursRounding::None.round(3.333), 3.33);
+        assert_eq!(HoursRounding::Nearest(0.25).round(3.1), 3.0);
+        assert_eq!(HoursRounding::Nearest(0.5).round(3.3), 3.5);
+        assert_eq!(HoursRounding::Up(0.25).round(3.01), 3.25);
+        assert_eq!(HoursRounding::Up(0.25).round(3.25), 3.25);
+        assert_eq!(HoursRounding::Up(1.0).round(0.5), 1.0);
+    }
+
+    #[test]
+    fn it_attributes_commits_to_days() {
+        let late_commit = commit_time("Sat, 6 Nov 2021 01:30:00 +0100");
+
+        // without settings the commit keeps its own offset
+        let commit_day = CommitDay::new(None, None);
+        assert_eq!(
+            commit_day.date(late_commit),
+            NaiveDate::from_ymd_opt(2021, 11, 6).unwrap()
+        );
+
+        // committed while travelling, but the client works in New York
+        let commit_day = CommitDay::new(Some("America/New_York"), None);
+        assert_eq!(
+            commit_day.date(late_commit),
+            NaiveDate::from_ymd_opt(2021, 11, 5).unwrap()
+        );
+
+        // work until 3am counts as the previous day
+        let commit_day = CommitDay::new(None, Some(3));
+        assert_eq!(
+            commit_day.date(late_commit),
+            NaiveDate::from_ymd_opt(2021, 11, 5).unwrap()
+        );
+        assert_eq!(
+            commit_day.date(commit_time("Sat, 6 Nov 2021 03:00:00 +0100")),
+            NaiveDate::from_ymd_opt(2021, 11, 6).unwrap()
+        );
+
+        // an unknown timezone is ignored
+        assert!(!is_valid_timezone("Mars/Olympus_Mons"));
+        let commit_day = CommitDay::new(Some("Mars/Olympus_Mons"), None);
+        assert_eq!(
+            commit_day.date(late_commit),
+            NaiveDate::from_ymd_opt(2021, 11, 6).unwrap()
+        );
+    }
+
     #[test]
     fn it_finds_adjacent_git_log_days_for_a_given_month() {
         let git_log_dates: Vec<GitLogDates> = vec![
@@ -480,7 +1162,8 @@ mod tests {
                 &1,
                 &adjacent_days_in_month,
                 Option::None,
-                0 // First repository
+                0, // First repository
+                1.0
             ),
             3.0
         );
@@ -490,7 +1173,8 @@ mod tests {
                 &1,
                 &adjacent_days_in_month,
                 Option::None,
-                1 // Second repository
+                1, // Second repository
+                1.0
             ),
             3.0
         );
@@ -500,7 +1184,8 @@ mod tests {
                 &1,
                 &adjacent_days_in_month,
                 Option::None,
-                2 // Third repository
+                2, // Third repository
+                1.0
             ),
             2.0
         );
@@ -517,7 +1202,8 @@ mod tests {
                 &5,
                 &adjacent_days_in_month,
                 Option::None,
-                0 // First repository
+                0, // First repository
+                1.0
             ),
             2.0
         );
@@ -527,9 +1213,44 @@ mod tests {
                 &5,
                 &adjacent_days_in_month,
                 Option::None,
-                4 // Fifth repository
+                4, // Fifth repository
+                1.0
             ),
             1.0
         );
     }
+
+    #[test]
+    fn it_distributes_hours_evenly_when_the_first_repository_has_no_commits() {
+        // repositories A, B and C, where only B and C were worked on on the 1st
+        let days_a = HashSet::from([2]);
+        let days_b = HashSet::from([1]);
+        let days_c = HashSet::from([1]);
+        let hours = |worked_days: &HashSet<u32>, adjacent: Vec<HashSet<u32>>, repo_index| {
+            return_worked_hours_from_worked_days(
+                &worked_days.iter().cloned().collect::<Vec<u32>>(),
+                &1,
+                &adjacent,
+                Option::from(7.0),
+                repo_index,
+                1.0,
+            )
+        };
+
+        let a = hours(&days_a, vec![days_b.clone(), days_c.clone()], 0);
+        let b = hours(&days_b, vec![days_a.clone(), days_c.clone()], 1);
+        let c = hours(&days_c, vec![days_a.clone(), days_b.clone()], 2);
+        assert_eq!((a, b, c), (0.0, 4.0, 3.0));
+    }
+
+    #[test]
+    fn it_keeps_the_adjacent_days_in_repository_order() {
+        let repo_a: GitLogDates = HashMap::from([(2021, HashMap::from([(9, HashSet::from([1]))]))]);
+        let repo_b: GitLogDates =
+            HashMap::from([(2021, HashMap::from([(10, HashSet::from([1]))]))]);
+        assert_eq!(
+            get_adjacent_git_log_days_for_month(vec![repo_a, repo_b], &2021, &10),
+            vec![HashSet::new(), HashSet::from([1])]
+        );
+    }
 }
@@ -1,13 +1,21 @@
 use crate::data::client_repositories::ClientRepositories;
+use crate::data::git_commit::GitCommit;
+use crate::data::git_history::GitHistory;
+use crate::data::locked_month::LockedMonth;
+use crate::data::manual_entry::ManualEntry;
 use crate::data::repository::Repository;
+use crate::data::timesheet_history::{self, ChangedDays, TimesheetChange};
 use crate::interface::help_prompt::ConfigurationDoc;
 use crate::utils::is_test_mode;
 use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use directories::ProjectDirs;
 use rusqlite::{Connection, OptionalExtension, Transaction, params};
 use serde_json::{Map, Number, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ClientRepository {
@@ -22,9 +30,11 @@ pub fn load_config_doc_from_db() -> Result<ConfigurationDoc, Box<dyn std::error:
     load_config_doc(&conn)
 }
 
-/// Directly save configuration document to database
+/// Directly save configuration document to database. The days the command changed are
+/// kept in the timesheet history under its source
 pub fn save_config_doc_to_db(
     config_doc: &ConfigurationDoc,
+    changed_days: &ChangedDays,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if is_test_mode() {
         return Ok(());
@@ -39,7 +49,7 @@ pub fn save_config_doc_to_db(
         remove_deleted_client_repositories(&tx, config_doc)?;
 
         for client_repo in config_doc.iter() {
-            save_client_repository(&tx, client_repo)?;
+            save_client_repository(&tx, client_repo, changed_days)?;
         }
         Ok(())
     })();
@@ -126,6 +136,8 @@ fn delete_client_repositories_by_client_id(
             "DELETE FROM git_log_years WHERE repository_id = ?",
             params![repo_id],
         )?;
+
+        delete_git_history(tx, repo_id)?;
     }
 
     // Delete repositories
@@ -134,6 +146,16 @@ fn delete_client_repositories_by_client_id(
         params![client_id],
     )?;
 
+    tx.execute(
+        "DELETE FROM manual_entries WHERE client_id = ?",
+        params![client_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM locked_months WHERE client_id = ?",
+        params![client_id],
+    )?;
+
     // Delete approvers (need to find client_repository_ids first)
     let mut stmt = tx.prepare("SELECT id FROM client_repositories WHERE client_id = ?")?;
     let client_repo_ids: Vec<i64> = stmt
@@ -161,31 +183,123 @@ fn delete_client_repositories_by_client_id(
     Ok(())
 }
 
+/// Set by the global --db flag, and takes precedence over AUTOLOG_DB
+static DB_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// The outcome of copying an old Homebrew database, which is only tried once per run
+static DB_MIGRATION: OnceLock<Result<(), String>> = OnceLock::new();
+
+pub fn set_db_path_override(path: &str) {
+    let _ = DB_PATH_OVERRIDE.set(PathBuf::from(path));
+}
+
+/// Locations that Homebrew installs used before the database moved to the data directory
+fn get_homebrew_db_candidates() -> Vec<PathBuf> {
+    let mut prefixes: Vec<PathBuf> = vec![];
+    if let Ok(prefix) = std::env::var("HOMEBREW_PREFIX") {
+        prefixes.push(PathBuf::from(prefix));
+    }
+    prefixes.extend(
+        ["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"]
+            .iter()
+            .map(PathBuf::from),
+    );
+
+    prefixes
+        .into_iter()
+        .map(|prefix| prefix.join("etc").join("autolog").join(DB_FILE_NAME))
+        .collect()
+}
+
+/// Copy the first existing database from the candidates to the target, if the target doesn't exist.
+/// The original is left in place so that an older version of autolog can still read it.
+fn migrate_db(target: &Path, candidates: &[PathBuf]) -> Result<Option<PathBuf>> {
+    if target.exists() {
+        return Ok(None);
+    }
+
+    match candidates.iter().find(|candidate| candidate.is_file()) {
+        Some(candidate) => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(candidate, target).context(format!(
+                "Failed to copy database from {:?} to {:?}",
+                candidate, target
+            ))?;
+            Ok(Some(candidate.clone()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn resolve_db_path(
+    path_override: Option<&PathBuf>,
+    env_path: Option<String>,
+    data_dir: Option<PathBuf>,
+) -> Result<PathBuf> {
+    if let Some(path) = path_override {
+        return Ok(path.clone());
+    }
+
+    if let Some(path) = env_path.filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    data_dir
+        .map(|data_dir| data_dir.join(DB_FILE_NAME))
+        .context("Could not find a data directory for the database. Set AUTOLOG_DB or pass --db")
+}
+
 /// Get the platform-specific path for the database
-pub fn get_db_path() -> PathBuf {
+pub fn get_db_path() -> Result<PathBuf> {
     if is_test_mode() {
-        return PathBuf::from("file:memdb_test?mode=memory&cache=shared");
+        return Ok(PathBuf::from("file:memdb_test?mode=memory&cache=shared"));
     }
 
-    // Get Homebrew prefix - this should always be available when installed through Homebrew
-    let homebrew_prefix = std::env::var("HOMEBREW_PREFIX")
-        .expect("HOMEBREW_PREFIX environment variable not found. This application should be installed via Homebrew.");
+    let data_dir = ProjectDirs::from("dev", "autolog", "autolog")
+        .map(|project_dirs| project_dirs.data_dir().to_path_buf());
+    let is_default = DB_PATH_OVERRIDE.get().is_none()
+        && std::env::var("AUTOLOG_DB").map_or(true, |path| path.is_empty());
+    let db_path = resolve_db_path(
+        DB_PATH_OVERRIDE.get(),
+        std::env::var("AUTOLOG_DB").ok(),
+        data_dir,
+    )?;
 
-    // Use Homebrew's etc directory pattern: #{HOMEBREW_PREFIX}/etc/#{name}
-    let config_dir = PathBuf::from(homebrew_prefix).join("etc").join("autolog");
+    // Only copy an old Homebrew database into the default location. Connections are opened
+    // from several threads, so the copy is only tried once and the others wait for it
+    if is_default {
+        DB_MIGRATION
+            .get_or_init(|| {
+                match migrate_db(&db_path, &get_homebrew_db_candidates()) {
+                    Ok(Some(migrated_from)) => {
+                        // stderr, so that a timesheet exported to stdout isn't changed
+                        eprintln!(
+                            "Copied autolog database from {:?} to {:?}",
+                            migrated_from, db_path
+                        );
+                        Ok(())
+                    }
+                    Ok(None) => Ok(()),
+                    Err(err) => Err(format!("{:#}", err)),
+                }
+            })
+            .clone()
+            .map_err(anyhow::Error::msg)?;
+    }
 
-    // Ensure directory exists
-    fs::create_dir_all(&config_dir).expect(&format!(
-        "Failed to create config directory at {:?}",
-        config_dir
-    ));
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create data directory at {:?}", parent))?;
+    }
 
-    config_dir.join(DB_FILE_NAME)
+    Ok(db_path)
 }
 
 /// Get a database connection
 pub fn get_connection() -> Result<Connection> {
-    let db_path = get_db_path();
+    let db_path = get_db_path()?;
     let db_path_str = db_path.to_str().unwrap_or("");
 
     let conn = if is_test_mode() {
@@ -207,6 +321,9 @@ pub fn get_connection() -> Result<Connection> {
     // Enable foreign key support
     conn.execute("PRAGMA foreign_keys = ON", [])?;
 
+    // history is stored from several threads at once, so wait for the other writers
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+
     // Initialize schema if needed
     init_schema(&conn).context("Failed to initialize database schema")?;
 
@@ -285,6 +402,160 @@ pub fn get_cached_version() -> Result<Option<String>, Box<dyn std::error::Error>
     }
 }
 
+/// Load the commits stored at the last scan of a repository
+pub fn load_git_history(
+    repository_id: &str,
+) -> Result<Option<GitHistory>, Box<dyn std::error::Error>> {
+    let conn = get_connection()?;
+    read_git_history(&conn, repository_id)
+}
+
+/// Replace the stored commits for a repository
+pub fn save_git_history(
+    repository_id: &str,
+    history: &GitHistory,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+    delete_git_history(&tx, repository_id)?;
+    write_git_history(&tx, repository_id, history, &history.commits)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Store the commits read since the last scan, along with the refs they were read up to
+pub fn add_to_git_history(
+    repository_id: &str,
+    history: &GitHistory,
+    new_commits: &[GitCommit],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+    write_git_history(&tx, repository_id, history, new_commits)?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn read_git_history(
+    conn: &Connection,
+    repository_id: &str,
+) -> Result<Option<GitHistory>, Box<dyn std::error::Error>> {
+    let scan = conn
+        .query_row(
+            "SELECT scan_key, scanned_commits FROM git_scans WHERE repository_id = ?",
+            params![repository_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()?;
+
+    let (scan_key, scanned_commits) = match scan {
+        Some(scan) => scan,
+        None => return Ok(None),
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT hash, author_name, author_email, author_date, committer_date, subject, lines_changed
+         FROM git_commits WHERE repository_id = ?",
+    )?;
+    let rows = stmt.query_map(params![repository_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, Option<i64>>(6)?,
+        ))
+    })?;
+
+    let mut commits = vec![];
+    for row in rows {
+        let (hash, author_name, author_email, author_date, committer_date, subject, lines) = row?;
+        commits.push(GitCommit {
+            hash,
+            author_name,
+            author_email,
+            author_date: chrono::DateTime::parse_from_rfc3339(&author_date)?,
+            committer_date: chrono::DateTime::parse_from_rfc3339(&committer_date)?,
+            subject,
+            lines_changed: lines.map(|lines| lines as u64),
+        });
+    }
+
+    Ok(Some(GitHistory {
+        scan_key,
+        scanned_commits: serde_json::from_str(&scanned_commits)?,
+        commits,
+    }))
+}
+
+fn write_git_history(
+    tx: &Transaction,
+    repository_id: &str,
+    history: &GitHistory,
+    commits: &[GitCommit],
+) -> Result<(), Box<dyn std::error::Error>> {
+    tx.execute(
+        "INSERT OR REPLACE INTO git_scans (repository_id, scan_key, scanned_commits, scanned_at)
+         VALUES (?, ?, ?, ?)",
+        params![
+            repository_id,
+            history.scan_key,
+            serde_json::to_string(&history.scanned_commits)?,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+
+    let mut stmt = tx.prepare(
+        "INSERT OR REPLACE INTO git_commits
+         (repository_id, hash, author_name, author_email, author_date, committer_date, subject, lines_changed)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )?;
+    for commit in commits {
+        stmt.execute(params![
+            repository_id,
+            commit.hash,
+            commit.author_name,
+            commit.author_email,
+            commit.author_date.to_rfc3339(),
+            commit.committer_date.to_rfc3339(),
+            commit.subject,
+            commit.lines_changed.map(|lines| lines as i64)
+        ])?;
+    }
+
+    Ok(())
+}
+
+fn delete_git_history(tx: &Transaction, repository_id: &str) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "DELETE FROM git_commits WHERE repository_id = ?",
+        params![repository_id],
+    )?;
+    tx.execute(
+        "DELETE FROM git_scans WHERE repository_id = ?",
+        params![repository_id],
+    )?;
+    Ok(())
+}
+
+/// Columns added to the repositories table after its first release
+const REPOSITORY_COLUMNS_ADDED: [(&str, &str); 12] = [
+    ("hours_estimation", "TEXT"),
+    ("commit_lead_in_minutes", "INTEGER"),
+    ("commit_session_gap_minutes", "INTEGER"),
+    ("hours_split", "TEXT"),
+    ("hours_rounding", "TEXT"),
+    ("timezone", "TEXT"),
+    ("day_start_hour", "INTEGER"),
+    ("author_identities", "TEXT"),
+    ("use_mailmap", "INTEGER"),
+    ("included_refs", "TEXT"),
+    ("excluded_refs", "TEXT"),
+    ("vcs", "TEXT"),
+];
+
 /// Initialize the database schema if tables don't exist
 fn init_schema(conn: &Connection) -> Result<()> {
     conn.execute(
@@ -344,12 +615,29 @@ fn init_schema(conn: &Connection) -> Result<()> {
     service_username TEXT,
     created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
     default_hours FLOAT NOT NULL DEFAULT 8.0,
+    hours_estimation TEXT,
+    commit_lead_in_minutes INTEGER,
+    commit_session_gap_minutes INTEGER,
+    hours_split TEXT,
+    hours_rounding TEXT,
+    timezone TEXT,
+    day_start_hour INTEGER,
+    author_identities TEXT, -- JSON list of the other identities the user commits under
+    use_mailmap INTEGER,
+    included_refs TEXT, -- JSON list of ref patterns to read history from
+    excluded_refs TEXT, -- JSON list of ref patterns to skip
+    vcs TEXT, -- the backend history is read with, git when missing
     FOREIGN KEY (client_id) REFERENCES clients (id)
     )",
         [],
     )
     .context("Failed to create repositories table")?;
 
+    // Databases created before these columns existed need them adding
+    for (column, definition) in REPOSITORY_COLUMNS_ADDED {
+        add_column_if_missing(conn, "repositories", column, definition)?;
+    }
+
     // Create git_log_dates table with proper structure for HashMap<i32, HashMap<u32, HashSet<u32>>>
     conn.execute(
         "CREATE TABLE IF NOT EXISTS git_log_years (
@@ -390,6 +678,34 @@ fn init_schema(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create git_log_days table")?;
 
+    // Commits read from each repository, so that only new commits are read from git next time
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS git_commits (
+    repository_id TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    author_email TEXT NOT NULL,
+    author_date TEXT NOT NULL,
+    committer_date TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    lines_changed INTEGER,
+    PRIMARY KEY (repository_id, hash)
+    )",
+        [],
+    )
+    .context("Failed to create git_commits table")?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS git_scans (
+    repository_id TEXT PRIMARY KEY,
+    scan_key TEXT NOT NULL,
+    scanned_commits TEXT NOT NULL, -- JSON array of the commits at the tips of the refs
+    scanned_at TEXT NOT NULL
+    )",
+        [],
+    )
+    .context("Failed to create git_scans table")?;
+
     // Create simplified timesheet table with direct column storage
     conn.execute(
         "CREATE TABLE IF NOT EXISTS timesheet_entries (
@@ -411,6 +727,65 @@ fn init_schema(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create timesheet_entries table")?;
 
+    // History saved before rows kept their client and namespace is copied across once
+    // the table has been created again
+    let copy_history = {
+        let columns = table_columns(conn, "timesheet_history")?;
+        !columns.is_empty() && !columns.iter().any(|name| name == "client_id")
+    };
+    if copy_history {
+        conn.execute(
+            "ALTER TABLE timesheet_history RENAME TO timesheet_history_old",
+            [],
+        )
+        .context("Failed to rename timesheet_history table")?;
+    }
+
+    // Every change to the hours of a timesheet day. Rows are only ever added and keep the
+    // client and namespace, so the reason a month's hours changed can be shown after it's
+    // been sent, even once the repository has been removed
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS timesheet_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    repository_id TEXT,
+    client_id TEXT NOT NULL,
+    namespace TEXT NOT NULL, -- the alias when the repository has one
+    year TEXT NOT NULL,
+    month TEXT NOT NULL,
+    day INTEGER NOT NULL,
+    old_hours REAL, -- NULL when the day is saved for the first time
+    new_hours REAL NOT NULL,
+    source TEXT NOT NULL, -- git, edit, reset or manual
+    changed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    )",
+        [],
+    )
+    .context("Failed to create timesheet_history table")?;
+
+    if copy_history {
+        conn.execute(
+            "INSERT INTO timesheet_history (
+    id, repository_id, client_id, namespace, year, month, day, old_hours, new_hours, source,
+    changed_at
+    )
+    SELECT h.id, h.repository_id, r.client_id, COALESCE(r.namespace_alias, r.namespace, ''),
+        h.year, h.month, h.day, h.old_hours, h.new_hours, h.source, h.changed_at
+    FROM timesheet_history_old h
+    JOIN repositories r ON r.id = h.repository_id",
+            [],
+        )
+        .context("Failed to copy timesheet_history table")?;
+        conn.execute("DROP TABLE timesheet_history_old", [])
+            .context("Failed to drop old timesheet_history table")?;
+    }
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS timesheet_history_month
+    ON timesheet_history (client_id, year, month)",
+        [],
+    )
+    .context("Failed to create timesheet_history index")?;
+
     // Create client_repositories table (for joining clients and users with approval info)
     conn.execute(
         "CREATE TABLE IF NOT EXISTS client_repositories (
@@ -441,16 +816,79 @@ fn init_schema(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create approvers table")?;
 
+    // Time logged by hand for work that doesn't show up in git
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS manual_entries (
+    id TEXT PRIMARY KEY,
+    client_id TEXT NOT NULL,
+    date TEXT NOT NULL,
+    hours REAL NOT NULL,
+    project TEXT,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (client_id) REFERENCES clients (id)
+    )",
+        [],
+    )
+    .context("Failed to create manual_entries table")?;
+
+    // Months whose hours are kept as they are rather than worked out from git again
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS locked_months (
+    client_id TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    locked_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (client_id, year, month),
+    FOREIGN KEY (client_id) REFERENCES clients (id)
+    )",
+        [],
+    )
+    .context("Failed to create locked_months table")?;
+
     Ok(())
 }
 
+/// Add a column to an existing table, if the table doesn't have it already
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let columns = table_columns(conn, table)?;
+
+    if !columns.iter().any(|name| name == column) {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .context(format!(
+            "Failed to add {} column to {} table",
+            column, table
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// The names of a table's columns, empty when the table doesn't exist
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+
+    Ok(columns)
+}
+
 /// Delete the database file (equivalent to delete_config_file)
 pub fn delete_db() -> Result<(), Box<dyn std::error::Error>> {
     if is_test_mode() {
         return Ok(());
     }
 
-    let db_path = get_db_path();
+    let db_path = get_db_path()?;
     if db_path.exists() {
         std::fs::remove_file(db_path)?;
     }
@@ -462,6 +900,7 @@ pub fn delete_db() -> Result<(), Box<dyn std::error::Error>> {
 pub fn save_client_repository(
     tx: &rusqlite::Transaction,
     client_repo: &ClientRepositories,
+    changed_days: &ChangedDays,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Save client if present
     if let Some(client) = &client_repo.client {
@@ -476,6 +915,9 @@ pub fn save_client_repository(
             ],
         )?;
 
+        write_manual_entries(tx, &client.id, client_repo.manual_entries.as_deref())?;
+        write_locked_months(tx, &client.id, client_repo.locked_months.as_deref())?;
+
         // IMPORTANT: Get all existing repositories for this client
         let mut existing_repos = Vec::new();
         let mut stmt = tx.prepare("SELECT id, namespace FROM repositories WHERE client_id = ?")?;
@@ -602,14 +1044,31 @@ pub fn save_client_repository(
                         )?;
                     }
 
+                    let author_identities = match repo.author_identities.as_ref() {
+                        Some(identities) => Some(serde_json::to_string(identities)?),
+                        None => None,
+                    };
+                    let included_refs = match repo.included_refs.as_ref() {
+                        Some(patterns) => Some(serde_json::to_string(patterns)?),
+                        None => None,
+                    };
+                    let excluded_refs = match repo.excluded_refs.as_ref() {
+                        Some(patterns) => Some(serde_json::to_string(patterns)?),
+                        None => None,
+                    };
+
                     // Save repository
                     tx.execute(
                         "INSERT OR REPLACE INTO repositories (
                         id, namespace, namespace_alias, repo_path, git_path,
                         user_id, name, email, client_id, client_name,
                         client_contact_person, client_address, project_number,
-                        service, service_username, default_hours
-                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                        service, service_username, default_hours, hours_estimation,
+                        commit_lead_in_minutes, commit_session_gap_minutes, hours_split,
+                        hours_rounding, timezone, day_start_hour, author_identities, use_mailmap,
+                        included_refs, excluded_refs, vcs
+                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                        ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28)",
                         params![
                             id,
                             repo.namespace,
@@ -626,7 +1085,19 @@ pub fn save_client_repository(
                             repo.project_number,
                             repo.service,
                             repo.service_username,
-                            repo.default_hours
+                            repo.default_hours,
+                            repo.hours_estimation,
+                            repo.commit_lead_in_minutes,
+                            repo.commit_session_gap_minutes,
+                            repo.hours_split,
+                            repo.hours_rounding,
+                            repo.timezone,
+                            repo.day_start_hour,
+                            author_identities,
+                            repo.use_mailmap,
+                            included_refs,
+                            excluded_refs,
+                            repo.vcs
                         ],
                     )?;
 
@@ -664,6 +1135,21 @@ pub fn save_client_repository(
 
                     // Save timesheet if present
                     if let Some(timesheet) = &repo.timesheet {
+                        // The hours being replaced are kept in the history
+                        let saved_hours = read_saved_hours(tx, id)?;
+                        let mut history_stmt = tx.prepare(
+                            "INSERT INTO timesheet_history (
+                            repository_id, client_id, namespace, year, month, day, old_hours,
+                            new_hours, source
+                            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                        )?;
+                        let namespace = repo
+                            .namespace_alias
+                            .as_ref()
+                            .or(repo.namespace.as_ref())
+                            .cloned()
+                            .unwrap_or_default();
+
                         // Prepare statement for efficiency
                         let mut stmt = tx.prepare(
                             "INSERT OR REPLACE INTO timesheet_entries (
@@ -709,6 +1195,23 @@ pub fn save_client_repository(
                                         Some(serde_json::to_string(&extra_data)?)
                                     };
 
+                                    let old_hours = saved_hours
+                                        .get(&(year.clone(), month.clone(), day))
+                                        .copied();
+                                    if TimesheetChange::is_change(old_hours, hours) {
+                                        let date = NaiveDate::from_ymd_opt(
+                                            year.parse()?,
+                                            month.parse()?,
+                                            day as u32,
+                                        )
+                                        .ok_or("Invalid timesheet day")?;
+                                        let source = changed_days.get_source(id, &date);
+                                        history_stmt.execute(params![
+                                            id, client.id, namespace, year, month, day, old_hours,
+                                            hours, source
+                                        ])?;
+                                    }
+
                                     // Insert or update the entry
                                     stmt.execute(params![
                                         id,
@@ -757,6 +1260,8 @@ pub fn save_client_repository(
                     params![repo_id],
                 )?;
 
+                delete_git_history(tx, &repo_id)?;
+
                 // Finally delete the repository itself
                 tx.execute("DELETE FROM repositories WHERE id = ?", params![repo_id])?;
             }
@@ -766,6 +1271,233 @@ pub fn save_client_repository(
     Ok(())
 }
 
+/// The hours saved on each day of a repository's timesheet, by year, month and day
+fn read_saved_hours(
+    tx: &Transaction,
+    repository_id: &str,
+) -> Result<HashMap<(String, String, usize), f64>, rusqlite::Error> {
+    let mut stmt = tx
+        .prepare("SELECT year, month, day, hours FROM timesheet_entries WHERE repository_id = ?")?;
+    let rows = stmt.query_map(params![repository_id], |row| {
+        Ok((
+            (row.get(0)?, row.get(1)?, row.get(2)?),
+            row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+        ))
+    })?;
+
+    rows.collect()
+}
+
+/// Load the changes made to the hours of a client's timesheet in a month, oldest first
+pub fn load_timesheet_history(
+    client_id: &str,
+    year: i32,
+    month: u32,
+) -> Result<Vec<TimesheetChange>, Box<dyn std::error::Error>> {
+    let conn = get_connection()?;
+    read_timesheet_history(&conn, client_id, year, month)
+}
+
+fn read_timesheet_history(
+    conn: &Connection,
+    client_id: &str,
+    year: i32,
+    month: u32,
+) -> Result<Vec<TimesheetChange>, Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT namespace, day, old_hours, new_hours, source, changed_at
+         FROM timesheet_history
+         WHERE client_id = ?1 AND year = ?2 AND month = ?3
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map(
+        params![client_id, year.to_string(), month.to_string()],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, Option<f64>>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        },
+    )?;
+
+    let mut changes = vec![];
+    for row in rows {
+        let (namespace, day, old_hours, new_hours, source, changed_at) = row?;
+        changes.push(TimesheetChange {
+            namespace,
+            date: NaiveDate::from_ymd_opt(year, month, day).ok_or("Invalid timesheet day")?,
+            old_hours,
+            new_hours,
+            source,
+            changed_at: NaiveDateTime::parse_from_str(&changed_at, "%Y-%m-%d %H:%M:%S")?,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Replace the manual entries stored for a client, adding the change to the hours of each
+/// project and day to the timesheet history
+fn write_manual_entries(
+    tx: &Transaction,
+    client_id: &str,
+    entries: Option<&[ManualEntry]>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_entries = read_manual_entries(tx, client_id)?.unwrap_or_default();
+    let saved_hours = total_manual_hours(&saved_entries);
+    let hours = total_manual_hours(entries.unwrap_or_default());
+
+    let mut history_stmt = tx.prepare(
+        "INSERT INTO timesheet_history (
+        client_id, namespace, year, month, day, old_hours, new_hours, source
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    let days: BTreeSet<_> = saved_hours.keys().chain(hours.keys()).collect();
+    for &(date, namespace) in days {
+        let old_hours = saved_hours.get(&(date, namespace)).copied();
+        let new_hours = hours.get(&(date, namespace)).copied().unwrap_or(0.0);
+        if TimesheetChange::is_change(old_hours, new_hours) {
+            history_stmt.execute(params![
+                client_id,
+                namespace,
+                date.year().to_string(),
+                date.month().to_string(),
+                date.day(),
+                old_hours,
+                new_hours,
+                timesheet_history::SOURCE_MANUAL
+            ])?;
+        }
+    }
+
+    tx.execute(
+        "DELETE FROM manual_entries WHERE client_id = ?",
+        params![client_id],
+    )?;
+
+    let mut stmt = tx.prepare(
+        "INSERT INTO manual_entries (id, client_id, date, hours, project, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for entry in entries.unwrap_or_default() {
+        stmt.execute(params![
+            entry.id,
+            client_id,
+            entry.date.format("%Y-%m-%d").to_string(),
+            entry.hours,
+            entry.project,
+            entry.note
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// The hours of manual entries added up for each day and line of the timesheet
+fn total_manual_hours(entries: &[ManualEntry]) -> BTreeMap<(NaiveDate, &str), f64> {
+    let mut hours = BTreeMap::new();
+    for entry in entries {
+        *hours
+            .entry((entry.date, entry.get_namespace()))
+            .or_insert(0.0) += entry.hours;
+    }
+
+    hours
+}
+
+fn read_manual_entries(
+    conn: &Connection,
+    client_id: &str,
+) -> Result<Option<Vec<ManualEntry>>, Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, date, hours, project, note FROM manual_entries
+         WHERE client_id = ?1 ORDER BY date, rowid",
+    )?;
+    let rows = stmt.query_map(params![client_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, f64>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+
+    let mut entries = vec![];
+    for row in rows {
+        let (id, date, hours, project, note) = row?;
+        entries.push(ManualEntry {
+            id,
+            date: chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")?,
+            hours,
+            project,
+            note,
+        });
+    }
+
+    Ok(if entries.is_empty() {
+        None
+    } else {
+        Some(entries)
+    })
+}
+
+/// Replace the locked months stored for a client, keeping when each was first locked
+fn write_locked_months(
+    tx: &Transaction,
+    client_id: &str,
+    locked_months: Option<&[LockedMonth]>,
+) -> Result<(), rusqlite::Error> {
+    let locked_months = locked_months.unwrap_or_default();
+
+    let mut stmt = tx.prepare("SELECT year, month FROM locked_months WHERE client_id = ?")?;
+    let saved = stmt
+        .query_map(params![client_id], |row| {
+            Ok(LockedMonth::new(row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<LockedMonth>, _>>()?;
+
+    for locked_month in saved.iter().filter(|saved| !locked_months.contains(saved)) {
+        tx.execute(
+            "DELETE FROM locked_months WHERE client_id = ?1 AND year = ?2 AND month = ?3",
+            params![client_id, locked_month.year, locked_month.month],
+        )?;
+    }
+
+    let mut stmt = tx.prepare(
+        "INSERT OR IGNORE INTO locked_months (client_id, year, month) VALUES (?1, ?2, ?3)",
+    )?;
+    for locked_month in locked_months {
+        stmt.execute(params![client_id, locked_month.year, locked_month.month])?;
+    }
+
+    Ok(())
+}
+
+fn read_locked_months(
+    conn: &Connection,
+    client_id: &str,
+) -> Result<Option<Vec<LockedMonth>>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT year, month FROM locked_months WHERE client_id = ?1 ORDER BY year, month",
+    )?;
+    let locked_months = stmt
+        .query_map(params![client_id], |row| {
+            Ok(LockedMonth::new(row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<LockedMonth>, _>>()?;
+
+    Ok(if locked_months.is_empty() {
+        None
+    } else {
+        Some(locked_months)
+    })
+}
+
 /// Load configuration document from database
 fn load_config_doc(conn: &Connection) -> Result<ConfigurationDoc, Box<dyn std::error::Error>> {
     let mut result = Vec::new();
@@ -855,7 +1587,10 @@ fn load_config_doc(conn: &Connection) -> Result<ConfigurationDoc, Box<dyn std::e
             "SELECT id, namespace, namespace_alias, repo_path, git_path,
       user_id, name, email, client_id, client_name,
       client_contact_person, client_address, project_number,
-      service, service_username, default_hours
+      service, service_username, default_hours, hours_estimation,
+      commit_lead_in_minutes, commit_session_gap_minutes, hours_split,
+      hours_rounding, timezone, day_start_hour, author_identities, use_mailmap,
+      included_refs, excluded_refs, vcs
       FROM repositories
       WHERE client_id = ?1",
         )?;
@@ -883,6 +1618,27 @@ fn load_config_doc(conn: &Connection) -> Result<ConfigurationDoc, Box<dyn std::e
                 git_log_dates: None,
                 timesheet: None,
                 default_hours: row.get(15)?,
+                hours_estimation: row.get(16)?,
+                commit_lead_in_minutes: row.get(17)?,
+                commit_session_gap_minutes: row.get(18)?,
+                hours_split: row.get(19)?,
+                hours_rounding: row.get(20)?,
+                timezone: row.get(21)?,
+                day_start_hour: row.get(22)?,
+                author_identities: row
+                    .get::<_, Option<String>>(23)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                use_mailmap: row.get(24)?,
+                included_refs: row
+                    .get::<_, Option<String>>(25)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                excluded_refs: row
+                    .get::<_, Option<String>>(26)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                vcs: row.get(27)?,
+                git_log_times: None,
+                git_log_lines_changed: None,
+                git_log_subjects: None,
             };
 
             Ok((repo_id, repository))
@@ -1038,6 +1794,8 @@ fn load_config_doc(conn: &Connection) -> Result<ConfigurationDoc, Box<dyn std::e
             },
             requires_approval,
             approver,
+            manual_entries: read_manual_entries(conn, &id)?,
+            locked_months: read_locked_months(conn, &id)?,
         };
 
         result.push(client_repository);
@@ -1057,6 +1815,7 @@ pub fn get_canonical_path(path: &str) -> String {
 #[cfg(test)]
 pub mod test_utils {
     use super::*;
+    use crate::data::author_identity::AuthorIdentity;
     use crate::data::client_repositories::{Approver, Client, ClientRepositories, User};
     use crate::data::repository::Repository;
     use rusqlite::Connection;
@@ -1069,12 +1828,17 @@ pub mod test_utils {
         init_schema(&conn).unwrap();
 
         // Clear all tables
+        conn.execute("DELETE FROM manual_entries", []).unwrap();
+        conn.execute("DELETE FROM locked_months", []).unwrap();
         conn.execute("DELETE FROM approvers", []).unwrap();
         conn.execute("DELETE FROM client_repositories", []).unwrap();
         conn.execute("DELETE FROM timesheet_entries", []).unwrap();
+        conn.execute("DELETE FROM timesheet_history", []).unwrap();
         conn.execute("DELETE FROM git_log_days", []).unwrap();
         conn.execute("DELETE FROM git_log_months", []).unwrap();
         conn.execute("DELETE FROM git_log_years", []).unwrap();
+        conn.execute("DELETE FROM git_commits", []).unwrap();
+        conn.execute("DELETE FROM git_scans", []).unwrap();
         conn.execute("DELETE FROM repositories", []).unwrap();
         conn.execute("DELETE FROM users", []).unwrap();
         conn.execute("DELETE FROM clients", []).unwrap();
@@ -1124,6 +1888,25 @@ pub mod test_utils {
             git_log_dates: Some(create_test_git_log_dates()),
             timesheet: Some(create_test_timesheet()),
             default_hours: Some(8.0),
+            hours_estimation: Some("commits".to_string()),
+            commit_lead_in_minutes: Some(45),
+            commit_session_gap_minutes: None,
+            hours_split: Some("lines".to_string()),
+            hours_rounding: Some("nearest_0.25".to_string()),
+            timezone: Some("Europe/Amsterdam".to_string()),
+            day_start_hour: Some(3),
+            author_identities: Some(vec![AuthorIdentity {
+                name: None,
+                email: Some("test@work.com".to_string()),
+                pattern: None,
+            }]),
+            use_mailmap: Some(true),
+            included_refs: Some(vec!["main".to_string(), "feature/*".to_string()]),
+            excluded_refs: None,
+            vcs: Some("git".to_string()),
+            git_log_times: None,
+            git_log_lines_changed: None,
+            git_log_subjects: None,
         };
 
         ClientRepositories {
@@ -1132,6 +1915,14 @@ pub mod test_utils {
             repositories: Some(vec![repository]),
             requires_approval: Some(true),
             approver: Some(approver),
+            manual_entries: Some(vec![ManualEntry {
+                id: format!("entry-{}", client_name),
+                date: chrono::NaiveDate::from_ymd_opt(2023, 5, 2).unwrap(),
+                hours: 1.5,
+                project: None,
+                note: Some("Planning call".to_string()),
+            }]),
+            locked_months: None,
         }
     }
 
@@ -1211,6 +2002,7 @@ pub mod test_utils {
 mod tests {
     use super::test_utils::*;
     use super::*;
+    use crate::data::manual_entry::MANUAL_ENTRIES_NAMESPACE;
     use rusqlite::Connection;
     use std::env;
     use tempfile::tempdir;
@@ -1229,7 +2021,7 @@ mod tests {
         let config_doc = vec![client_repo];
 
         // Save the client
-        save_client_repository(&tx, &config_doc[0]).unwrap();
+        save_client_repository(&tx, &config_doc[0], &ChangedDays::default()).unwrap();
         tx.commit().unwrap();
 
         // Verify data was saved
@@ -1278,7 +2070,7 @@ mod tests {
         // Save both clients
         let tx = conn.transaction().unwrap();
         for client_repo in &config_doc {
-            save_client_repository(&tx, client_repo).unwrap();
+            save_client_repository(&tx, client_repo, &ChangedDays::default()).unwrap();
         }
         tx.commit().unwrap();
 
@@ -1332,7 +2124,7 @@ mod tests {
         {
             let tx = conn.transaction().unwrap();
             for client_repo in &config_doc {
-                save_client_repository(&tx, client_repo).unwrap();
+                save_client_repository(&tx, client_repo, &ChangedDays::default()).unwrap();
             }
             tx.commit().unwrap();
         }
@@ -1350,7 +2142,7 @@ mod tests {
             remove_deleted_client_repositories(&tx, &updated_config_doc).unwrap();
 
             for client_repo in updated_config_doc.iter() {
-                save_client_repository(&tx, client_repo).unwrap();
+                save_client_repository(&tx, client_repo, &ChangedDays::default()).unwrap();
             }
             tx.commit().unwrap();
         }
@@ -1366,6 +2158,326 @@ mod tests {
         assert_eq!(client_id, "client1");
     }
 
+    #[test]
+    fn test_hours_settings_round_trip() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let tx = conn.transaction().unwrap();
+        save_client_repository(
+            &tx,
+            &create_test_client("client1", "repo1"),
+            &ChangedDays::default(),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let config_doc = load_config_doc(&conn).unwrap();
+        let repository = &config_doc[0].repositories.as_ref().unwrap()[0];
+        assert_eq!(repository.hours_estimation, Some("commits".to_string()));
+        assert_eq!(repository.commit_lead_in_minutes, Some(45));
+        assert_eq!(repository.commit_session_gap_minutes, None);
+        assert_eq!(repository.hours_split, Some("lines".to_string()));
+        assert_eq!(repository.hours_rounding, Some("nearest_0.25".to_string()));
+        assert_eq!(repository.timezone, Some("Europe/Amsterdam".to_string()));
+        assert_eq!(repository.day_start_hour, Some(3));
+        assert_eq!(
+            repository.author_identities.as_ref().unwrap()[0].to_string(),
+            "test@work.com"
+        );
+        assert_eq!(repository.use_mailmap, Some(true));
+        assert_eq!(
+            repository.included_refs,
+            Some(vec!["main".to_string(), "feature/*".to_string()])
+        );
+        assert_eq!(repository.excluded_refs, None);
+        assert_eq!(repository.vcs, Some("git".to_string()));
+    }
+
+    #[test]
+    fn test_manual_entries_round_trip() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut client = create_test_client("client1", "repo1");
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        let config_doc = load_config_doc(&conn).unwrap();
+        assert_eq!(config_doc[0].manual_entries, client.manual_entries);
+
+        client.manual_entries = None;
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(count_entities(&conn, "manual_entries"), 0);
+        let config_doc = load_config_doc(&conn).unwrap();
+        assert_eq!(config_doc[0].manual_entries, None);
+    }
+
+    #[test]
+    fn test_timesheet_history_records_manual_entry_changes() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut client = create_test_client("client1", "repo1");
+        let date = NaiveDate::from_ymd_opt(2023, 5, 2).unwrap();
+        client
+            .manual_entries
+            .as_mut()
+            .unwrap()
+            .push(ManualEntry::new(date, "2", None, Some("Design review")).unwrap());
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        client.manual_entries.as_mut().unwrap().remove(0);
+        // saving the same entries again doesn't add to the history
+        for _ in 0..2 {
+            let tx = conn.transaction().unwrap();
+            save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let history = read_timesheet_history(&conn, "client1", 2023, 5).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].namespace, MANUAL_ENTRIES_NAMESPACE);
+        assert_eq!(history[0].date, date);
+        assert_eq!(history[0].old_hours, None);
+        assert_eq!(history[0].new_hours, 3.5);
+        assert_eq!(history[0].source, timesheet_history::SOURCE_MANUAL);
+        assert_eq!(history[1].old_hours, Some(3.5));
+        assert_eq!(history[1].new_hours, 2.0);
+    }
+
+    #[test]
+    fn test_locked_months_round_trip() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut client = create_test_client("client1", "repo1");
+        client.lock_month(2026, 9);
+        client.lock_month(2026, 8);
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        let config_doc = load_config_doc(&conn).unwrap();
+        assert_eq!(
+            config_doc[0].locked_months,
+            Some(vec![LockedMonth::new(2026, 8), LockedMonth::new(2026, 9)])
+        );
+
+        client.unlock_month(2026, 8);
+        client.unlock_month(2026, 9);
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(count_entities(&conn, "locked_months"), 0);
+        let config_doc = load_config_doc(&conn).unwrap();
+        assert_eq!(config_doc[0].locked_months, None);
+    }
+
+    #[test]
+    fn test_timesheet_history_records_hour_changes() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut client = create_test_client("client1", "repo1");
+        let mut changed_days = ChangedDays::new(timesheet_history::SOURCE_EDIT);
+        changed_days.add("repo-repo1", NaiveDate::from_ymd_opt(2021, 11, 1).unwrap());
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &changed_days).unwrap();
+        tx.commit().unwrap();
+
+        let history = read_timesheet_history(&conn, "client1", 2021, 11).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].namespace, "repo1");
+        assert_eq!(
+            history[0].date,
+            NaiveDate::from_ymd_opt(2021, 11, 1).unwrap()
+        );
+        assert_eq!(history[0].old_hours, None);
+        assert_eq!(history[0].new_hours, 8.0);
+        assert_eq!(history[0].source, timesheet_history::SOURCE_EDIT);
+
+        let repository = &mut client.repositories.as_mut().unwrap()[0];
+        repository
+            .mutate_timesheet_entry(
+                &"2021".to_string(),
+                &11,
+                1,
+                [
+                    ("hours".to_string(), serde_json::json!(6.5)),
+                    ("user_edited".to_string(), serde_json::json!(false)),
+                ],
+            )
+            .unwrap();
+        // saving the same hours again doesn't add to the history
+        for _ in 0..2 {
+            let tx = conn.transaction().unwrap();
+            save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let history = read_timesheet_history(&conn, "client1", 2021, 11).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].old_hours, Some(8.0));
+        assert_eq!(history[1].new_hours, 6.5);
+        assert_eq!(history[1].source, timesheet_history::SOURCE_GIT);
+        assert!(
+            read_timesheet_history(&conn, "client2", 2021, 11)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_timesheet_history_is_kept_for_removed_repositories() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut client = create_test_client("client1", "repo1");
+        client.repositories.as_mut().unwrap()[0].namespace_alias = Some("Website".to_string());
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        client.repositories = None;
+        let tx = conn.transaction().unwrap();
+        save_client_repository(&tx, &client, &ChangedDays::default()).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(count_entities(&conn, "repositories"), 0);
+        let history = read_timesheet_history(&conn, "client1", 2021, 11).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].namespace, "Website");
+        assert_eq!(history[0].new_hours, 8.0);
+    }
+
+    #[test]
+    fn test_timesheet_history_is_copied_from_older_databases() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let tx = conn.transaction().unwrap();
+        save_client_repository(
+            &tx,
+            &create_test_client("client1", "repo1"),
+            &ChangedDays::default(),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        conn.execute_batch(
+            "DROP TABLE timesheet_history;
+            CREATE TABLE timesheet_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repository_id TEXT NOT NULL,
+            year TEXT NOT NULL,
+            month TEXT NOT NULL,
+            day INTEGER NOT NULL,
+            old_hours REAL,
+            new_hours REAL NOT NULL,
+            source TEXT NOT NULL,
+            changed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO timesheet_history (repository_id, year, month, day, new_hours, source)
+            VALUES ('repo-repo1', '2021', '11', 1, 8.0, 'edit');",
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+
+        let history = read_timesheet_history(&conn, "client1", 2021, 11).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].namespace, "repo1");
+        assert_eq!(history[0].source, timesheet_history::SOURCE_EDIT);
+        assert!(
+            table_columns(&conn, "timesheet_history_old")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_git_history_round_trip() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(read_git_history(&conn, "repo1").unwrap(), None);
+
+        let date = chrono::DateTime::parse_from_rfc3339("2024-01-15T10:30:00+01:00").unwrap();
+        let commit = GitCommit {
+            hash: "abc".to_string(),
+            author_name: "Jim Jones".to_string(),
+            author_email: "jim@jones.com".to_string(),
+            author_date: date,
+            committer_date: date,
+            subject: "Add timesheets".to_string(),
+            lines_changed: Some(12),
+        };
+        let history = GitHistory {
+            scan_key: "author=*;mailmap=false;shortstat=true".to_string(),
+            scanned_commits: vec!["abc".to_string()],
+            commits: vec![commit.clone()],
+        };
+
+        let tx = conn.transaction().unwrap();
+        write_git_history(&tx, "repo1", &history, &history.commits).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(read_git_history(&conn, "repo1").unwrap(), Some(history));
+
+        // new commits are added to the ones already stored
+        let new_commit = GitCommit {
+            hash: "def".to_string(),
+            lines_changed: None,
+            ..commit
+        };
+        let history = GitHistory {
+            scan_key: "author=*;mailmap=false;shortstat=true".to_string(),
+            scanned_commits: vec!["def".to_string()],
+            commits: vec![],
+        };
+        let tx = conn.transaction().unwrap();
+        write_git_history(&tx, "repo1", &history, &[new_commit]).unwrap();
+        tx.commit().unwrap();
+
+        let stored = read_git_history(&conn, "repo1").unwrap().unwrap();
+        assert_eq!(stored.scanned_commits, vec!["def".to_string()]);
+        assert_eq!(stored.commits.len(), 2);
+
+        let tx = conn.transaction().unwrap();
+        delete_git_history(&tx, "repo1").unwrap();
+        tx.commit().unwrap();
+        assert_eq!(read_git_history(&conn, "repo1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_init_schema_adds_missing_repository_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE repositories (
+            id TEXT PRIMARY KEY,
+            default_hours FLOAT NOT NULL DEFAULT 8.0
+            )",
+            [],
+        )
+        .unwrap();
+
+        init_schema(&conn).unwrap();
+        // running it again leaves the columns in place
+        init_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO repositories (id, hours_estimation, commit_lead_in_minutes)
+            VALUES ('repo1', 'commits', 30)",
+            [],
+        )
+        .unwrap();
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_version_cache_functions() {
@@ -1430,4 +2542,55 @@ mod tests {
         assert!(!versions.contains(&"1.1.0".to_string()));
         assert!(!versions.contains(&"1.2.0".to_string()));
     }
+
+    #[test]
+    fn test_resolve_db_path_precedence() {
+        let data_dir = Some(PathBuf::from("/data/autolog"));
+        let flag = PathBuf::from("/flag/autolog.db");
+
+        assert_eq!(
+            resolve_db_path(
+                Some(&flag),
+                Some("/env/autolog.db".to_string()),
+                data_dir.clone()
+            )
+            .unwrap(),
+            flag
+        );
+        assert_eq!(
+            resolve_db_path(None, Some("/env/autolog.db".to_string()), data_dir.clone()).unwrap(),
+            PathBuf::from("/env/autolog.db")
+        );
+        assert_eq!(
+            resolve_db_path(None, Some(String::new()), data_dir).unwrap(),
+            PathBuf::from("/data/autolog/autolog.db")
+        );
+        assert!(resolve_db_path(None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_migrate_db_from_homebrew_location() {
+        let temp_dir = tempdir().unwrap();
+        let homebrew_db = temp_dir
+            .path()
+            .join("etc")
+            .join("autolog")
+            .join(DB_FILE_NAME);
+        fs::create_dir_all(homebrew_db.parent().unwrap()).unwrap();
+        fs::write(&homebrew_db, "sqlite").unwrap();
+
+        let missing = temp_dir.path().join("missing.db");
+        let target = temp_dir.path().join("data").join(DB_FILE_NAME);
+        let candidates = vec![missing, homebrew_db.clone()];
+
+        assert_eq!(
+            migrate_db(&target, &candidates).unwrap(),
+            Some(homebrew_db.clone())
+        );
+        assert_eq!(fs::read_to_string(&target).unwrap(), "sqlite");
+        assert!(homebrew_db.exists());
+
+        // Nothing is copied once the database exists in the new location
+        assert_eq!(migrate_db(&target, &candidates).unwrap(), None);
+    }
 }
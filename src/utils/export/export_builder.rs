@@ -0,0 +1,399 @@
+use crate::data::client_repositories::{Approver, Client, ClientRepositories, User};
+use crate::utils::date::date_parser::{check_for_valid_month, check_for_valid_year};
+use crate::utils::link::link_builder::{
+    Timesheet, generate_timesheet_vec, get_day_description, get_string_month_year,
+};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Box<dyn Error>;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            _ => Err(format!("'{}' is not a supported export format", value).into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ExportDocument<'a> {
+    month_year: &'a str,
+    client: &'a Option<Client>,
+    user: &'a Option<User>,
+    approver: &'a Option<Approver>,
+    requires_approval: Option<bool>,
+    timesheets: &'a [Timesheet],
+    total_hours: f64,
+}
+
+fn calculate_grand_total(timesheets: &[Timesheet]) -> f64 {
+    timesheets
+        .iter()
+        .map(|timesheet| timesheet.total_hours)
+        .sum()
+}
+
+fn get_hours(day: &serde_json::Map<String, serde_json::Value>) -> f64 {
+    day.get("hours")
+        .and_then(|hours| hours.as_f64())
+        .unwrap_or(0.0)
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_markdown_cell(field: &str) -> String {
+    field.replace('|', "\\|").replace('\n', " ")
+}
+
+fn render_csv(year: i32, month: u32, timesheets: &[Timesheet]) -> String {
+    let mut output =
+        String::from("date,namespace,project_number,hours,weekend,user_edited,description\n");
+
+    for timesheet in timesheets {
+        for (index, day) in timesheet.timesheet.iter().enumerate() {
+            let date = NaiveDate::from_ymd_opt(year, month, index as u32 + 1)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+
+            output.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                date,
+                escape_csv_field(&timesheet.namespace),
+                escape_csv_field(timesheet.project_number.as_deref().unwrap_or("")),
+                get_hours(day),
+                day.get("weekend")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                day.get("user_edited")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                escape_csv_field(get_day_description(day).unwrap_or("")),
+            ));
+        }
+    }
+
+    output
+}
+
+fn render_json(
+    month_year_string: &str,
+    timesheets: &[Timesheet],
+    client_repositories: &ClientRepositories,
+) -> Result<String, Box<dyn Error>> {
+    let document = ExportDocument {
+        month_year: month_year_string,
+        client: &client_repositories.client,
+        user: &client_repositories.user,
+        approver: &client_repositories.approver,
+        requires_approval: client_repositories.requires_approval,
+        timesheets,
+        total_hours: calculate_grand_total(timesheets),
+    };
+
+    Ok(serde_json::to_string_pretty(&document)? + "\n")
+}
+
+fn render_markdown(
+    month_year_string: &str,
+    year: i32,
+    month: u32,
+    timesheets: &[Timesheet],
+    client_repositories: &ClientRepositories,
+) -> String {
+    let mut output = format!("# Timesheet: {}\n\n", month_year_string);
+
+    if let Some(client) = client_repositories.client.as_ref() {
+        output.push_str(&format!("**Client:** {}  \n", client.client_name));
+        if !client.client_contact_person.is_empty() {
+            output.push_str(&format!(
+                "**Contact:** {}  \n",
+                client.client_contact_person
+            ));
+        }
+    }
+    if let Some(user) = client_repositories.user.as_ref() {
+        output.push_str(&format!("**Name:** {} <{}>  \n", user.name, user.email));
+    }
+    let (approvers_name, approvers_email) = client_repositories.get_approver();
+    if !approvers_name.is_empty() {
+        output.push_str(&format!(
+            "**Approver:** {} <{}>  \n",
+            approvers_name, approvers_email
+        ));
+    }
+
+    let headers: Vec<String> = timesheets
+        .iter()
+        .map(|timesheet| match timesheet.project_number.as_ref() {
+            Some(project_number) => format!("{} ({})", timesheet.namespace, project_number),
+            None => timesheet.namespace.clone(),
+        })
+        .collect();
+
+    output.push_str(&format!(
+        "\n| Date | {} | Total | Description |\n",
+        headers.join(" | ")
+    ));
+    output.push_str(&format!(
+        "|---|{}---:|---|\n",
+        "---:|".repeat(headers.len())
+    ));
+
+    let days_in_month = timesheets
+        .iter()
+        .map(|timesheet| timesheet.timesheet.len())
+        .max()
+        .unwrap_or(0);
+
+    for index in 0..days_in_month {
+        let hours: Vec<f64> = timesheets
+            .iter()
+            .map(|timesheet| timesheet.timesheet.get(index).map_or(0.0, get_hours))
+            .collect();
+        let day_total: f64 = hours.iter().sum();
+
+        // only list the days that were worked to keep the table readable
+        if day_total == 0.0 {
+            continue;
+        }
+
+        let date = NaiveDate::from_ymd_opt(year, month, index as u32 + 1)
+            .map(|date| date.format("%a %d %b").to_string())
+            .unwrap_or_default();
+        let columns: Vec<String> = hours.iter().map(|hours| hours.to_string()).collect();
+
+        // name the repository alongside each description when there's more than one
+        let descriptions: Vec<String> = timesheets
+            .iter()
+            .filter_map(|timesheet| {
+                let description = get_day_description(timesheet.timesheet.get(index)?)?;
+                Some(match timesheets.len() {
+                    1 => description.to_string(),
+                    _ => format!("{}: {}", timesheet.namespace, description),
+                })
+            })
+            .collect();
+
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            date,
+            columns.join(" | "),
+            day_total,
+            escape_markdown_cell(&descriptions.join("; "))
+        ));
+    }
+
+    let totals: Vec<String> = timesheets
+        .iter()
+        .map(|timesheet| format!("**{}**", timesheet.total_hours))
+        .collect();
+    output.push_str(&format!(
+        "| **Total** | {} | **{}** | |\n",
+        totals.join(" | "),
+        calculate_grand_total(timesheets)
+    ));
+
+    output
+}
+
+pub fn render_export(
+    format: ExportFormat,
+    month_year_string: &str,
+    year: i32,
+    month: u32,
+    timesheets: &[Timesheet],
+    client_repositories: &ClientRepositories,
+) -> Result<String, Box<dyn Error>> {
+    match format {
+        ExportFormat::Csv => Ok(render_csv(year, month, timesheets)),
+        ExportFormat::Json => render_json(month_year_string, timesheets, client_repositories),
+        ExportFormat::Markdown => Ok(render_markdown(
+            month_year_string,
+            year,
+            month,
+            timesheets,
+            client_repositories,
+        )),
+    }
+}
+
+/// Write the timesheet for the month in options to stdout, or to the output path when passed.
+/// It's built locally rather than sent to the API, so it can be generated without a network
+/// connection.
+pub fn build_export(
+    client_repositories: &mut ClientRepositories,
+    options: Vec<Option<String>>,
+) -> Result<(), Box<dyn Error>> {
+    let format: ExportFormat = options[3]
+        .as_ref()
+        .ok_or("Export format not found")?
+        .parse()?;
+    let output_path = options[4].clone();
+
+    let month = check_for_valid_month(&options[1])?;
+    let year: i32 = check_for_valid_year(&options[2])?.parse()?;
+    let month_year_string = get_string_month_year(&options[1], &options[2])?;
+    let timesheets = generate_timesheet_vec(client_repositories, options, &month_year_string)?;
+
+    let output = render_export(
+        format,
+        &month_year_string,
+        year,
+        month,
+        &timesheets,
+        client_repositories,
+    )?;
+
+    match output_path {
+        Some(path) => {
+            fs::write(&path, output)?;
+            crate::interface::help_prompt::HelpPrompt::show_export_success(
+                &month_year_string,
+                &path,
+            );
+        }
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+    use serde_json::Value;
+
+    fn get_mock_timesheets() -> Vec<Timesheet> {
+        let options = vec![
+            Option::None,
+            Option::from("10".to_owned()),
+            Option::from("2021".to_owned()),
+        ];
+
+        let mut client_repository = ClientRepositories {
+            repositories: Option::from(vec![mocks::create_mock_repository()]),
+            ..Default::default()
+        };
+
+        generate_timesheet_vec(
+            &mut client_repository,
+            options,
+            &"October, 2021".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_parses_export_formats() {
+        assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert_eq!("JSON".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert_eq!(
+            "markdown".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn it_escapes_csv_fields() {
+        assert_eq!(escape_csv_field("autolog"), "autolog");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn it_renders_csv() {
+        let csv = render_csv(2021, 10, &get_mock_timesheets());
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "date,namespace,project_number,hours,weekend,user_edited,description"
+        );
+        // one line for each day in October, plus the header
+        assert_eq!(lines.len(), 32);
+        assert_eq!(lines[20], "2021-10-20,autolog,,8,false,false,");
+        assert_eq!(lines[23], "2021-10-23,autolog,,8,true,false,");
+    }
+
+    #[test]
+    fn it_renders_day_descriptions() {
+        let mut timesheets = get_mock_timesheets();
+        timesheets[0].timesheet[19].insert(
+            "description".to_string(),
+            Value::String("Add invoices; Fix totals, again".to_string()),
+        );
+
+        let csv = render_csv(2021, 10, &timesheets);
+        assert_eq!(
+            csv.lines().nth(20).unwrap(),
+            "2021-10-20,autolog,,8,false,false,\"Add invoices; Fix totals, again\""
+        );
+
+        let markdown = render_markdown(
+            "October, 2021",
+            2021,
+            10,
+            &timesheets,
+            &ClientRepositories::default(),
+        );
+        assert!(markdown.contains("| Wed 20 Oct | 8 | 8 | Add invoices; Fix totals, again |"));
+    }
+
+    #[test]
+    fn it_renders_json() {
+        let client_repositories = ClientRepositories::default();
+        let json = render_json(
+            "October, 2021",
+            &get_mock_timesheets(),
+            &client_repositories,
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["month_year"], "October, 2021");
+        assert_eq!(value["total_hours"], 24.0);
+        assert_eq!(value["timesheets"][0]["namespace"], "autolog");
+    }
+
+    #[test]
+    fn it_renders_markdown() {
+        let mut client_repositories = ClientRepositories::default();
+        mocks::create_mock_client_repository(&mut client_repositories);
+
+        let markdown = render_markdown(
+            "October, 2021",
+            2021,
+            10,
+            &get_mock_timesheets(),
+            &client_repositories,
+        );
+
+        assert!(markdown.starts_with("# Timesheet: October, 2021\n"));
+        assert!(markdown.contains("**Client:** alphabet"));
+        assert!(markdown.contains("| Date | autolog | Total | Description |"));
+        assert!(markdown.contains("| Wed 20 Oct | 8 | 8 |  |"));
+        assert!(markdown.contains("| **Total** | **24** | **24** | |"));
+    }
+}
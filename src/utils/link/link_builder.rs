@@ -1,8 +1,11 @@
 use crate::data::client_repositories::{Approver, Client, ClientRepositories, User};
 use crate::data::repository::Repository;
-use crate::utils::date::date_parser::{check_for_valid_month, check_for_valid_year};
-use chrono::{DateTime, Month, Utc};
-use dotenv;
+use crate::utils::date::date_parser::{
+    HoursRounding, check_for_valid_month, check_for_valid_year, create_single_day_object,
+    get_days_from_month, is_weekend,
+};
+use crate::utils::settings::settings_file::{self, ShareConfig};
+use chrono::{DateTime, Datelike, Month, Utc};
 use mongodb::bson::doc;
 use num_traits::cast::FromPrimitive;
 use rand::distr::Alphanumeric;
@@ -10,33 +13,32 @@ use rand::{Rng, rng};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::error::Error;
-use std::process;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-struct Timesheet {
-    namespace: String,
-    timesheet: TimesheetHoursForMonth,
-    total_hours: f64,
-    project_number: Option<String>,
+pub struct Timesheet {
+    pub namespace: String,
+    pub timesheet: TimesheetHoursForMonth,
+    pub total_hours: f64,
+    pub project_number: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct TimesheetDocument {
+pub struct TimesheetDocument {
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
-    creation_date: DateTime<Utc>,
-    random_path: String,
-    month_year: String,
-    client: Option<Client>,
-    user: Option<User>,
-    approver: Option<Approver>,
-    timesheets: Vec<Timesheet>,
-    approved: bool,
-    requires_approval: Option<bool>,
+    pub creation_date: DateTime<Utc>,
+    pub random_path: String,
+    pub month_year: String,
+    pub client: Option<Client>,
+    pub user: Option<User>,
+    pub approver: Option<Approver>,
+    pub timesheets: Vec<Timesheet>,
+    pub approved: bool,
+    pub requires_approval: Option<bool>,
 }
 
 pub type TimesheetHoursForMonth = Vec<Map<String, Value>>;
 
-fn get_string_month_year(
+pub(crate) fn get_string_month_year(
     month: &Option<String>,
     year: &Option<String>,
 ) -> Result<String, Box<dyn Error>> {
@@ -55,11 +57,11 @@ fn find_month_from_timesheet<'a>(
     options: &'a [Option<String>],
 ) -> Result<Option<&'a TimesheetHoursForMonth>, Box<dyn Error>> {
     // safe to unwrap options here as it would have been caught above
+    // projects without git may not have a timesheet of their own
     let option = sheet
         .timesheet
         .as_ref()
-        .unwrap()
-        .get(&options[2].as_ref().unwrap().to_string())
+        .and_then(|timesheet| timesheet.get(&options[2].as_ref().unwrap().to_string()))
         .and_then(|year| {
             year.get(&options[1].as_ref().unwrap().to_string())
                 .and_then(Option::from)
@@ -67,7 +69,7 @@ fn find_month_from_timesheet<'a>(
     Ok(option)
 }
 
-fn build_document<'a>(
+pub(crate) fn build_document<'a>(
     creation_date: DateTime<Utc>,
     random_path: &'a str,
     month_year_string: &'a str,
@@ -90,17 +92,136 @@ fn build_document<'a>(
     }
 }
 
-fn calculate_total_hours(timesheet_month: &TimesheetHoursForMonth) -> f64 {
-    let hours: Vec<f64> = timesheet_month
+/// The description of the work done on a day, when there is one
+pub(crate) fn get_day_description(day: &Map<String, Value>) -> Option<&str> {
+    day.get("description")
+        .and_then(|description| description.as_str())
+        .map(|description| description.trim())
+        .filter(|description| !description.is_empty())
+}
+
+/// The rounding policy chosen for a repository's hours. Without one the hours are left as
+/// they are, since the hours worked out from git are already rounded to whole hours
+fn get_total_rounding(hours_rounding: Option<&str>) -> HoursRounding {
+    hours_rounding.map_or(HoursRounding::None, |setting| {
+        HoursRounding::from_setting(Some(setting))
+    })
+}
+
+/// Round each day in the same way as the hours worked out from git, so hours that were edited
+/// or logged by hand are shown and billed in the same increments
+fn round_timesheet_hours(
+    timesheet_month: &TimesheetHoursForMonth,
+    rounding: &HoursRounding,
+) -> TimesheetHoursForMonth {
+    timesheet_month
         .iter()
-        .map(|x| x.get("hours").unwrap().as_f64().unwrap())
+        .map(|day| {
+            let mut day = day.clone();
+            if let Some(hours) = day.get("hours").and_then(Value::as_f64) {
+                day.insert("hours".to_string(), Value::from(rounding.round(hours)));
+            }
+            day
+        })
+        .collect()
+}
+
+pub(crate) fn calculate_total_hours(timesheet_month: &TimesheetHoursForMonth) -> f64 {
+    // the days are summed in hundredths so fractional days add up exactly
+    let hundredths: Vec<i64> = timesheet_month
+        .iter()
+        .map(|x| (x.get("hours").unwrap().as_f64().unwrap() * 100.0).round() as i64)
         .collect();
 
-    let total_hours: f64 = hours.iter().copied().sum();
+    let total_hours: f64 = hundredths.iter().sum::<i64>() as f64 / 100.0;
     total_hours
 }
 
-fn generate_timesheet_vec(
+/// Lines for the hours logged by hand in a month, one for each project, in the same shape as
+/// the months generated from git history
+fn generate_manual_timesheets(
+    client_repositories: &ClientRepositories,
+    year: i32,
+    month: u32,
+) -> Vec<Timesheet> {
+    let entries = client_repositories.get_manual_entries_for_month(year, month);
+    let repos = client_repositories
+        .repositories
+        .as_deref()
+        .unwrap_or_default();
+    let find_repo = |namespace: &str| {
+        repos.iter().find(|repo| {
+            repo.namespace
+                .as_ref()
+                .is_some_and(|x| x.to_lowercase() == namespace.to_lowercase())
+        })
+    };
+
+    let mut namespaces: Vec<&str> = entries.iter().map(|entry| entry.get_namespace()).collect();
+    namespaces.sort();
+    namespaces.dedup();
+
+    let days_in_month = get_days_from_month(year, month);
+
+    namespaces
+        .into_iter()
+        .map(|namespace| {
+            let timesheet: TimesheetHoursForMonth = (1..=days_in_month)
+                .map(|day| {
+                    let day_entries: Vec<_> = entries
+                        .iter()
+                        .filter(|entry| {
+                            entry.get_namespace() == namespace && entry.date.day() == day
+                        })
+                        .collect();
+                    let hours = (day_entries.iter().map(|entry| entry.hours).sum::<f64>() * 100.0)
+                        .round()
+                        / 100.0;
+
+                    let mut day_map: Map<String, Value> = create_single_day_object(
+                        is_weekend(&(year, month, days_in_month), day),
+                        hours,
+                        !day_entries.is_empty(),
+                    )
+                    .into_iter()
+                    .collect();
+
+                    let notes: Vec<&str> = day_entries
+                        .iter()
+                        .filter_map(|entry| entry.note.as_deref())
+                        .collect();
+                    if !notes.is_empty() {
+                        day_map.insert("description".to_string(), Value::String(notes.join("; ")));
+                    }
+
+                    day_map
+                })
+                .collect();
+
+            // keep the line apart from the repository's own line when it shares its name,
+            // while projects without git only have this line
+            let repo = find_repo(namespace);
+            let timesheet = round_timesheet_hours(
+                &timesheet,
+                &get_total_rounding(
+                    repo.and_then(|repo| repo.hours_rounding.as_deref())
+                        .or(repos.iter().find_map(|repo| repo.hours_rounding.as_deref())),
+                ),
+            );
+            Timesheet {
+                namespace: match repo {
+                    Some(repo) if repo.is_git_repository() => format!("{} (manual)", namespace),
+                    _ => namespace.to_string(),
+                },
+                total_hours: calculate_total_hours(&timesheet),
+                timesheet,
+                project_number: repo.and_then(|repo| repo.project_number.clone()),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn generate_timesheet_vec(
     client_repositories: &mut ClientRepositories,
     options: Vec<Option<String>>,
     month_year_string: &String,
@@ -121,15 +242,23 @@ fn generate_timesheet_vec(
             });
 
         if let Some(timesheet) = timesheet_hours_for_month {
+            let timesheet = round_timesheet_hours(
+                timesheet,
+                &get_total_rounding(repo.hours_rounding.as_deref()),
+            );
             timesheets.push(Timesheet {
                 namespace: namespace.as_ref().map(|x| x.to_owned()).unwrap(),
-                timesheet: timesheet.to_owned(),
-                total_hours: calculate_total_hours(timesheet),
+                total_hours: calculate_total_hours(&timesheet),
+                timesheet,
                 project_number: project_number.to_owned(),
             });
         }
     }
 
+    let month = check_for_valid_month(&options[1])?;
+    let year: i32 = check_for_valid_year(&options[2])?.parse()?;
+    timesheets.extend(generate_manual_timesheets(client_repositories, year, month));
+
     // prevent this from building a document if there aren't timesheets for the month
     if timesheets.is_empty() {
         eprintln!(
@@ -155,11 +284,12 @@ fn generate_random_path(length: usize) -> Result<String, Box<dyn Error>> {
     Ok(random_string)
 }
 
+/// Share the month's timesheet, returning the link it can be seen at
 pub async fn build_unique_uri(
     client_repositories: &mut ClientRepositories,
     options: Vec<Option<String>>,
-) -> Result<(), Box<dyn Error>> {
-    dotenv::dotenv().ok();
+) -> Result<String, Box<dyn Error>> {
+    crate::utils::load_dotenv();
     let month_year_string = get_string_month_year(&options[1], &options[2])?;
     let timesheets = generate_timesheet_vec(client_repositories, options, &month_year_string)?;
 
@@ -167,15 +297,8 @@ pub async fn build_unique_uri(
         &month_year_string,
     );
 
-    let expire_time_seconds: i32 = env!("EXPIRE_TIME_SECONDS")
-        .parse()
-        .expect("Expire time can't be parsed to i32");
-
-    // API connection details
-    let api_endpoint = env!("API_ENDPOINT");
-    let api_key = env!("API_ROUTE_BEARER_KEY");
-    let autolog_uri = env!("AUTOLOG_URI");
-    let api_route = format!("{}/{}", autolog_uri, api_endpoint);
+    let settings = settings_file::load_settings()?;
+    let share_config = ShareConfig::resolve(&settings)?;
 
     let random_path = generate_random_path(16)?;
 
@@ -194,13 +317,14 @@ pub async fn build_unique_uri(
     let client = reqwest::Client::new();
 
     // Send the document to the API
-    let response = client
-        .get(api_route)
-        .header("Authorization", format!("Bearer {}", api_key))
+    let mut request = client
+        .get(share_config.api_route())
         .header("Content-Type", "application/json")
-        .json(&document)
-        .send()
-        .await?;
+        .json(&document);
+    if let Some(api_key) = share_config.api_key.as_ref() {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let response = request.send().await?;
 
     // Check if the request was successful
     if !response.status().is_success() {
@@ -210,28 +334,30 @@ pub async fn build_unique_uri(
         return Err(format!("API request failed: {} - {}", status, error_text).into());
     }
 
-    // Format the URL exactly as in your original code
-    let timesheet_gen_uri: String = format!("{}/{}", env!("AUTOLOG_URI"), &random_path);
+    let timesheet_gen_uri: String = share_config.timesheet_uri(&random_path);
 
     // Use your existing function to display the URL
     crate::interface::help_prompt::HelpPrompt::show_new_link_success(
-        expire_time_seconds / 60,
+        (share_config.expire_time_seconds / 60) as i32,
         &timesheet_gen_uri,
     );
 
-    process::exit(exitcode::OK);
+    Ok(timesheet_gen_uri)
 }
 
 #[cfg(test)]
 mod test {
     use crate::data::client_repositories::{Approver, Client, ClientRepositories, User};
+    use crate::data::manual_entry::ManualEntry;
     use crate::data::repository::Repository;
     use crate::helpers::mocks;
+    use crate::utils::date::date_parser::{HoursRounding, create_single_day_object};
     use crate::utils::link::link_builder::{
-        Timesheet, TimesheetDocument, build_document, calculate_total_hours,
-        find_month_from_timesheet, generate_timesheet_vec, get_string_month_year,
+        Timesheet, TimesheetDocument, TimesheetHoursForMonth, build_document,
+        calculate_total_hours, find_month_from_timesheet, generate_timesheet_vec,
+        get_string_month_year, get_total_rounding, round_timesheet_hours,
     };
-    use chrono::{TimeZone, Utc};
+    use chrono::{NaiveDate, TimeZone, Utc};
     use expect_test::expect_file;
     use nanoid::nanoid;
     use serde_json::json;
@@ -261,6 +387,69 @@ mod test {
         expected.assert_debug_eq(&timesheets.get(0));
     }
 
+    #[test]
+    fn it_generates_lines_for_manual_entries() {
+        let options = vec![
+            Option::None,
+            Option::from("10".to_owned()),
+            Option::from("2021".to_owned()),
+        ];
+
+        let mut repository = mocks::create_mock_repository();
+        repository.git_path = Option::from("/autolog/.git/".to_string());
+        let project = Repository {
+            namespace: Option::from("figma".to_string()),
+            project_number: Option::from("PO-2".to_string()),
+            ..Default::default()
+        };
+
+        let mut client_repository = ClientRepositories {
+            repositories: Option::from(vec![repository, project]),
+            ..Default::default()
+        };
+
+        let date = |day| NaiveDate::from_ymd_opt(2021, 10, day).unwrap();
+        client_repository
+            .add_manual_entry(
+                ManualEntry::new(date(4), "1", Some("autolog"), Some("Standup")).unwrap(),
+            )
+            .add_manual_entry(
+                ManualEntry::new(date(4), "0.5", Some("autolog"), Some("Review")).unwrap(),
+            )
+            .add_manual_entry(ManualEntry::new(date(12), "2", None, None).unwrap())
+            .add_manual_entry(ManualEntry::new(date(13), "4", Some("figma"), None).unwrap());
+
+        let timesheets = generate_timesheet_vec(
+            &mut client_repository,
+            options,
+            &"October, 2021".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(timesheets.len(), 4);
+
+        assert_eq!(timesheets[1].namespace, "Other work");
+        assert_eq!(timesheets[1].total_hours, 2.0);
+        assert_eq!(timesheets[1].timesheet.len(), 31);
+        assert_eq!(timesheets[1].timesheet[11]["hours"], json!(2.0));
+        assert_eq!(timesheets[1].timesheet[11].get("description"), None);
+
+        assert_eq!(timesheets[2].namespace, "autolog (manual)");
+        assert_eq!(timesheets[2].total_hours, 1.5);
+        assert_eq!(timesheets[2].timesheet[3]["user_edited"], json!(true));
+        assert_eq!(
+            timesheets[2].timesheet[3]["description"],
+            json!("Standup; Review")
+        );
+        assert_eq!(timesheets[2].timesheet[4]["hours"], json!(0.0));
+        assert_eq!(timesheets[2].timesheet[4]["user_edited"], json!(false));
+
+        // projects without git only have the line for their manual entries
+        assert_eq!(timesheets[3].namespace, "figma");
+        assert_eq!(timesheets[3].total_hours, 4.0);
+        assert_eq!(timesheets[3].project_number, Some("PO-2".to_string()));
+    }
+
     #[test]
     fn it_builds_document() {
         let timesheet_for_month = mocks::create_mock_timesheet_hours_for_month();
@@ -330,6 +519,89 @@ mod test {
         assert_eq!(calculate_total_hours(&month), 24.0);
     }
 
+    #[test]
+    fn it_calculates_fractional_total_hours() {
+        let month: TimesheetHoursForMonth = [0.1, 0.2, 7.62, 0.38]
+            .iter()
+            .map(|hours| {
+                let mut day = serde_json::Map::new();
+                day.insert("hours".to_string(), json!(hours));
+                day
+            })
+            .collect();
+        assert_eq!(calculate_total_hours(&month), 8.3);
+    }
+
+    #[test]
+    fn it_rounds_each_day_of_a_timesheet() {
+        let month: TimesheetHoursForMonth = [2.1, 2.1, 2.1, 2.1]
+            .iter()
+            .map(|hours| {
+                let mut day = serde_json::Map::new();
+                day.insert("hours".to_string(), json!(hours));
+                day
+            })
+            .collect();
+        let hours = |month: &TimesheetHoursForMonth| -> Vec<f64> {
+            month
+                .iter()
+                .map(|day| day["hours"].as_f64().unwrap())
+                .collect()
+        };
+
+        // the total is the sum of the days shown, where rounding the total of 8.4 would give 8.5
+        let rounded = round_timesheet_hours(&month, &HoursRounding::Nearest(0.5));
+        assert_eq!(hours(&rounded), [2.0, 2.0, 2.0, 2.0]);
+        assert_eq!(calculate_total_hours(&rounded), 8.0);
+
+        let rounded = round_timesheet_hours(&month, &HoursRounding::Up(0.25));
+        assert_eq!(hours(&rounded), [2.25, 2.25, 2.25, 2.25]);
+        assert_eq!(calculate_total_hours(&rounded), 9.0);
+
+        let rounded = round_timesheet_hours(&month, &get_total_rounding(Option::None));
+        assert_eq!(calculate_total_hours(&rounded), 8.4);
+    }
+
+    #[test]
+    fn it_rounds_edited_days_on_generated_timesheets() {
+        let options = vec![
+            Option::None,
+            Option::from("10".to_owned()),
+            Option::from("2021".to_owned()),
+        ];
+
+        let mut repository = mocks::create_mock_repository();
+        repository.set_hours_rounding("up_0.25".to_string());
+        repository
+            .mutate_timesheet_entry(
+                &"2021".to_string(),
+                &10,
+                1,
+                create_single_day_object(false, 7.3, true),
+            )
+            .unwrap();
+        let mut client_repository = ClientRepositories {
+            repositories: Option::from(vec![repository]),
+            ..Default::default()
+        };
+
+        let timesheets = generate_timesheet_vec(
+            &mut client_repository,
+            options,
+            &"October, 2021".to_string(),
+        )
+        .unwrap();
+
+        // the day is shown as it's counted in the total
+        assert_eq!(timesheets[0].timesheet[0]["hours"], json!(7.5));
+        let days: f64 = timesheets[0]
+            .timesheet
+            .iter()
+            .map(|day| day["hours"].as_f64().unwrap())
+            .sum();
+        assert_eq!(timesheets[0].total_hours, days);
+    }
+
     #[test]
     fn it_throws_error_getting_string_month_year_with_incorrect_month() {
         let options = vec![
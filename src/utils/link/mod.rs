@@ -1 +1 @@
-pub(crate) mod link_builder;
+pub mod link_builder;
@@ -1,25 +1,50 @@
 pub mod date;
 pub mod db;
+pub mod export;
 pub mod file;
 pub mod link;
+pub mod pdf;
+pub mod settings;
 
 use dialoguer::Confirm;
 use dotenv::dotenv;
 use std::env;
 use std::error::Error;
 use std::process::Output;
+use std::sync::OnceLock;
+
+/// Set by the global --yes/--non-interactive flag
+static NON_INTERACTIVE: OnceLock<bool> = OnceLock::new();
+
+pub fn set_non_interactive(value: bool) {
+    let _ = NON_INTERACTIVE.set(value);
+}
+
+/// Without a terminal to prompt on, confirmations are answered yes and every other
+/// value has to be passed as a flag
+pub fn is_non_interactive() -> bool {
+    NON_INTERACTIVE.get().copied().unwrap_or(false)
+}
 
 pub fn confirm() -> Result<bool, Box<dyn Error>> {
-    if is_test_mode() {
+    if is_test_mode() || is_non_interactive() {
         return Ok(true);
     }
 
     Ok(Confirm::new().default(true).interact()?)
 }
 
+/// Load environment variables from a .env file in the current directory. Only development
+/// builds do this, as a .env left in a repository could otherwise change where timesheets
+/// are shared and the key they're sent with
+pub fn load_dotenv() {
+    if cfg!(debug_assertions) {
+        let _ = dotenv();
+    }
+}
+
 pub fn is_test_mode() -> bool {
-    // Load environment variables from .env file if available
-    let _ = dotenv();
+    load_dotenv();
 
     // Check for TEST_MODE environment variable first
     match env::var("TEST_MODE") {
@@ -0,0 +1 @@
+pub mod pdf_builder;
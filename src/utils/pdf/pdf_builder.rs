@@ -0,0 +1,355 @@
+use crate::data::client_repositories::ClientRepositories;
+use crate::utils::date::date_parser::{check_for_valid_month, check_for_valid_year};
+use crate::utils::link::link_builder::{
+    TimesheetDocument, build_document, generate_timesheet_vec, get_day_description,
+    get_string_month_year,
+};
+use chrono::{NaiveDate, Utc};
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Point,
+};
+use std::error::Error;
+use std::fs;
+
+// Renders a TimesheetDocument to a pdf on the local machine, so a timesheet can be
+// shared without any of its data being uploaded.
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+/// Roughly how many characters of a description fit beside the hours column
+const DESCRIPTION_MAX_CHARS: usize = 40;
+
+struct PdfWriter {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    cursor: f32,
+    pages: usize,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self, Box<dyn Error>> {
+        let (document, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let regular = document.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = document.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = document.get_page(page).get_layer(layer);
+
+        Ok(PdfWriter {
+            document,
+            layer,
+            regular,
+            bold,
+            cursor: PAGE_HEIGHT - MARGIN,
+            pages: 1,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .document
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.cursor = PAGE_HEIGHT - MARGIN;
+        self.pages += 1;
+    }
+
+    /// Move the cursor down by the line height, starting a new page if it runs out of space
+    fn advance(&mut self, line_height: f32) {
+        if self.cursor - line_height < MARGIN {
+            self.new_page();
+        }
+        self.cursor -= line_height;
+    }
+
+    fn text_at(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(text, size, Mm(MARGIN + x), Mm(self.cursor), font);
+    }
+
+    fn line(&mut self, text: &str, size: f32, bold: bool) {
+        self.advance(size * 0.5);
+        self.text_at(text, size, 0.0, bold);
+    }
+
+    fn row(&mut self, columns: &[(f32, &str)], bold: bool) {
+        self.advance(6.0);
+        for (x, text) in columns {
+            self.text_at(text, 10.0, *x, bold);
+        }
+    }
+
+    fn rule(&mut self) {
+        self.advance(3.0);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.cursor)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.cursor)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn space(&mut self, height: f32) {
+        self.advance(height);
+    }
+
+    fn save_to_bytes(self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.document.save_to_bytes()?)
+    }
+}
+
+fn format_hours(hours: f64) -> String {
+    format!("{}", hours)
+}
+
+fn truncate_description(description: &str) -> String {
+    match description.chars().count() > DESCRIPTION_MAX_CHARS {
+        true => {
+            let truncated: String = description
+                .chars()
+                .take(DESCRIPTION_MAX_CHARS - 3)
+                .collect();
+            format!("{}...", truncated.trim_end())
+        }
+        false => description.to_string(),
+    }
+}
+
+fn write_details(writer: &mut PdfWriter, document: &TimesheetDocument) {
+    if let Some(client) = document.client.as_ref() {
+        writer.line(&format!("Client: {}", client.client_name), 11.0, false);
+        if !client.client_contact_person.is_empty() {
+            writer.line(
+                &format!("Contact: {}", client.client_contact_person),
+                11.0,
+                false,
+            );
+        }
+        for (index, address_line) in client
+            .client_address
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let label = if index == 0 {
+                "Address: "
+            } else {
+                "              "
+            };
+            writer.line(&format!("{}{}", label, address_line.trim()), 11.0, false);
+        }
+    }
+
+    if let Some(user) = document.user.as_ref() {
+        writer.line(&format!("Name: {}", user.name), 11.0, false);
+        writer.line(&format!("Email: {}", user.email), 11.0, false);
+    }
+
+    if document.requires_approval.unwrap_or(false)
+        && let Some(approver) = document.approver.as_ref()
+    {
+        writer.line(
+            &format!(
+                "Approver: {} {}",
+                approver.approvers_name.as_deref().unwrap_or(""),
+                approver
+                    .approvers_email
+                    .as_deref()
+                    .map(|email| format!("<{}>", email))
+                    .unwrap_or_default()
+            ),
+            11.0,
+            false,
+        );
+    }
+}
+
+fn write_timesheets(writer: &mut PdfWriter, document: &TimesheetDocument, year: i32, month: u32) {
+    for timesheet in document.timesheets.iter() {
+        writer.space(6.0);
+        writer.line(&timesheet.namespace, 13.0, true);
+        if let Some(project_number) = timesheet.project_number.as_ref() {
+            writer.line(&format!("Project number: {}", project_number), 10.0, false);
+        }
+
+        writer.row(
+            &[
+                (0.0, "Date"),
+                (40.0, "Day"),
+                (80.0, "Hours"),
+                (100.0, "Description"),
+            ],
+            true,
+        );
+        writer.rule();
+
+        for (index, day) in timesheet.timesheet.iter().enumerate() {
+            let hours = day.get("hours").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            if hours == 0.0 {
+                continue;
+            }
+
+            let date = NaiveDate::from_ymd_opt(year, month, index as u32 + 1);
+            let date_string = date
+                .map(|date| date.format("%d/%m/%Y").to_string())
+                .unwrap_or_default();
+            let weekday = date
+                .map(|date| date.format("%A").to_string())
+                .unwrap_or_default();
+
+            let description = truncate_description(get_day_description(day).unwrap_or(""));
+
+            writer.row(
+                &[
+                    (0.0, &date_string),
+                    (40.0, &weekday),
+                    (80.0, &format_hours(hours)),
+                    (100.0, &description),
+                ],
+                false,
+            );
+        }
+
+        writer.rule();
+        writer.row(
+            &[
+                (0.0, "Total hours"),
+                (80.0, &format_hours(timesheet.total_hours)),
+            ],
+            true,
+        );
+    }
+
+    let total_hours: f64 = document
+        .timesheets
+        .iter()
+        .map(|timesheet| timesheet.total_hours)
+        .sum();
+
+    writer.space(8.0);
+    writer.line(
+        &format!(
+            "Total hours for {}: {}",
+            document.month_year,
+            format_hours(total_hours)
+        ),
+        12.0,
+        true,
+    );
+}
+
+fn render_pdf(
+    document: &TimesheetDocument,
+    year: i32,
+    month: u32,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let title = format!("Timesheet - {}", document.month_year);
+    let mut writer = PdfWriter::new(&title)?;
+
+    writer.line("Timesheet", 20.0, true);
+    writer.line(&document.month_year, 14.0, false);
+    writer.space(4.0);
+
+    write_details(&mut writer, document);
+    write_timesheets(&mut writer, document, year, month);
+
+    writer.space(8.0);
+    writer.line(
+        &format!(
+            "Generated by autolog on {}",
+            document.creation_date.format("%d/%m/%Y")
+        ),
+        8.0,
+        false,
+    );
+
+    writer.save_to_bytes()
+}
+
+/// Build the timesheet document for the month in options and write it to the passed pdf path
+pub fn build_pdf(
+    client_repositories: &mut ClientRepositories,
+    options: Vec<Option<String>>,
+) -> Result<(), Box<dyn Error>> {
+    let path = options[5].clone().ok_or("Pdf output path not found")?;
+
+    let month = check_for_valid_month(&options[1])?;
+    let year: i32 = check_for_valid_year(&options[2])?.parse()?;
+    let month_year_string = get_string_month_year(&options[1], &options[2])?;
+    let timesheets = generate_timesheet_vec(client_repositories, options, &month_year_string)?;
+
+    crate::interface::help_prompt::HelpPrompt::show_generating_timesheet_message(
+        &month_year_string,
+    );
+
+    // The document is never uploaded, so it doesn't need a random path
+    let document = build_document(
+        Utc::now(),
+        "",
+        &month_year_string,
+        &timesheets,
+        client_repositories,
+    );
+
+    fs::write(&path, render_pdf(&document, year, month)?)?;
+
+    crate::interface::help_prompt::HelpPrompt::show_export_success(&month_year_string, &path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mocks;
+    use crate::utils::link::link_builder::Timesheet;
+
+    fn get_mock_document(repeat: usize) -> TimesheetDocument {
+        let mut client_repositories = ClientRepositories::default();
+        mocks::create_mock_client_repository(&mut client_repositories);
+
+        let timesheets: Vec<Timesheet> = (0..repeat)
+            .map(|index| Timesheet {
+                namespace: format!("project_{}", index),
+                timesheet: mocks::create_mock_timesheet_hours_for_month(),
+                total_hours: 24.0,
+                project_number: Some("PO-1".to_string()),
+            })
+            .collect();
+
+        build_document(
+            Utc::now(),
+            "",
+            "October, 2021",
+            &timesheets,
+            &client_repositories,
+        )
+    }
+
+    #[test]
+    fn it_renders_a_pdf() {
+        let bytes = render_pdf(&get_mock_document(1), 2021, 10).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn it_adds_pages_when_the_content_overflows() {
+        let mut writer = PdfWriter::new("test").unwrap();
+        for _ in 0..100 {
+            writer.line("row", 10.0, false);
+        }
+        assert!(writer.pages > 1);
+        assert!(writer.cursor >= MARGIN);
+    }
+
+    #[test]
+    fn it_renders_a_pdf_with_many_timesheets() {
+        let bytes = render_pdf(&get_mock_document(20), 2021, 10).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+}
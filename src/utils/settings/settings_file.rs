@@ -0,0 +1,313 @@
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Settings that can change without rebuilding autolog. They're stored as json in the
+// platform config directory and edited with `autolog config set <key> <value>`.
+// Share values resolve from the environment first, then the settings file, then any
+// value baked in when the binary was built, and finally the defaults below.
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const DEFAULT_SHARE_ENDPOINT: &str = "https://autolog.dev";
+const DEFAULT_SHARE_API_ENDPOINT: &str = "api/timesheet";
+const DEFAULT_EXPIRE_TIME_SECONDS: i64 = 86400;
+
+pub const SETTING_KEYS: [&str; 4] = [
+    "share.endpoint",
+    "share.api_endpoint",
+    "share.api_key",
+    "share.expire_time_seconds",
+];
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShareSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_time_seconds: Option<i64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Settings {
+    #[serde(default)]
+    pub share: ShareSettings,
+}
+
+fn unknown_key(key: &str) -> Box<dyn Error> {
+    format!(
+        "'{}' is not a setting. Expected one of: {}",
+        key,
+        SETTING_KEYS.join(", ")
+    )
+    .into()
+}
+
+fn parse_endpoint(value: &str) -> Result<String, Box<dyn Error>> {
+    let value = value.trim().trim_end_matches('/');
+    if !value.starts_with("http://") && !value.starts_with("https://") {
+        return Err(format!("'{}' should start with http:// or https://", value).into());
+    }
+    Ok(value.to_string())
+}
+
+fn parse_expire_time_seconds(value: &str) -> Result<i64, Box<dyn Error>> {
+    match value.trim().parse::<i64>() {
+        Ok(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(format!("'{}' should be a positive number of seconds", value).into()),
+    }
+}
+
+impl Settings {
+    /// Load settings from the path, falling back to the defaults when the file doesn't exist
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        if contents.trim().is_empty() {
+            return Ok(Settings::default());
+        }
+
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, Box<dyn Error>> {
+        match key {
+            "share.endpoint" => Ok(self.share.endpoint.clone()),
+            "share.api_endpoint" => Ok(self.share.api_endpoint.clone()),
+            "share.api_key" => Ok(self.share.api_key.clone()),
+            "share.expire_time_seconds" => {
+                Ok(self.share.expire_time_seconds.map(|x| x.to_string()))
+            }
+            _ => Err(unknown_key(key)),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+        match key {
+            "share.endpoint" => self.share.endpoint = Some(parse_endpoint(value)?),
+            "share.api_endpoint" => {
+                self.share.api_endpoint = Some(value.trim().trim_matches('/').to_string())
+            }
+            "share.api_key" => self.share.api_key = Some(value.trim().to_string()),
+            "share.expire_time_seconds" => {
+                self.share.expire_time_seconds = Some(parse_expire_time_seconds(value)?)
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<(), Box<dyn Error>> {
+        match key {
+            "share.endpoint" => self.share.endpoint = None,
+            "share.api_endpoint" => self.share.api_endpoint = None,
+            "share.api_key" => self.share.api_key = None,
+            "share.expire_time_seconds" => self.share.expire_time_seconds = None,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+/// Get the platform-specific path for the settings file
+pub fn get_settings_path() -> PathBuf {
+    match ProjectDirs::from("dev", "autolog", "autolog") {
+        Some(project_dirs) => project_dirs.config_dir().join(SETTINGS_FILE_NAME),
+        None => PathBuf::from(SETTINGS_FILE_NAME),
+    }
+}
+
+pub fn load_settings() -> Result<Settings, Box<dyn Error>> {
+    Settings::load_from(&get_settings_path())
+}
+
+/// The resolved connection details for sharing a timesheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareConfig {
+    pub endpoint: String,
+    pub api_endpoint: String,
+    pub api_key: Option<String>,
+    pub expire_time_seconds: i64,
+}
+
+impl ShareConfig {
+    pub fn resolve(settings: &Settings) -> Result<Self, Box<dyn Error>> {
+        crate::utils::load_dotenv();
+        Self::resolve_with(settings, |name| env::var(name).ok())
+    }
+
+    fn resolve_with<F>(settings: &Settings, get_env: F) -> Result<Self, Box<dyn Error>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let endpoint = match get_env("AUTOLOG_URI") {
+            Some(endpoint) => parse_endpoint(&endpoint)?,
+            None => settings
+                .share
+                .endpoint
+                .clone()
+                .or(option_env!("AUTOLOG_URI").map(String::from))
+                .unwrap_or_else(|| DEFAULT_SHARE_ENDPOINT.to_string()),
+        };
+
+        let api_endpoint = get_env("API_ENDPOINT")
+            .or(settings.share.api_endpoint.clone())
+            .or(option_env!("API_ENDPOINT").map(String::from))
+            .unwrap_or_else(|| DEFAULT_SHARE_API_ENDPOINT.to_string());
+
+        let api_key = get_env("API_ROUTE_BEARER_KEY")
+            .or(settings.share.api_key.clone())
+            .or(option_env!("API_ROUTE_BEARER_KEY").map(String::from))
+            .filter(|key| !key.is_empty());
+
+        let expire_time_seconds = match get_env("EXPIRE_TIME_SECONDS") {
+            Some(seconds) => parse_expire_time_seconds(&seconds)?,
+            None => match settings.share.expire_time_seconds {
+                Some(seconds) => seconds,
+                None => match option_env!("EXPIRE_TIME_SECONDS") {
+                    Some(seconds) => parse_expire_time_seconds(seconds)?,
+                    None => DEFAULT_EXPIRE_TIME_SECONDS,
+                },
+            },
+        };
+
+        Ok(ShareConfig {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_endpoint: api_endpoint.trim_matches('/').to_string(),
+            api_key,
+            expire_time_seconds,
+        })
+    }
+
+    pub fn api_route(&self) -> String {
+        format!("{}/{}", self.endpoint, self.api_endpoint)
+    }
+
+    pub fn timesheet_uri(&self, random_path: &str) -> String {
+        format!("{}/{}", self.endpoint, random_path)
+    }
+
+    /// The value in use for a setting key, with the api key masked
+    pub fn get(&self, key: &str) -> Result<String, Box<dyn Error>> {
+        match key {
+            "share.endpoint" => Ok(self.endpoint.clone()),
+            "share.api_endpoint" => Ok(self.api_endpoint.clone()),
+            "share.api_key" => Ok(match self.api_key.as_ref() {
+                Some(_) => "********".to_string(),
+                None => "(not set)".to_string(),
+            }),
+            "share.expire_time_seconds" => Ok(self.expire_time_seconds.to_string()),
+            _ => Err(unknown_key(key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve_with_env(settings: &Settings, vars: &[(&str, &str)]) -> ShareConfig {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        ShareConfig::resolve_with(settings, |name| vars.get(name).cloned()).unwrap()
+    }
+
+    #[test]
+    fn it_sets_and_unsets_settings() {
+        let mut settings = Settings::default();
+        settings
+            .set("share.endpoint", "https://timesheets.example.com/")
+            .unwrap();
+        settings.set("share.expire_time_seconds", "3600").unwrap();
+
+        assert_eq!(
+            settings.get("share.endpoint").unwrap(),
+            Some("https://timesheets.example.com".to_string())
+        );
+        assert_eq!(settings.share.expire_time_seconds, Some(3600));
+
+        settings.unset("share.endpoint").unwrap();
+        assert_eq!(settings.get("share.endpoint").unwrap(), None);
+    }
+
+    #[test]
+    fn it_rejects_invalid_settings() {
+        let mut settings = Settings::default();
+        assert!(settings.set("share.endpoint", "example.com").is_err());
+        assert!(settings.set("share.expire_time_seconds", "-1").is_err());
+        assert!(settings.set("share.unknown", "value").is_err());
+    }
+
+    #[test]
+    fn it_saves_and_loads_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(SETTINGS_FILE_NAME);
+
+        assert_eq!(Settings::load_from(&path).unwrap(), Settings::default());
+
+        let mut settings = Settings::default();
+        settings
+            .set("share.endpoint", "http://localhost:8080")
+            .unwrap();
+        settings.save_to(&path).unwrap();
+
+        assert_eq!(Settings::load_from(&path).unwrap(), settings);
+    }
+
+    #[test]
+    fn it_resolves_share_config_from_settings() {
+        let mut settings = Settings::default();
+        settings
+            .set("share.endpoint", "http://localhost:8080")
+            .unwrap();
+        settings
+            .set("share.api_endpoint", "/api/timesheet/")
+            .unwrap();
+        settings.set("share.api_key", "secret").unwrap();
+
+        let config = resolve_with_env(&settings, &[]);
+        assert_eq!(config.api_route(), "http://localhost:8080/api/timesheet");
+        assert_eq!(config.timesheet_uri("abc"), "http://localhost:8080/abc");
+        assert_eq!(config.api_key, Some("secret".to_string()));
+        assert_eq!(config.get("share.api_key").unwrap(), "********");
+    }
+
+    #[test]
+    fn it_prefers_the_environment_over_settings() {
+        let mut settings = Settings::default();
+        settings
+            .set("share.endpoint", "http://localhost:8080")
+            .unwrap();
+        settings.set("share.expire_time_seconds", "60").unwrap();
+
+        let config = resolve_with_env(
+            &settings,
+            &[
+                ("AUTOLOG_URI", "https://share.example.com/"),
+                ("EXPIRE_TIME_SECONDS", "120"),
+            ],
+        );
+        assert_eq!(config.endpoint, "https://share.example.com");
+        assert_eq!(config.expire_time_seconds, 120);
+    }
+}
@@ -115,3 +115,141 @@ fn runs_remove_and_prompts_to_remove_namespace() -> Result<(), Box<dyn std::erro
 
     Ok(())
 }
+
+#[test]
+fn runs_make_and_exports_only_the_timesheet_to_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let repo = dir.path().join("exported");
+    let db = dir.path().join("autolog.db");
+    std::fs::create_dir(&repo)?;
+
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo)
+            .env("GIT_AUTHOR_DATE", "2026-09-15T10:00:00+00:00")
+            .env("GIT_COMMITTER_DATE", "2026-09-15T10:00:00+00:00")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "--quiet"]);
+    git(&["config", "user.name", "Jim Jones"]);
+    git(&["config", "user.email", "jim@jones.com"]);
+    git(&["commit", "--quiet", "--allow-empty", "-m", "First commit"]);
+
+    let autolog = || {
+        let mut cmd = Command::cargo_bin("autolog").unwrap();
+        cmd.env_remove("TEST_MODE")
+            .env("AUTOLOG_DB", &db)
+            .current_dir(&repo);
+        cmd
+    };
+    autolog()
+        .args(["--yes", "init", "--client=Acme", "--contact=Sally Smith"])
+        .assert()
+        .success();
+
+    let output = autolog()
+        .args(["make", "-c", "Acme", "-m9", "-y2026", "--format=csv"])
+        .output()?;
+    assert!(output.status.success());
+
+    // the month is locked after the export, but the notice mustn't end up in the csv
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines = stdout.lines();
+    assert_eq!(
+        lines.next(),
+        Some("date,namespace,project_number,hours,weekend,user_edited,description")
+    );
+    assert!(lines.all(|line| line.starts_with("2026-09-")));
+    assert!(stdout.contains("2026-09-15,exported,,8,false,false,First commit"));
+    assert!(String::from_utf8(output.stderr)?.contains("September, 2026 is now locked"));
+
+    Ok(())
+}
+
+#[test]
+fn runs_make_and_locks_the_shared_month() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let repo = dir.path().join("shared");
+    let db = dir.path().join("autolog.db");
+    std::fs::create_dir(&repo)?;
+
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo)
+            .env("GIT_AUTHOR_DATE", "2026-09-15T10:00:00+00:00")
+            .env("GIT_COMMITTER_DATE", "2026-09-15T10:00:00+00:00")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "--quiet"]);
+    git(&["config", "user.name", "Jim Jones"]);
+    git(&["config", "user.email", "jim@jones.com"]);
+    git(&["commit", "--quiet", "--allow-empty", "-m", "First commit"]);
+
+    // share to the bundled server, stopping it however the test ends
+    struct ServerProcess(std::process::Child);
+    impl Drop for ServerProcess {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+    let address = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .to_string();
+    let _server = ServerProcess(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("autolog-server"))
+            .env("API_ROUTE_BEARER_KEY", "secret")
+            .env("API_ENDPOINT", "api/timesheet")
+            .arg(format!("--address={}", address))
+            .arg(format!("--db={}", dir.path().join("server.db").display()))
+            .spawn()?,
+    );
+    for _ in 0..50 {
+        if reqwest::blocking::get(format!("http://{}/health", address)).is_ok() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let autolog = || {
+        let mut cmd = Command::cargo_bin("autolog").unwrap();
+        cmd.env_remove("TEST_MODE")
+            .env("AUTOLOG_DB", &db)
+            .env("AUTOLOG_URI", format!("http://{}", address))
+            .env("API_ENDPOINT", "api/timesheet")
+            .env("API_ROUTE_BEARER_KEY", "secret")
+            .current_dir(&repo);
+        cmd
+    };
+    autolog()
+        .args(["--yes", "init", "--client=Acme", "--contact=Sally Smith"])
+        .assert()
+        .success();
+
+    let output = autolog()
+        .args(["--yes", "make", "-c", "Acme", "-m9", "-y2026"])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let uri = stdout
+        .split_whitespace()
+        .find(|word| word.starts_with(&format!("http://{}/", address)))
+        .expect("the shared link is shown");
+    assert_eq!(reqwest::blocking::get(uri)?.status(), 200);
+    assert!(String::from_utf8(output.stderr)?.contains("September, 2026 is now locked"));
+
+    // the lock was saved, so sharing the month again doesn't lock it again
+    let output = autolog()
+        .args(["--yes", "make", "-c", "Acme", "-m9", "-y2026"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(!String::from_utf8(output.stderr)?.contains("is now locked"));
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use assert_cmd::cargo::cargo_bin;
+use autolog::data::client_repositories::{Approver, Client, User};
+use autolog::utils::link::link_builder::{Timesheet, TimesheetDocument};
+use chrono::Utc;
+use serde_json::{Map, Number, Value};
+use std::net::TcpListener;
+use std::process::{Child, Command};
+use std::thread;
+use std::time::Duration;
+
+struct ServerProcess(Child);
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn get_free_address() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+fn start_server(address: &str, db_path: &str) -> ServerProcess {
+    let child = Command::new(cargo_bin("autolog-server"))
+        .env("API_ROUTE_BEARER_KEY", "secret")
+        .env("API_ENDPOINT", "api/timesheet")
+        .arg(format!("--address={}", address))
+        .arg(format!("--db={}", db_path))
+        .spawn()
+        .unwrap();
+    let server = ServerProcess(child);
+
+    for _ in 0..50 {
+        if reqwest::blocking::get(format!("http://{}/health", address)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    server
+}
+
+fn create_document(random_path: &str) -> TimesheetDocument {
+    let mut day = Map::new();
+    day.insert("hours".to_string(), Value::Number(Number::from(8)));
+    day.insert("weekend".to_string(), Value::Bool(false));
+    day.insert("user_edited".to_string(), Value::Bool(false));
+
+    TimesheetDocument {
+        creation_date: Utc::now(),
+        random_path: random_path.to_string(),
+        month_year: "October, 2021".to_string(),
+        client: Some(Client {
+            id: "1".to_string(),
+            client_name: "alphabet".to_string(),
+            client_address: "Spaghetti Way, USA".to_string(),
+            client_contact_person: "John Smith".to_string(),
+        }),
+        user: Some(User {
+            id: "2".to_string(),
+            name: "Jim Jones".to_string(),
+            email: "jim@jones.com".to_string(),
+            is_alias: false,
+            thumbnail: None,
+        }),
+        approver: Some(Approver {
+            approvers_name: Some("Bob Brown".to_string()),
+            approvers_email: Some("bob@brown.com".to_string()),
+        }),
+        timesheets: vec![Timesheet {
+            namespace: "autolog".to_string(),
+            timesheet: vec![day],
+            total_hours: 8.0,
+            project_number: None,
+        }],
+        approved: false,
+        requires_approval: Some(true),
+    }
+}
+
+#[test]
+fn shares_and_approves_a_timesheet_with_the_self_hosted_server() {
+    let address = get_free_address();
+    let db = tempfile::NamedTempFile::new().unwrap();
+    let _server = start_server(&address, db.path().to_str().unwrap());
+    let client = reqwest::blocking::Client::new();
+
+    // autolog sends the document as the json body of a GET request
+    let response = client
+        .get(format!("http://{}/api/timesheet", address))
+        .header("Authorization", "Bearer secret")
+        .json(&create_document("abc123"))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let page = client
+        .get(format!("http://{}/abc123", address))
+        .send()
+        .unwrap();
+    assert_eq!(page.status(), 200);
+    assert!(page.text().unwrap().contains("Approve timesheet"));
+
+    let approved = client
+        .post(format!("http://{}/abc123/approve", address))
+        .send()
+        .unwrap();
+    assert_eq!(approved.status(), 200);
+    assert!(
+        approved
+            .text()
+            .unwrap()
+            .contains("This timesheet has been approved.")
+    );
+}
+
+#[test]
+fn rejects_a_timesheet_without_the_bearer_key() {
+    let address = get_free_address();
+    let db = tempfile::NamedTempFile::new().unwrap();
+    let _server = start_server(&address, db.path().to_str().unwrap());
+
+    let response = reqwest::blocking::Client::new()
+        .get(format!("http://{}/api/timesheet", address))
+        .json(&create_document("abc123"))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 401);
+}